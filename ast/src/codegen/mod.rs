@@ -0,0 +1,767 @@
+//! Compile a [`Module`] to a JavaScript ES module.
+//!
+//! Functions are curried, so `fn a b => ...` becomes `(a) => (b) => ...` and is called as
+//! `f(a)(b)`. Enums are tagged arrays, matching their JSON encoding: `#just 1` becomes
+//! `["just", 1]`. The exceptions are `#true`, `#false` and `#undefined`, which become the
+//! JavaScript values of the same name.
+
+use std::{collections::HashSet, path::Path};
+
+use crate::{
+    decl::Decl,
+    expr::{
+        literal::StringParts, operator::is_operator_name, Expr, Literal, Operator, Pattern,
+        StringPart,
+    },
+    module::{
+        import::{Import, Source},
+        Meta, Module,
+    },
+};
+
+#[cfg(test)]
+mod tests;
+
+/// The name of the namespace that external declarations are imported from. It is lowercase so
+/// it can't clash with the namespace of an import.
+const FFI: &str = "$ffi";
+
+/// Structural equality, used for `==` and `!=`
+const EQ_HELPER: &str = r#"function $eq(a, b) {
+    if (a === b) return true;
+    if (typeof a !== "object" || typeof b !== "object" || a === null || b === null) return false;
+    if (Array.isArray(a)) return Array.isArray(b) && a.length === b.length && a.every((x, i) => $eq(x, b[i]));
+    const keys = Object.keys(a);
+    return keys.length === Object.keys(b).length && keys.every((k) => k in b && $eq(a[k], b[k]));
+}"#;
+
+const RESERVED: &[&str] = &[
+    "arguments",
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "eval",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "let",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "static",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "undefined",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+];
+
+/// The path of the file `ext` declarations are read from, relative to the module. It is named
+/// after the module's source file, e.g. `./List.ffi.js` for `src/List.ren`, or after the module
+/// if it has no path.
+pub fn ffi_path(meta: &Meta) -> String {
+    let stem = Path::new(meta.path())
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or(meta.name());
+    if stem.is_empty() {
+        "./ffi.js".to_string()
+    } else {
+        format!("./{}.ffi.js", stem)
+    }
+}
+
+/// Generate the source of an ES module from a Ren module.
+///
+/// Imports become namespace imports and exposed names are imported by name. `pub` declarations
+/// are exported, and `ext` declarations are read from the module's FFI file (see [`ffi_path`]).
+/// Type declarations have no runtime representation and are dropped.
+pub fn module_to_js(module: &Module) -> String {
+    let mut gen = Generator {
+        globals: module
+            .declarations()
+            .iter()
+            .map(|decl| ident(decl.name()))
+            .collect(),
+        ..Default::default()
+    };
+    let mut imports = module
+        .all_imports()
+        .iter()
+        .map(|import| gen.import(import, module.meta().pkg_path()))
+        .collect::<Vec<_>>();
+    if module.meta().uses_ffi() {
+        imports.push(format!(
+            "import * as {} from {};",
+            FFI,
+            quote(&ffi_path(module.meta()))
+        ));
+    }
+    let decls = module
+        .declarations()
+        .iter()
+        .filter_map(|decl| gen.decl(decl))
+        .collect::<Vec<_>>();
+
+    let mut sections = Vec::new();
+    if !imports.is_empty() {
+        sections.push(imports.join("\n"));
+    }
+    if gen.uses_eq {
+        sections.push(EQ_HELPER.to_string());
+    }
+    sections.extend(decls);
+    let mut js = sections.join("\n\n");
+    js.push('\n');
+    js
+}
+
+/// Generate a JavaScript expression from a Ren expression
+pub fn expr_to_js(expr: &Expr) -> String {
+    let mut gen = Generator::default();
+    let js = gen.expr(expr);
+    if gen.uses_eq {
+        format!("(() => {{\n{}\nreturn {};\n}})()", EQ_HELPER, js)
+    } else {
+        js
+    }
+}
+
+#[derive(Default)]
+struct Generator {
+    indent: usize,
+    /// Counter used to create unique temporary names
+    fresh: usize,
+    /// Whether the `$eq` helper is needed
+    uses_eq: bool,
+    /// Names declared at the top level of the module
+    globals: HashSet<String>,
+    /// The JavaScript names of the variables in scope, innermost last. Ren allows a `let` to
+    /// shadow a variable bound in the same block, so these may differ from the Ren name.
+    scope: Vec<(String, String)>,
+}
+
+impl Generator {
+    fn fresh(&mut self) -> String {
+        let name = format!("${}", self.fresh);
+        self.fresh += 1;
+        name
+    }
+    fn newline(&self, indent: usize) -> String {
+        format!("\n{}", "    ".repeat(indent))
+    }
+
+    // SCOPE ===================================================================
+    fn lookup(&self, name: &str) -> String {
+        self.scope
+            .iter()
+            .rev()
+            .find(|(n, _)| n == name)
+            .map_or_else(|| ident(name), |(_, js)| js.clone())
+    }
+    /// Bring a variable bound by a `const` into scope. Declaring a `const` with the same name
+    /// as a variable which is already visible would either be a redeclaration or shadow the
+    /// variable before it has been initialised, so the variable is renamed instead.
+    fn bind(&mut self, name: &str) -> String {
+        let js = ident(name);
+        let js = if self.globals.contains(&js) || self.scope.iter().any(|(n, _)| n == name) {
+            format!("{}{}", js, self.fresh())
+        } else {
+            js
+        };
+        self.scope.push((name.to_string(), js.clone()));
+        js
+    }
+
+    // MODULES =================================================================
+    fn import(&mut self, import: &Import, pkg_path: &str) -> String {
+        let specifier = match import.source {
+            Source::Project => with_extension(&import.path),
+            Source::Package => {
+                let pkg_path = if pkg_path.starts_with("./")
+                    || pkg_path.starts_with("../")
+                    || pkg_path.starts_with('/')
+                {
+                    pkg_path.to_string()
+                } else {
+                    format!("./{}", pkg_path)
+                };
+                with_extension(&format!("{}{}", pkg_path, import.path))
+            }
+            Source::External => import.path.clone(),
+        };
+        if import.alias.is_empty() && import.exposing.is_empty() {
+            return format!("import {};", quote(&specifier));
+        }
+        let mut statements = Vec::new();
+        if !import.alias.is_empty() {
+            let namespace = namespace(&import.alias);
+            self.globals.insert(namespace.clone());
+            statements.push(format!(
+                "import * as {} from {};",
+                namespace,
+                quote(&specifier)
+            ));
+        }
+        // A namespace import can't also name bindings, so exposed names get their own import
+        if !import.exposing.is_empty() {
+            let names = import
+                .exposing
+                .iter()
+                .map(|name| ident(name))
+                .collect::<Vec<_>>();
+            self.globals.extend(names.iter().cloned());
+            statements.push(format!(
+                "import {{ {} }} from {};",
+                names.join(", "),
+                quote(&specifier)
+            ));
+        }
+        statements.join("\n")
+    }
+    fn decl(&mut self, decl: &Decl) -> Option<String> {
+        let (exposed, var, value) = match decl {
+            Decl::Let {
+                exposed, var, expr, ..
+            } => (exposed, var, self.expr(expr)),
+            Decl::Infix {
+                exposed, op, expr, ..
+            } => (exposed, op, self.expr(expr)),
+            Decl::Ext {
+                exposed, var, name, ..
+            } => (exposed, var, member(FFI, name)),
+            Decl::Type { .. } | Decl::Expect { .. } => return None,
+        };
+        Some(format!(
+            "{}const {} = {};",
+            if *exposed { "export " } else { "" },
+            ident(var),
+            value
+        ))
+    }
+
+    // EXPRESSIONS =============================================================
+    fn expr(&mut self, expr: &Expr) -> String {
+        match expr {
+            Expr::Access(_, rec, key) => member(&self.operand(rec), key),
+            Expr::Annotated(_, expr, _) => self.expr(expr),
+            Expr::Binop(_, lhs, op, rhs) => self.binop(lhs, *op, rhs),
+            Expr::Call(_, fun, args) => {
+                let mut js = self.operand(fun);
+                for arg in args {
+                    js = format!("{}({})", js, self.expr(arg));
+                }
+                js
+            }
+            Expr::If(_, cond, then_, else_) => format!(
+                "{} ? {} : {}",
+                self.operand(cond),
+                self.operand(then_),
+                self.operand(else_)
+            ),
+            Expr::Lambda(_, params, body) => self.lambda(params, body),
+            Expr::Assert(..) | Expr::Let(..) | Expr::Switch(..) => {
+                format!("(() => {})()", self.block(Vec::new(), expr))
+            }
+            Expr::Literal(_, lit) => self.literal(lit),
+            // A lone placeholder has nothing to abstract over
+            Expr::Placeholder(_) => "undefined".to_string(),
+            Expr::Scoped(_, ns, var) => member(&namespace(ns), &ident(var)),
+            Expr::Update(_, rec, fields) => {
+                let mut js = format!("{{ ...{}", self.operand(rec));
+                for (_, key, value) in fields {
+                    js.push_str(&format!(", {}: {}", key, self.expr(value)));
+                }
+                js + " }"
+            }
+            Expr::Var(_, name) => self.lookup(name),
+        }
+    }
+    /// Generate an expression which may be used as an operand without being regrouped
+    fn operand(&mut self, expr: &Expr) -> String {
+        let js = self.expr(expr);
+        if is_atomic(expr) {
+            js
+        } else {
+            format!("({})", js)
+        }
+    }
+    fn binop(&mut self, lhs: &Expr, op: Operator, rhs: &Expr) -> String {
+        let symbol = match op {
+            Operator::Add => "+",
+            Operator::And => "&&",
+            Operator::Div => "/",
+            Operator::Gte => ">=",
+            Operator::Gt => ">",
+            Operator::Lte => "<=",
+            Operator::Lt => "<",
+            Operator::Mod => "%",
+            Operator::Mul => "*",
+            Operator::Or => "||",
+            Operator::Pow => "**",
+            Operator::Sub => "-",
+            Operator::Concat => {
+                return format!("{}.concat({})", self.operand(lhs), self.expr(rhs));
+            }
+            Operator::Cons => return format!("[{}, ...{}]", self.expr(lhs), self.operand(rhs)),
+            Operator::Eq | Operator::Neq => {
+                self.uses_eq = true;
+                return format!(
+                    "{}$eq({}, {})",
+                    if op == Operator::Neq { "!" } else { "" },
+                    self.expr(lhs),
+                    self.expr(rhs)
+                );
+            }
+            Operator::Pipe => return format!("{}({})", self.operand(rhs), self.expr(lhs)),
+        };
+        format!("{} {} {}", self.operand(lhs), symbol, self.operand(rhs))
+    }
+    fn lambda(&mut self, params: &[Pattern], body: &Expr) -> String {
+        let scope = self.scope.len();
+        let mut head = String::new();
+        let mut destructured = Vec::new();
+        for param in params {
+            let name = match param {
+                Pattern::Var(name) => {
+                    let js = ident(name);
+                    self.scope.push((name.clone(), js.clone()));
+                    js
+                }
+                Pattern::Any => "_".to_string(),
+                _ => {
+                    let tmp = self.fresh();
+                    destructured.push((param, tmp.clone()));
+                    tmp
+                }
+            };
+            head.push_str(&format!("({}) => ", name));
+        }
+        let body = if !destructured.is_empty() || is_block(body) {
+            let mut bindings = Vec::new();
+            for (param, tmp) in destructured {
+                // Function parameters are assumed to be irrefutable, so the conditions are
+                // not checked
+                let mut conditions = Vec::new();
+                self.pattern(param, &tmp, &mut conditions, &mut bindings);
+            }
+            self.block(bindings, body)
+        } else if matches!(
+            body,
+            Expr::Literal(_, Literal::Record(_)) | Expr::Update(..)
+        ) {
+            format!("({})", self.expr(body))
+        } else {
+            self.expr(body)
+        };
+        self.scope.truncate(scope);
+        head + &body
+    }
+    fn literal(&mut self, lit: &Literal<Expr>) -> String {
+        match lit {
+            Literal::Array(items) => format!("[{}]", self.exprs(items)),
+            Literal::Enum(tag, args) => match (tag.as_str(), args.is_empty()) {
+                ("true" | "false" | "undefined", true) => tag.clone(),
+                _ if args.is_empty() => format!("[{}]", quote(tag)),
+                _ => format!("[{}, {}]", quote(tag), self.exprs(args)),
+            },
+            Literal::Number(n) => n.to_string(),
+            Literal::Record(fields) if fields.is_empty() => "{}".to_string(),
+            Literal::Record(fields) => format!(
+                "{{ {} }}",
+                fields
+                    .iter()
+                    .map(|(key, value)| format!("{}: {}", key, self.expr(value)))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Literal::LStr(parts) => match parts.as_simple() {
+                Some(text) => quote(&text),
+                None if parts.is_empty() => quote(""),
+                None => {
+                    let mut js = "`".to_string();
+                    for part in parts {
+                        match part {
+                            StringPart::Text(text) => js.push_str(
+                                &text
+                                    .replace('\\', "\\\\")
+                                    .replace('`', "\\`")
+                                    .replace("${", "\\${"),
+                            ),
+                            StringPart::Value(value) => {
+                                js.push_str(&format!("${{{}}}", self.expr(value)))
+                            }
+                        }
+                    }
+                    js.push('`');
+                    js
+                }
+            },
+        }
+    }
+    fn exprs(&mut self, exprs: &[Expr]) -> String {
+        exprs
+            .iter()
+            .map(|expr| self.expr(expr))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    // STATEMENTS ==============================================================
+    /// Generate a block of statements which binds the given variables then returns the value
+    /// of the expression
+    fn block(&mut self, bindings: Vec<(String, String)>, expr: &Expr) -> String {
+        self.nested(|gen, out| {
+            for (name, value) in bindings {
+                let js = gen.bind(&name);
+                out.push(format!("const {} = {};", js, value));
+            }
+            gen.statements(expr, out);
+        })
+    }
+    /// Wrap the statements pushed by `f` in braces, indented one level deeper
+    fn nested<F>(&mut self, f: F) -> String
+    where
+        F: FnOnce(&mut Self, &mut Vec<String>),
+    {
+        let scope = self.scope.len();
+        self.indent += 1;
+        let mut statements = Vec::new();
+        f(self, &mut statements);
+        let inner = self.newline(self.indent);
+        self.indent -= 1;
+        self.scope.truncate(scope);
+        format!(
+            "{{{}{}{}}}",
+            inner,
+            statements.join(&inner),
+            self.newline(self.indent)
+        )
+    }
+    fn statements(&mut self, expr: &Expr, out: &mut Vec<String>) {
+        match expr {
+            Expr::Assert(_, cond, body) => {
+                let cond = self.expr(cond);
+                out.push(format!(
+                    "if (!({})) throw new Error(\"Assertion failed\");",
+                    cond
+                ));
+                self.statements(body, out);
+            }
+            Expr::Let(_, pattern, value, body) => {
+                let value = self.expr(value);
+                match pattern {
+                    Pattern::Any => out.push(format!("{};", value)),
+                    Pattern::Var(name) => {
+                        let js = self.bind(name);
+                        out.push(format!("const {} = {};", js, value));
+                    }
+                    _ => {
+                        let tmp = self.fresh();
+                        out.push(format!("const {} = {};", tmp, value));
+                        let (mut conditions, mut bindings) = (Vec::new(), Vec::new());
+                        self.pattern(pattern, &tmp, &mut conditions, &mut bindings);
+                        for (name, value) in bindings {
+                            let js = self.bind(&name);
+                            out.push(format!("const {} = {};", js, value));
+                        }
+                    }
+                }
+                self.statements(body, out);
+            }
+            Expr::Switch(_, subject, arms) => {
+                let subject = match subject.as_ref() {
+                    Expr::Var(_, name) => self.lookup(name),
+                    subject => {
+                        let tmp = self.fresh();
+                        let value = self.expr(subject);
+                        out.push(format!("const {} = {};", tmp, value));
+                        tmp
+                    }
+                };
+                for (pattern, guard, body) in arms {
+                    let (mut conditions, mut bindings) = (Vec::new(), Vec::new());
+                    self.pattern(pattern, &subject, &mut conditions, &mut bindings);
+                    let arm = |gen: &mut Self, out: &mut Vec<String>| {
+                        for (name, value) in bindings {
+                            let js = gen.bind(&name);
+                            out.push(format!("const {} = {};", js, value));
+                        }
+                        match guard {
+                            Some(guard) => {
+                                let guard = gen.expr(guard);
+                                let body = gen.nested(|gen, out| gen.statements(body, out));
+                                out.push(format!("if ({}) {}", guard, body));
+                            }
+                            None => gen.statements(body, out),
+                        }
+                    };
+                    if conditions.is_empty() && guard.is_none() {
+                        // Any remaining arms are unreachable
+                        arm(self, out);
+                        return;
+                    }
+                    let body = self.nested(arm);
+                    if conditions.is_empty() {
+                        out.push(body);
+                    } else {
+                        out.push(format!("if ({}) {}", conditions.join(" && "), body));
+                    }
+                }
+                out.push("throw new Error(\"Non-exhaustive switch\");".to_string());
+            }
+            _ => {
+                let value = self.expr(expr);
+                out.push(format!("return {};", value));
+            }
+        }
+    }
+
+    // PATTERNS ================================================================
+    /// Collect the conditions under which `subject` matches a pattern, and the (Ren) names and
+    /// values of the variables it binds. Conditions are in the order they must be checked in,
+    /// so that each one may assume the ones before it hold.
+    fn pattern(
+        &mut self,
+        pattern: &Pattern,
+        subject: &str,
+        conditions: &mut Vec<String>,
+        bindings: &mut Vec<(String, String)>,
+    ) {
+        match pattern {
+            Pattern::Any => {}
+            Pattern::Var(name) | Pattern::Spread(name) => {
+                bindings.push((name.clone(), subject.to_string()))
+            }
+            Pattern::Type(typ, pattern) => {
+                conditions.push(match typ.as_str() {
+                    "Array" => format!("Array.isArray({})", subject),
+                    "Boolean" | "Function" | "Number" | "String" => {
+                        format!("typeof {} === {}", subject, quote(&typ.to_lowercase()))
+                    }
+                    "Undefined" => format!("{} === undefined", subject),
+                    _ => format!("{}?.constructor?.name === {}", subject, quote(typ)),
+                });
+                self.pattern(pattern, subject, conditions, bindings);
+            }
+            Pattern::Literal(Literal::Array(items)) => {
+                let spread = items
+                    .iter()
+                    .position(|item| matches!(item, Pattern::Spread(_)));
+                conditions.push(format!("Array.isArray({})", subject));
+                conditions.push(match spread {
+                    Some(_) => format!("{}.length >= {}", subject, items.len() - 1),
+                    None => format!("{}.length === {}", subject, items.len()),
+                });
+                for (i, item) in items.iter().enumerate() {
+                    let element = match spread {
+                        Some(s) if i == s => format!(
+                            "{}.slice({}, {}.length - {})",
+                            subject,
+                            i,
+                            subject,
+                            items.len() - i - 1
+                        ),
+                        Some(s) if i > s => {
+                            format!("{}[{}.length - {}]", subject, subject, items.len() - i)
+                        }
+                        _ => format!("{}[{}]", subject, i),
+                    };
+                    self.pattern(item, &element, conditions, bindings);
+                }
+            }
+            Pattern::Literal(Literal::Enum(tag, args)) => match (tag.as_str(), args.is_empty()) {
+                ("true" | "false" | "undefined", true) => {
+                    conditions.push(format!("{} === {}", subject, tag))
+                }
+                _ => {
+                    conditions.push(format!("Array.isArray({})", subject));
+                    conditions.push(format!("{}[0] === {}", subject, quote(tag)));
+                    for (i, arg) in args.iter().enumerate() {
+                        self.pattern(
+                            arg,
+                            &format!("{}[{}]", subject, i + 1),
+                            conditions,
+                            bindings,
+                        );
+                    }
+                }
+            },
+            Pattern::Literal(Literal::Number(n)) => {
+                conditions.push(format!("{} === {}", subject, n))
+            }
+            Pattern::Literal(Literal::Record(fields)) => {
+                for (key, pattern) in fields {
+                    if let Pattern::Spread(name) = pattern {
+                        // Destructure the other fields away, leaving the rest
+                        let others = fields
+                            .iter()
+                            .filter(|(_, pattern)| !matches!(pattern, Pattern::Spread(_)))
+                            .enumerate()
+                            .map(|(i, (key, _))| format!("{}: ${}, ", quote(key), i))
+                            .collect::<String>();
+                        bindings.push((
+                            name.clone(),
+                            format!("(({{ {}...$rest }}) => $rest)({})", others, subject),
+                        ));
+                    } else {
+                        self.pattern(pattern, &member(subject, key), conditions, bindings);
+                    }
+                }
+            }
+            Pattern::Literal(Literal::LStr(parts)) => match parts.as_simple() {
+                Some(text) => conditions.push(format!("{} === {}", subject, quote(&text))),
+                None => {
+                    // Match the text with a regular expression, capturing the parts between
+                    // the text to be matched against the nested patterns
+                    let mut regex = "/^".to_string();
+                    for part in parts {
+                        match part {
+                            StringPart::Text(text) => regex.push_str(&escape_regex(text)),
+                            StringPart::Value(_) => regex.push_str("(.*?)"),
+                        }
+                    }
+                    regex.push_str("$/s");
+                    conditions.push(format!("typeof {} === \"string\"", subject));
+                    conditions.push(format!("{}.test({})", regex, subject));
+                    let values = parts.iter().filter_map(|part| match part {
+                        StringPart::Value(pattern) => Some(pattern),
+                        StringPart::Text(_) => None,
+                    });
+                    for (i, pattern) in values.enumerate() {
+                        let capture = format!("{}.exec({})[{}]", regex, subject, i + 1);
+                        self.pattern(pattern, &capture, conditions, bindings);
+                    }
+                }
+            },
+        }
+    }
+}
+
+/// Whether an expression must be wrapped in parentheses to be used as an operand
+fn is_atomic(expr: &Expr) -> bool {
+    match expr {
+        Expr::Access(..)
+        | Expr::Call(..)
+        | Expr::Placeholder(_)
+        | Expr::Scoped(..)
+        | Expr::Var(..) => true,
+        Expr::Annotated(_, expr, _) => is_atomic(expr),
+        Expr::Literal(_, Literal::Number(n)) => *n >= 0.0,
+        Expr::Literal(..) | Expr::Update(..) => true,
+        _ => false,
+    }
+}
+/// Whether an expression compiles to a block of statements
+fn is_block(expr: &Expr) -> bool {
+    matches!(expr, Expr::Assert(..) | Expr::Let(..) | Expr::Switch(..))
+}
+/// Escape a variable name which is reserved in JavaScript
+fn ident(name: &str) -> String {
+    if RESERVED.contains(&name) {
+        format!("{}$", name)
+    } else if is_operator_name(name) {
+        // Operators are spelled out character by character, e.g. `<+>` becomes `$lt$plus$gt`
+        name.chars()
+            .map(|c| {
+                let word = match c {
+                    '!' => "bang",
+                    '$' => "dollar",
+                    '%' => "percent",
+                    '&' => "amp",
+                    '*' => "star",
+                    '+' => "plus",
+                    '-' => "minus",
+                    '.' => "dot",
+                    '/' => "slash",
+                    ':' => "colon",
+                    '<' => "lt",
+                    '=' => "eq",
+                    '>' => "gt",
+                    '^' => "caret",
+                    '|' => "bar",
+                    '~' => "tilde",
+                    _ => unreachable!("{:?} is not an operator character", c),
+                };
+                format!("${}", word)
+            })
+            .collect()
+    } else {
+        name.to_string()
+    }
+}
+/// The name an import alias is bound to, e.g. `Data.Array` becomes `$Data$Array`. The prefix
+/// stops an alias like `Array` or `Error` shadowing the JavaScript global of the same name.
+fn namespace(alias: &[String]) -> String {
+    format!("${}", alias.join("$"))
+}
+fn member(object: &str, key: &str) -> String {
+    let is_ident = key.chars().next().is_some_and(|c| !c.is_ascii_digit())
+        && key
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '$');
+    if is_ident {
+        format!("{}.{}", object, key)
+    } else {
+        format!("{}[{}]", object, quote(key))
+    }
+}
+fn quote(s: &str) -> String {
+    serde_json::to_string(s).expect("strings should serialise")
+}
+/// The path of the JavaScript a module is compiled to, e.g. `./list` and `./list.ren` both
+/// become `./list.js`
+fn with_extension(path: &str) -> String {
+    if path.ends_with(".js") || path.ends_with(".mjs") {
+        path.to_string()
+    } else {
+        format!("{}.js", path.strip_suffix(".ren").unwrap_or(path))
+    }
+}
+fn escape_regex(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '\\' | '^' | '$' | '.' | '|' | '?' | '*' | '+' | '(' | ')' | '[' | ']' | '{' | '}'
+            | '/' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,529 @@
+use expect_test::{expect, Expect};
+
+use super::{expr_to_js, ffi_path, module_to_js};
+use crate::{
+    expr::{fixtures::var, Literal, StringPart},
+    module, Decl, Expr, Import, Module, Operator, Pattern, Span,
+};
+
+fn check_expr(expr: Expr, expected: Expect) {
+    expected.assert_eq(&expr_to_js(&expr));
+}
+fn check_module(imports: Vec<Import>, decls: Vec<Decl>, expected: Expect) {
+    let module = Module::new(
+        module::Meta::new("Main", "src/Main.ren"),
+        imports.into_iter(),
+        decls.into_iter(),
+    );
+    expected.assert_eq(&module_to_js(&module));
+}
+
+#[test]
+fn literals() {
+    check_expr(
+        Expr::literal(vec![
+            ("a", Expr::literal(1)),
+            ("b", Expr::literal("say \"hi\"")),
+            (
+                "c",
+                Expr::literal(vec![Expr::literal(1.5), Expr::literal(-2)]),
+            ),
+        ]),
+        expect![[r#"{ a: 1, b: "say \"hi\"", c: [1.5, -2] }"#]],
+    );
+    check_expr(
+        Expr::literal(Literal::LStr(vec![
+            StringPart::Text("`${x}` is ".to_string()),
+            StringPart::Value(Expr::var("x")),
+        ])),
+        expect![[r#"`\`\${x}\` is ${x}`"#]],
+    );
+}
+
+#[test]
+fn enums_are_tagged_arrays() {
+    check_expr(
+        Expr::literal(vec![
+            Expr::literal(Literal::Enum(
+                "just".to_string(),
+                vec![Expr::literal(1), Expr::var("x")],
+            )),
+            Expr::literal(Literal::Enum("nothing".to_string(), vec![])),
+            Expr::literal(Literal::Enum("true".to_string(), vec![])),
+            Expr::literal(()),
+        ]),
+        expect![[r#"[["just", 1, x], ["nothing"], true, undefined]"#]],
+    );
+}
+
+#[test]
+fn functions_are_curried() {
+    check_expr(
+        Expr::lambda(
+            vec![var("a"), Pattern::Any],
+            Expr::apply_many(
+                Expr::scoped(vec!["Math".to_string()], "max".to_string()),
+                [Expr::var("a"), Expr::literal(1)],
+            ),
+        ),
+        expect!["(a) => (_) => $Math.max(a)(1)"],
+    );
+    check_expr(
+        Expr::lambda(vec![var("x")], Expr::literal(vec![("x", Expr::var("x"))])),
+        expect![[r#"(x) => ({ x: x })"#]],
+    );
+}
+
+#[test]
+fn operators() {
+    check_expr(
+        Expr::binop(
+            Expr::binop(Expr::var("a"), Operator::Add, Expr::literal(1)),
+            Operator::Mul,
+            Expr::binop(Expr::var("b"), Operator::Sub, Expr::literal(-2)),
+        ),
+        expect![[r#"(a + 1) * (b - (-2))"#]],
+    );
+    check_expr(
+        Expr::binop(
+            Expr::binop(Expr::var("x"), Operator::Cons, Expr::var("xs")),
+            Operator::Concat,
+            Expr::literal(vec![Expr::literal(1)]),
+        ),
+        expect![[r#"([x, ...xs]).concat([1])"#]],
+    );
+    check_expr(
+        Expr::binop(Expr::var("x"), Operator::Pipe, Expr::var("f")),
+        expect![[r#"f(x)"#]],
+    );
+    check_expr(
+        Expr::binop(Expr::var("a"), Operator::Neq, Expr::var("b")),
+        expect![[r#"
+            (() => {
+            function $eq(a, b) {
+                if (a === b) return true;
+                if (typeof a !== "object" || typeof b !== "object" || a === null || b === null) return false;
+                if (Array.isArray(a)) return Array.isArray(b) && a.length === b.length && a.every((x, i) => $eq(x, b[i]));
+                const keys = Object.keys(a);
+                return keys.length === Object.keys(b).length && keys.every((k) => k in b && $eq(a[k], b[k]));
+            }
+            return !$eq(a, b);
+            })()"#]],
+    );
+}
+
+#[test]
+fn placeholders() {
+    check_expr(
+        Expr::binop(Expr::placeholder(), Operator::Add, Expr::literal(1)),
+        expect!["undefined + 1"],
+    );
+    check_expr(
+        Expr::access(Expr::placeholder(), "x"),
+        expect!["undefined.x"],
+    );
+}
+
+#[test]
+fn lets_shadow() {
+    check_expr(
+        Expr::binding(
+            var("x"),
+            Expr::literal(1),
+            Expr::binding(
+                var("x"),
+                Expr::binop(Expr::var("x"), Operator::Add, Expr::literal(1)),
+                Expr::var("x"),
+            ),
+        ),
+        expect![[r#"
+            (() => {
+                const x = 1;
+                const x$0 = x + 1;
+                return x$0;
+            })()"#]],
+    );
+    check_expr(
+        Expr::lambda(
+            vec![Literal::Record(vec![("a".to_string(), var("new"))]).into()],
+            Expr::var("new"),
+        ),
+        expect![[r#"
+            ($0) => {
+                const new$ = $0.a;
+                return new$;
+            }"#]],
+    );
+}
+
+#[test]
+fn record_spreads() {
+    check_expr(
+        Expr::lambda(
+            vec![Literal::Record(vec![
+                ("a".to_string(), var("a")),
+                ("default".to_string(), var("b")),
+                ("rest".to_string(), Pattern::Spread("rest".to_string())),
+            ])
+            .into()],
+            Expr::var("rest"),
+        ),
+        expect![[r#"
+            ($0) => {
+                const a = $0.a;
+                const b = $0.default;
+                const rest = (({ "a": $0, "default": $1, ...$rest }) => $rest)($0);
+                return rest;
+            }"#]],
+    );
+}
+
+#[test]
+fn record_updates() {
+    check_expr(
+        Expr::lambda(
+            vec![var("r")],
+            Expr::update(
+                Expr::var("r"),
+                [(Span::default(), "default", Expr::literal(1))],
+            ),
+        ),
+        expect!["(r) => ({ ...r, default: 1 })"],
+    );
+}
+
+#[test]
+fn assertions_throw() {
+    check_expr(
+        Expr::lambda(
+            vec![var("x")],
+            Expr::assert(
+                Expr::binop(Expr::var("x"), Operator::Gt, Expr::literal(0)),
+                Expr::var("x"),
+            ),
+        ),
+        expect![[r#"
+            (x) => {
+                if (!(x > 0)) throw new Error("Assertion failed");
+                return x;
+            }"#]],
+    );
+}
+
+#[test]
+fn switch() {
+    check_expr(
+        Expr::switch(
+            Expr::access(Expr::var("r"), "value"),
+            vec![
+                (
+                    Literal::Enum("just".to_string(), vec![Pattern::Literal(0.into())]).into(),
+                    None,
+                    Expr::literal("zero"),
+                ),
+                (
+                    Literal::Enum("just".to_string(), vec![var("n")]).into(),
+                    Some(Expr::binop(Expr::var("n"), Operator::Gt, Expr::literal(0))),
+                    Expr::literal("positive"),
+                ),
+                (
+                    Literal::Array(vec![
+                        var("first"),
+                        Pattern::Spread("rest".to_string()),
+                        var("last"),
+                    ])
+                    .into(),
+                    None,
+                    Expr::var("rest"),
+                ),
+                (
+                    Pattern::Type("String".to_string(), Box::new(var("s"))),
+                    None,
+                    Expr::var("s"),
+                ),
+                (Pattern::Any, None, Expr::literal("other")),
+            ],
+        ),
+        expect![[r#"
+            (() => {
+                const $0 = r.value;
+                if (Array.isArray($0) && $0[0] === "just" && $0[1] === 0) {
+                    return "zero";
+                }
+                if (Array.isArray($0) && $0[0] === "just") {
+                    const n = $0[1];
+                    if (n > 0) {
+                        return "positive";
+                    }
+                }
+                if (Array.isArray($0) && $0.length >= 2) {
+                    const first = $0[0];
+                    const rest = $0.slice(1, $0.length - 1);
+                    const last = $0[$0.length - 1];
+                    return rest;
+                }
+                if (typeof $0 === "string") {
+                    const s = $0;
+                    return s;
+                }
+                return "other";
+            })()"#]],
+    );
+}
+
+#[test]
+fn switch_on_strings() {
+    check_expr(
+        Expr::lambda(
+            vec![var("s")],
+            Expr::switch(
+                Expr::var("s"),
+                vec![(
+                    Literal::LStr(vec![
+                        StringPart::Text("Hello, ".to_string()),
+                        StringPart::Value(var("name")),
+                        StringPart::Text(".".to_string()),
+                    ])
+                    .into(),
+                    None,
+                    Expr::var("name"),
+                )],
+            ),
+        ),
+        expect![[r#"
+            (s) => {
+                if (typeof s === "string" && /^Hello, (.*?)\.$/s.test(s)) {
+                    const name = /^Hello, (.*?)\.$/s.exec(s)[1];
+                    return name;
+                }
+                throw new Error("Non-exhaustive switch");
+            }"#]],
+    );
+}
+
+#[test]
+fn module() {
+    check_module(
+        vec![
+            Import::project("./utils".to_string(), vec!["Utils".to_string()], vec![]),
+            Import::project("./list.ren".to_string(), vec!["List".to_string()], vec![]),
+            Import::package(
+                "ren/array".to_string(),
+                vec!["Data".to_string(), "Array".to_string()],
+                vec![],
+            ),
+            Import::external("node:fs".to_string(), vec!["Fs".to_string()], vec![]),
+            Import::external("./polyfill.js".to_string(), vec![], vec![]),
+        ],
+        vec![
+            Decl::typ(crate::Type::num(), ((1, 1), (1, 1)), true, "Count"),
+            Decl::external(None, ((2, 1), (2, 1)), false, "log", "log"),
+            Decl::external(None, ((3, 1), (3, 1)), true, "default", "default"),
+            Decl::local(
+                None,
+                ((4, 1), (4, 1)),
+                true,
+                "main",
+                Expr::lambda(
+                    vec![Pattern::Any],
+                    Expr::apply(
+                        Expr::var("log"),
+                        Expr::apply(
+                            Expr::scoped(
+                                vec!["Data".to_string(), "Array".to_string()],
+                                "length".to_string(),
+                            ),
+                            Expr::var("xs"),
+                        ),
+                    ),
+                ),
+            ),
+            Decl::local(
+                None,
+                ((5, 1), (5, 1)),
+                false,
+                "xs",
+                Expr::literal(vec![Expr::literal(1)]),
+            ),
+        ],
+        expect![[r#"
+            import * as $Utils from "./utils.js";
+            import * as $List from "./list.js";
+            import * as $Data$Array from "./.pkg/ren/array.js";
+            import * as $Fs from "node:fs";
+            import "./polyfill.js";
+            import * as $ffi from "./Main.ffi.js";
+
+            const log = $ffi.log;
+
+            export const default$ = $ffi.default;
+
+            export const main = (_) => log($Data$Array.length(xs));
+
+            const xs = [1];
+        "#]],
+    );
+}
+
+#[test]
+fn ffi_files_are_named_after_the_source_file() {
+    let ffi = |name, path| ffi_path(&module::Meta::new(name, path));
+    assert_eq!(ffi("Main", "src/main.ren"), "./main.ffi.js");
+    assert_eq!(ffi("Main", ""), "./Main.ffi.js");
+    assert_eq!(ffi("", ""), "./ffi.js");
+}
+
+#[test]
+fn namespaces_do_not_shadow_globals() {
+    check_module(
+        vec![Import::package(
+            "ren/array".to_string(),
+            vec!["Array".to_string()],
+            vec![],
+        )],
+        vec![Decl::local(
+            None,
+            ((1, 1), (1, 1)),
+            true,
+            "head",
+            Expr::lambda(
+                vec![var("xs")],
+                Expr::switch(
+                    Expr::var("xs"),
+                    vec![(
+                        Literal::Array(vec![var("x"), Pattern::Spread("rest".to_string())]).into(),
+                        None,
+                        Expr::apply(
+                            Expr::scoped(vec!["Array".to_string()], "length".to_string()),
+                            Expr::var("rest"),
+                        ),
+                    )],
+                ),
+            ),
+        )],
+        expect![[r#"
+            import * as $Array from "./.pkg/ren/array.js";
+
+            export const head = (xs) => {
+                if (Array.isArray(xs) && xs.length >= 1) {
+                    const x = xs[0];
+                    const rest = xs.slice(1, xs.length - 0);
+                    return $Array.length(rest);
+                }
+                throw new Error("Non-exhaustive switch");
+            };
+        "#]],
+    );
+}
+
+#[test]
+fn exposed_names() {
+    check_module(
+        vec![
+            Import::project(
+                "./list".to_string(),
+                vec!["List".to_string()],
+                vec!["map".to_string(), "<+>".to_string()],
+            ),
+            Import::package(
+                "ren/maybe".to_string(),
+                vec![],
+                vec!["withDefault".to_string()],
+            ),
+        ],
+        vec![Decl::local(
+            None,
+            ((1, 1), (1, 1)),
+            false,
+            "f",
+            Expr::lambda(
+                vec![var("map")],
+                Expr::apply_many(
+                    Expr::var("<+>"),
+                    [Expr::var("map"), Expr::var("withDefault")],
+                ),
+            ),
+        )],
+        expect![[r#"
+            import * as $List from "./list.js";
+            import { map, $lt$plus$gt } from "./list.js";
+            import { withDefault } from "./.pkg/ren/maybe.js";
+
+            const f = (map) => $lt$plus$gt(map)(withDefault);
+        "#]],
+    );
+}
+
+#[test]
+fn module_without_imports() {
+    check_module(
+        vec![],
+        vec![Decl::local(
+            None,
+            ((1, 1), (1, 1)),
+            false,
+            "same",
+            Expr::binop(Expr::literal(1), Operator::Eq, Expr::literal(1)),
+        )],
+        expect![[r#"
+            function $eq(a, b) {
+                if (a === b) return true;
+                if (typeof a !== "object" || typeof b !== "object" || a === null || b === null) return false;
+                if (Array.isArray(a)) return Array.isArray(b) && a.length === b.length && a.every((x, i) => $eq(x, b[i]));
+                const keys = Object.keys(a);
+                return keys.length === Object.keys(b).length && keys.every((k) => k in b && $eq(a[k], b[k]));
+            }
+
+            const same = $eq(1, 1);
+        "#]],
+    );
+}
+
+#[test]
+fn operators_are_spelled_out() {
+    check_module(
+        vec![],
+        vec![
+            Decl::infix(
+                ((1, 1), (1, 1)),
+                true,
+                crate::Fixity::DEFAULT,
+                "<+>",
+                Expr::var("add"),
+            ),
+            Decl::local(
+                None,
+                ((2, 1), (2, 1)),
+                false,
+                "x",
+                Expr::apply_many(Expr::var("<+>"), [Expr::literal(1), Expr::literal(2)]),
+            ),
+            Decl::infix(
+                ((3, 1), (3, 1)),
+                false,
+                crate::Fixity::DEFAULT,
+                "<.:>",
+                Expr::var("compose"),
+            ),
+        ],
+        expect![[r#"
+            export const $lt$plus$gt = add;
+
+            const x = $lt$plus$gt(1)(2);
+
+            const $lt$dot$colon$gt = compose;
+        "#]],
+    );
+}
+
+#[test]
+fn placeholders_are_lambdas() {
+    check_expr(
+        Expr::apply_many(
+            Expr::var("f"),
+            [Expr::placeholder(), Expr::var("x"), Expr::placeholder()],
+        )
+        .replace_placeholders(),
+        expect![[r#"(_0) => (_1) => f(_0)(x)(_1)"#]],
+    );
+}
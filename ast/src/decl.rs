@@ -1,6 +1,7 @@
+use ren_json_derive::RenJson;
 use serde::{Deserialize, Serialize};
 
-use crate::{expr::Expr, ren_type::Type, Span};
+use crate::{expr::Expr, ren_type::Type, Fixity, Span};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Meta {
@@ -32,9 +33,20 @@ impl Meta {
     {
         self.span = span.into();
     }
+    pub fn get_span(&self) -> Span {
+        self.span
+    }
     pub fn get_type(&self) -> &Type {
         &self.typ
     }
+    /// Set the type of the declaration, without affecting whether it was inferred
+    pub fn set_type(&mut self, typ: Type) {
+        self.typ = typ;
+    }
+    /// Whether the type was inferred, rather than annotated in the source
+    pub fn is_inferred(&self) -> bool {
+        self.inferred
+    }
 }
 impl Default for Meta {
     fn default() -> Self {
@@ -47,32 +59,43 @@ impl Default for Meta {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, RenJson)]
 pub enum Decl {
     Let {
+        meta: Meta,
         exposed: bool,
         var: String,
-        typ: Type,
         expr: Expr,
     },
     Ext {
+        meta: Meta,
         exposed: bool,
         var: String,
-        typ: Type,
         name: String,
     },
     Type {
+        meta: Meta,
         exposed: bool,
         name: String,
         vars: Vec<String>,
         typ: Type,
     },
+    /// A test, which passes if its expression evaluates to `true`
+    Expect { meta: Meta, expr: Expr },
+    /// An infix operator standing for the value of its expression, e.g. `infixl 6 <+> = add`
+    Infix {
+        meta: Meta,
+        exposed: bool,
+        fixity: Fixity,
+        op: String,
+        expr: Expr,
+    },
 }
 impl Decl {
     // CONSTRUCTORS ============================================================
     pub fn local<N, S>(
         type_annotation: Option<Type>,
-        _span: S,
+        span: S,
         exposed: bool,
         var: N,
         expr: Expr,
@@ -82,15 +105,15 @@ impl Decl {
         S: Into<Span>,
     {
         Self::Let {
+            meta: Meta::new(type_annotation, span),
             exposed,
             var: var.to_string(),
-            typ: type_annotation.unwrap_or_default(),
             expr,
         }
     }
     pub fn external<N, E, S>(
         type_annotation: Option<Type>,
-        _span: S,
+        span: S,
         exposed: bool,
         var: N,
         name: E,
@@ -101,47 +124,115 @@ impl Decl {
         S: Into<Span>,
     {
         Self::Ext {
+            meta: Meta::new(type_annotation, span),
             exposed,
             var: var.to_string(),
-            typ: type_annotation.unwrap_or_default(),
             name: name.to_string(),
         }
     }
-    pub fn typ<N, S>(type_annotation: Type, _span: S, exposed: bool, name: N) -> Self
+    pub fn typ<N, S>(type_annotation: Type, span: S, exposed: bool, name: N) -> Self
     where
         N: ToString,
         S: Into<Span>,
+    {
+        Self::generic_typ(type_annotation, span, exposed, name, Vec::<String>::new())
+    }
+    /// A type declaration with type variables, e.g. `type Maybe a = #just a | #nothing`
+    pub fn generic_typ<N, S, V>(
+        type_annotation: Type,
+        span: S,
+        exposed: bool,
+        name: N,
+        vars: V,
+    ) -> Self
+    where
+        N: ToString,
+        S: Into<Span>,
+        V: IntoIterator,
+        V::Item: ToString,
     {
         Self::Type {
+            meta: Meta::new(None, span),
             exposed,
             name: name.to_string(),
-            vars: Vec::new(),
+            vars: vars.into_iter().map(|var| var.to_string()).collect(),
             typ: type_annotation,
         }
     }
 
+    pub fn expect<S>(span: S, expr: Expr) -> Self
+    where
+        S: Into<Span>,
+    {
+        Self::Expect {
+            meta: Meta::new(None, span),
+            expr,
+        }
+    }
+
+    pub fn infix<O, S>(span: S, exposed: bool, fixity: Fixity, op: O, expr: Expr) -> Self
+    where
+        O: ToString,
+        S: Into<Span>,
+    {
+        Self::Infix {
+            meta: Meta::new(None, span),
+            exposed,
+            fixity,
+            op: op.to_string(),
+            expr,
+        }
+    }
+
     // QUERIES ============================================================
+    pub fn meta(&self) -> &Meta {
+        match self {
+            Decl::Let { meta, .. }
+            | Decl::Ext { meta, .. }
+            | Decl::Type { meta, .. }
+            | Decl::Expect { meta, .. }
+            | Decl::Infix { meta, .. } => meta,
+        }
+    }
+    pub fn meta_mut(&mut self) -> &mut Meta {
+        match self {
+            Decl::Let { meta, .. }
+            | Decl::Ext { meta, .. }
+            | Decl::Type { meta, .. }
+            | Decl::Expect { meta, .. }
+            | Decl::Infix { meta, .. } => meta,
+        }
+    }
+    /// The name declared, which is empty for an `expect`, or the operator for an `infix`
     pub fn name(&self) -> &str {
         match self {
-            Decl::Let { var, .. } | Decl::Ext { var, .. } | Decl::Type { name: var, .. } => var,
+            Decl::Let { var, .. }
+            | Decl::Ext { var, .. }
+            | Decl::Type { name: var, .. }
+            | Decl::Infix { op: var, .. } => var,
+            Decl::Expect { .. } => "",
         }
     }
     pub fn is_exposed(&self) -> bool {
         match self {
-            Decl::Let { exposed, .. } | Decl::Ext { exposed, .. } | Decl::Type { exposed, .. } => {
-                *exposed
-            }
+            Decl::Let { exposed, .. }
+            | Decl::Ext { exposed, .. }
+            | Decl::Type { exposed, .. }
+            | Decl::Infix { exposed, .. } => *exposed,
+            Decl::Expect { .. } => false,
         }
     }
     pub fn is_local(&self) -> bool {
         match self {
-            Decl::Let { .. } | Decl::Type { .. } => true,
+            Decl::Let { .. } | Decl::Type { .. } | Decl::Expect { .. } | Decl::Infix { .. } => true,
             Decl::Ext { .. } => false,
         }
     }
     pub fn is_external(&self) -> bool {
         match self {
-            Decl::Let { .. } | Decl::Type { .. } => false,
+            Decl::Let { .. } | Decl::Type { .. } | Decl::Expect { .. } | Decl::Infix { .. } => {
+                false
+            }
             Decl::Ext { .. } => true,
         }
     }
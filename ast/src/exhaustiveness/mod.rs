@@ -0,0 +1,479 @@
+//! Exhaustiveness and redundancy checking for `switch` expressions, using the pattern matrix
+//! algorithm from Maranget's "Warnings for pattern matching".
+//!
+//! Variants are structural, so the variants a subject may be are taken from the type inferred
+//! for it (see [`crate::infer`]) when there is one, and are otherwise assumed to be the ones
+//! its cases name. Numbers and strings are never covered by literals alone. Guarded cases,
+//! type patterns and string patterns with interpolated parts are treated conservatively: they
+//! are never relied on to match anything, so they can't make a `switch` exhaustive or another
+//! case unreachable.
+
+use std::fmt::Display;
+
+use crate::{
+    decl::Decl,
+    expr::{literal::StringParts, Expr, Literal, Pattern, StringPart},
+    module::Module,
+    pretty::Pretty,
+    ren_type::Type,
+    span::Span,
+};
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WarningKind {
+    /// A `switch` doesn't match every value, with an example of one which it doesn't match
+    NonExhaustive(Pattern),
+    /// A case, by its index, which earlier cases always match before it
+    Unreachable(usize),
+}
+
+/// A problem with a `switch`. A non-exhaustive `switch` is located at the `switch`, and an
+/// unreachable case at its body.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Warning {
+    kind: WarningKind,
+    span: Span,
+}
+impl Warning {
+    pub fn kind(&self) -> &WarningKind {
+        &self.kind
+    }
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+impl Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ((line, col), _): ((usize, usize), (usize, usize)) = self.span.into();
+        write!(f, "{}:{}: ", line, col)?;
+        match &self.kind {
+            WarningKind::NonExhaustive(example) => write!(
+                f,
+                "switch does not match every value, e.g. `{}`",
+                example.pretty(usize::MAX)
+            ),
+            WarningKind::Unreachable(_) => {
+                write!(
+                    f,
+                    "unreachable case, earlier cases match every value it does"
+                )
+            }
+        }
+    }
+}
+
+/// Check every `switch` in a module. If the module has been type checked, the inferred types
+/// of their subjects are used to find the variants they might be.
+pub fn check_module(module: &Module) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+    for decl in module.declarations() {
+        if let Decl::Let { expr, .. } | Decl::Infix { expr, .. } | Decl::Expect { expr, .. } = decl
+        {
+            check(expr, &mut warnings);
+        }
+    }
+    warnings
+}
+
+/// Check every `switch` in an expression
+pub fn check_expr(expr: &Expr) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+    check(expr, &mut warnings);
+    warnings
+}
+
+fn check(expr: &Expr, warnings: &mut Vec<Warning>) {
+    match expr {
+        Expr::Access(_, expr, _) | Expr::Annotated(_, expr, _) => check(expr, warnings),
+        Expr::Assert(_, lhs, rhs) | Expr::Binop(_, lhs, _, rhs) => {
+            check(lhs, warnings);
+            check(rhs, warnings);
+        }
+        Expr::Call(_, func, args) => {
+            check(func, warnings);
+            args.iter().for_each(|arg| check(arg, warnings));
+        }
+        Expr::If(_, condition, then, otherwise) => {
+            check(condition, warnings);
+            check(then, warnings);
+            check(otherwise, warnings);
+        }
+        Expr::Lambda(_, _, body) => check(body, warnings),
+        Expr::Let(_, _, value, body) => {
+            check(value, warnings);
+            check(body, warnings);
+        }
+        Expr::Literal(_, literal) => match literal {
+            Literal::Array(items) | Literal::Enum(_, items) => {
+                items.iter().for_each(|item| check(item, warnings))
+            }
+            Literal::Number(_) => {}
+            Literal::Record(fields) => fields.iter().for_each(|(_, value)| check(value, warnings)),
+            Literal::LStr(parts) => parts.iter().for_each(|part| {
+                if let StringPart::Value(value) = part {
+                    check(value, warnings);
+                }
+            }),
+        },
+        Expr::Placeholder(_) | Expr::Scoped(..) | Expr::Var(..) => {}
+        Expr::Switch(meta, subject, cases) => {
+            check(subject, warnings);
+            let types = [subject.meta().get_type().clone()];
+            let mut rows = Vec::new();
+            for (index, (pattern, guard, body)) in cases.iter().enumerate() {
+                let row = vec![Pat::from(pattern)];
+                if !is_useful(&rows, &row, &types) {
+                    warnings.push(Warning {
+                        kind: WarningKind::Unreachable(index),
+                        span: body.meta().get_span(),
+                    });
+                }
+                if guard.is_none() {
+                    rows.push(row);
+                }
+            }
+            if let Some(mut example) = missing(&rows, &types) {
+                warnings.push(Warning {
+                    kind: WarningKind::NonExhaustive(example.remove(0)),
+                    span: meta.get_span(),
+                });
+            }
+            for (_, guard, body) in cases {
+                if let Some(guard) = guard {
+                    check(guard, warnings);
+                }
+                check(body, warnings);
+            }
+        }
+        Expr::Update(_, target, fields) => {
+            check(target, warnings);
+            fields
+                .iter()
+                .for_each(|(_, _, value)| check(value, warnings));
+        }
+    }
+}
+
+// PATTERNS ====================================================================
+
+/// A pattern, keeping only what decides which values it matches
+#[derive(Debug, Clone, PartialEq)]
+enum Pat {
+    /// Matches every value
+    Wild,
+    /// Matches some values, but is never relied on to match any
+    Opaque,
+    Tag(String, Vec<Pat>),
+    Number(f64),
+    Str(String),
+    /// The items before a spread, and the items after it if there is one
+    Array(Vec<Pat>, Option<Vec<Pat>>),
+    Record(Vec<(String, Pat)>),
+}
+impl From<&Pattern> for Pat {
+    fn from(pattern: &Pattern) -> Self {
+        match pattern {
+            Pattern::Any | Pattern::Var(_) | Pattern::Spread(_) => Pat::Wild,
+            Pattern::Type(..) => Pat::Opaque,
+            Pattern::Literal(Literal::Array(items)) => {
+                match items
+                    .iter()
+                    .position(|item| matches!(item, Pattern::Spread(_)))
+                {
+                    Some(spread) => Pat::Array(
+                        items[..spread].iter().map(Pat::from).collect(),
+                        Some(items[spread + 1..].iter().map(Pat::from).collect()),
+                    ),
+                    None => Pat::Array(items.iter().map(Pat::from).collect(), None),
+                }
+            }
+            Pattern::Literal(Literal::Enum(tag, args)) => {
+                Pat::Tag(tag.clone(), args.iter().map(Pat::from).collect())
+            }
+            Pattern::Literal(Literal::Number(n)) => Pat::Number(*n),
+            // The fields a record pattern doesn't name may be anything
+            Pattern::Literal(Literal::Record(fields)) => Pat::Record(
+                fields
+                    .iter()
+                    .filter(|(_, pattern)| !matches!(pattern, Pattern::Spread(_)))
+                    .map(|(key, pattern)| (key.clone(), Pat::from(pattern)))
+                    .collect(),
+            ),
+            Pattern::Literal(Literal::LStr(parts)) => match parts.as_simple() {
+                Some(text) => Pat::Str(text),
+                None if parts.is_empty() => Pat::Str(String::new()),
+                None => Pat::Opaque,
+            },
+        }
+    }
+}
+
+/// The shape of the values matched by a pattern, with the number of values inside it
+#[derive(Debug, Clone, PartialEq)]
+enum Ctor {
+    Tag(String, usize),
+    Number(f64),
+    Str(String),
+    /// An array of exactly this length
+    Array(usize),
+    /// A record, with every field named by a pattern in the same column
+    Record(Vec<String>),
+}
+impl Ctor {
+    fn arity(&self) -> usize {
+        match self {
+            Ctor::Tag(_, arity) | Ctor::Array(arity) => *arity,
+            Ctor::Number(_) | Ctor::Str(_) => 0,
+            Ctor::Record(keys) => keys.len(),
+        }
+    }
+    /// The types of the values inside a value of this shape, if it has type `typ`
+    fn arg_types(&self, typ: &Type) -> Vec<Type> {
+        let known = match (self, typ) {
+            (Ctor::Tag(tag, arity), Type::Sum(row)) => {
+                row.get(tag).filter(|args| args.len() == *arity).cloned()
+            }
+            (Ctor::Array(len), Type::App(con, args)) if **con == Type::Con("Array".to_string()) => {
+                args.first().map(|elem| vec![elem.clone(); *len])
+            }
+            (Ctor::Record(keys), Type::Rec(row)) => Some(
+                keys.iter()
+                    .map(|key| {
+                        row.get(key)
+                            .and_then(|t| t.first())
+                            .cloned()
+                            .unwrap_or_default()
+                    })
+                    .collect(),
+            ),
+            _ => None,
+        };
+        known.unwrap_or_else(|| vec![Type::Hole; self.arity()])
+    }
+    /// An example of a value of this shape, containing `args`
+    fn example(&self, args: Vec<Pattern>) -> Pattern {
+        Pattern::Literal(match self {
+            Ctor::Tag(tag, _) => Literal::Enum(tag.clone(), args),
+            Ctor::Number(n) => Literal::Number(*n),
+            Ctor::Str(text) => Literal::LStr(vec![StringPart::Text(text.clone())]),
+            Ctor::Array(_) => Literal::Array(args),
+            Ctor::Record(keys) => Literal::Record(keys.iter().cloned().zip(args).collect()),
+        })
+    }
+}
+
+/// The patterns in the same row as `row` for the values inside a value of shape `ctor`,
+/// followed by the rest of the row, if the first pattern in the row can match that shape
+fn specialise(row: &[Pat], ctor: &Ctor) -> Option<Vec<Pat>> {
+    let (head, rest) = row.split_first()?;
+    let mut args = match (head, ctor) {
+        (Pat::Wild, _) => vec![Pat::Wild; ctor.arity()],
+        (Pat::Tag(tag, args), Ctor::Tag(t, arity)) if tag == t && args.len() == *arity => {
+            args.clone()
+        }
+        (Pat::Number(n), Ctor::Number(m)) if n == m => Vec::new(),
+        (Pat::Str(text), Ctor::Str(t)) if text == t => Vec::new(),
+        (Pat::Array(items, None), Ctor::Array(len)) if items.len() == *len => items.clone(),
+        (Pat::Array(before, Some(after)), Ctor::Array(len))
+            if before.len() + after.len() <= *len =>
+        {
+            let spread = vec![Pat::Wild; len - before.len() - after.len()];
+            [before.clone(), spread, after.clone()].concat()
+        }
+        (Pat::Record(fields), Ctor::Record(keys)) => keys
+            .iter()
+            .map(|key| {
+                fields
+                    .iter()
+                    .find(|(k, _)| k == key)
+                    .map_or(Pat::Wild, |(_, pat)| pat.clone())
+            })
+            .collect(),
+        _ => return None,
+    };
+    args.extend_from_slice(rest);
+    Some(args)
+}
+
+/// The rest of each row whose first pattern matches every value
+fn default_rows(rows: &[Vec<Pat>]) -> Vec<Vec<Pat>> {
+    rows.iter()
+        .filter(|row| row.first() == Some(&Pat::Wild))
+        .map(|row| row[1..].to_vec())
+        .collect()
+}
+
+/// The shapes which the values in a column may have, with the type of the column
+enum Signature {
+    /// Every value has one of these shapes
+    Complete(Vec<Ctor>),
+    /// Some values have none of the shapes in the column, like this example
+    Incomplete(Pattern),
+}
+
+fn signature(column: &[&Pat], typ: &Type) -> Signature {
+    let heads = column
+        .iter()
+        .filter(|pat| !matches!(pat, Pat::Wild | Pat::Opaque))
+        .collect::<Vec<_>>();
+    let Some(first) = heads.first() else {
+        return match typ {
+            Type::Sum(_) => Signature::Complete(tags(&[], typ)),
+            _ => Signature::Incomplete(Pattern::Any),
+        };
+    };
+    if heads
+        .iter()
+        .any(|pat| std::mem::discriminant(**pat) != std::mem::discriminant(**first))
+    {
+        return Signature::Incomplete(Pattern::Any);
+    }
+    match first {
+        Pat::Tag(..) => Signature::Complete(tags(&heads, typ)),
+        Pat::Number(_) => {
+            let unused = (0..)
+                .map(f64::from)
+                .find(|n| !heads.contains(&&&Pat::Number(*n)))
+                .expect("only finitely many numbers are matched");
+            Signature::Incomplete(Ctor::Number(unused).example(Vec::new()))
+        }
+        Pat::Str(_) => {
+            let unused = (0..)
+                .map(|len| "a".repeat(len))
+                .find(|text| !heads.contains(&&&Pat::Str(text.clone())))
+                .expect("only finitely many strings are matched");
+            Signature::Incomplete(Ctor::Str(unused).example(Vec::new()))
+        }
+        // Arrays longer than any array pattern without a spread all match the same cases
+        Pat::Array(..) => {
+            let longest = heads
+                .iter()
+                .map(|pat| match pat {
+                    Pat::Array(items, None) => items.len(),
+                    Pat::Array(before, Some(after)) => before.len() + after.len(),
+                    _ => 0,
+                })
+                .max()
+                .unwrap_or_default();
+            Signature::Complete((0..=longest + 1).map(Ctor::Array).collect())
+        }
+        Pat::Record(_) => {
+            let mut keys = Vec::new();
+            for pat in &heads {
+                if let Pat::Record(fields) = pat {
+                    for (key, _) in fields {
+                        if !keys.contains(key) {
+                            keys.push(key.clone());
+                        }
+                    }
+                }
+            }
+            Signature::Complete(vec![Ctor::Record(keys)])
+        }
+        Pat::Wild | Pat::Opaque => unreachable!("wildcards were filtered out"),
+    }
+}
+
+/// Every variant a column may be: those named in it, those of its type, and both booleans if
+/// either is named
+fn tags(heads: &[&&Pat], typ: &Type) -> Vec<Ctor> {
+    let mut tags = Vec::new();
+    let mut add = |ctor: Ctor| {
+        if !tags.contains(&ctor) {
+            tags.push(ctor);
+        }
+    };
+    for pat in heads {
+        if let Pat::Tag(tag, args) = pat {
+            add(Ctor::Tag(tag.clone(), args.len()));
+        }
+    }
+    if let Type::Sum(row) = typ {
+        let mut variants = row.iter().collect::<Vec<_>>();
+        variants.sort_by_key(|(tag, _)| *tag);
+        for (tag, args) in variants {
+            add(Ctor::Tag(tag.clone(), args.len()));
+        }
+    }
+    for (tag, other) in [("true", "false"), ("false", "true")] {
+        if heads
+            .iter()
+            .any(|pat| matches!(pat, Pat::Tag(t, args) if t == tag && args.is_empty()))
+        {
+            add(Ctor::Tag(other.to_string(), 0));
+        }
+    }
+    tags
+}
+
+// MATRICES ====================================================================
+
+/// Whether `row` matches some value which none of `rows` do. `types` are the types of the
+/// columns, or [`Type::Hole`] if they aren't known.
+fn is_useful(rows: &[Vec<Pat>], row: &[Pat], types: &[Type]) -> bool {
+    let Some((head, rest)) = row.split_first() else {
+        return rows.is_empty();
+    };
+    let useful_for = |ctor: &Ctor| {
+        let specialised = rows
+            .iter()
+            .filter_map(|r| specialise(r, ctor))
+            .collect::<Vec<_>>();
+        let row = specialise(&[[head.clone()].as_slice(), rest].concat(), ctor)
+            .or_else(|| specialise(&[[Pat::Wild].as_slice(), rest].concat(), ctor))
+            .expect("a wildcard matches every shape");
+        let types = [ctor.arg_types(&types[0]), types[1..].to_vec()].concat();
+        is_useful(&specialised, &row, &types)
+    };
+    let column = rows
+        .iter()
+        .filter_map(|r| r.first())
+        .chain([head])
+        .collect::<Vec<_>>();
+    match head {
+        // A pattern which isn't relied on is useful if a wildcard would be
+        Pat::Wild | Pat::Opaque => match signature(&column, &types[0]) {
+            Signature::Complete(ctors) => ctors.iter().any(useful_for),
+            Signature::Incomplete(_) => is_useful(&default_rows(rows), rest, &types[1..]),
+        },
+        Pat::Array(_, Some(_)) | Pat::Record(_) => match signature(&column, &types[0]) {
+            Signature::Complete(ctors) => ctors
+                .iter()
+                .filter(|ctor| specialise(row, ctor).is_some())
+                .any(useful_for),
+            // Patterns of other kinds in the column can't match these values
+            Signature::Incomplete(_) => true,
+        },
+        Pat::Tag(tag, args) => useful_for(&Ctor::Tag(tag.clone(), args.len())),
+        Pat::Number(n) => useful_for(&Ctor::Number(*n)),
+        Pat::Str(text) => useful_for(&Ctor::Str(text.clone())),
+        Pat::Array(items, None) => useful_for(&Ctor::Array(items.len())),
+    }
+}
+
+/// An example of values, one for each column, which none of `rows` match, if there is one
+fn missing(rows: &[Vec<Pat>], types: &[Type]) -> Option<Vec<Pattern>> {
+    let Some(typ) = types.first() else {
+        return rows.is_empty().then(Vec::new);
+    };
+    let column = rows.iter().filter_map(|r| r.first()).collect::<Vec<_>>();
+    match signature(&column, typ) {
+        Signature::Complete(ctors) => ctors.iter().find_map(|ctor| {
+            let specialised = rows
+                .iter()
+                .filter_map(|r| specialise(r, ctor))
+                .collect::<Vec<_>>();
+            let types = [ctor.arg_types(typ), types[1..].to_vec()].concat();
+            let mut example = missing(&specialised, &types)?;
+            let rest = example.split_off(ctor.arity());
+            Some([vec![ctor.example(example)], rest].concat())
+        }),
+        Signature::Incomplete(head) => {
+            let rest = missing(&default_rows(rows), &types[1..])?;
+            Some([vec![head], rest].concat())
+        }
+    }
+}
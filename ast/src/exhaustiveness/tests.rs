@@ -0,0 +1,290 @@
+use super::{check_expr, check_module, WarningKind};
+use crate::{
+    expr::{fixtures::var, Literal, StringPart},
+    module,
+    pretty::Pretty,
+    Decl, Expr, Module, Pattern, Type,
+};
+
+fn tag(name: &str, args: Vec<Pattern>) -> Pattern {
+    Pattern::Literal(Literal::Enum(name.to_string(), args))
+}
+fn num(n: f64) -> Pattern {
+    Pattern::Literal(Literal::Number(n))
+}
+fn string(text: &str) -> Pattern {
+    Pattern::Literal(Literal::LStr(vec![StringPart::Text(text.to_string())]))
+}
+fn array(items: Vec<Pattern>) -> Pattern {
+    Pattern::Literal(Literal::Array(items))
+}
+fn record(fields: Vec<(&str, Pattern)>) -> Pattern {
+    Pattern::Literal(Literal::Record(
+        fields
+            .into_iter()
+            .map(|(key, pattern)| (key.to_string(), pattern))
+            .collect(),
+    ))
+}
+fn spread(name: &str) -> Pattern {
+    Pattern::Spread(name.to_string())
+}
+
+/// The warnings for a switch over a subject of type `typ`, with the example of a value it
+/// doesn't match printed
+fn check(typ: Type, cases: Vec<Pattern>) -> Vec<String> {
+    check_guarded(typ, cases.into_iter().map(|case| (case, false)).collect())
+}
+fn check_guarded(typ: Type, cases: Vec<(Pattern, bool)>) -> Vec<String> {
+    let mut subject = Expr::var("x");
+    subject.meta_mut().set_type(typ);
+    let switch = Expr::switch(
+        subject,
+        cases
+            .into_iter()
+            .map(|(pattern, guarded)| (pattern, guarded.then(|| Expr::var("c")), Expr::literal(1)))
+            .collect(),
+    );
+    check_expr(&switch)
+        .iter()
+        .map(|warning| match warning.kind() {
+            WarningKind::NonExhaustive(example) => format!("missing {}", example.pretty(80)),
+            WarningKind::Unreachable(index) => format!("unreachable {}", index),
+        })
+        .collect()
+}
+
+#[test]
+fn variants() {
+    let maybe = Type::sum([("just", vec![Type::var("a")]), ("nothing", vec![])]);
+    assert_eq!(
+        check(maybe.clone(), vec![tag("just", vec![var("a")])]),
+        ["missing #nothing"]
+    );
+    assert_eq!(
+        check(maybe.clone(), vec![tag("nothing", vec![])]),
+        ["missing #just _"]
+    );
+    assert!(check(
+        maybe,
+        vec![tag("nothing", vec![]), tag("just", vec![Pattern::Any])]
+    )
+    .is_empty());
+    // Without a type, the variants named by the cases are the only ones
+    assert!(check(Type::Hole, vec![tag("a", vec![]), tag("b", vec![])]).is_empty());
+}
+
+#[test]
+fn nested_variants() {
+    let result = Type::sum([("ok", vec![Type::boolean()]), ("err", vec![Type::string()])]);
+    assert_eq!(
+        check(
+            result.clone(),
+            vec![
+                tag("ok", vec![tag("true", vec![])]),
+                tag("err", vec![var("e")])
+            ]
+        ),
+        ["missing #ok (#false)"]
+    );
+    assert_eq!(
+        check(
+            result,
+            vec![
+                tag("ok", vec![tag("true", vec![])]),
+                tag("ok", vec![tag("false", vec![])]),
+                tag("err", vec![string("")]),
+            ]
+        ),
+        ["missing #err \"a\""]
+    );
+}
+
+#[test]
+fn booleans() {
+    // The other boolean is missing even when the type isn't known
+    assert_eq!(
+        check(Type::Hole, vec![tag("true", vec![])]),
+        ["missing #false"]
+    );
+    let both = |a: &str, b: &str| record(vec![("a", tag(a, vec![])), ("b", tag(b, vec![]))]);
+    assert!(check(
+        Type::Hole,
+        vec![
+            both("true", "true"),
+            record(vec![("a", tag("false", vec![]))]),
+            record(vec![("b", tag("false", vec![])), ("c", Pattern::Any)]),
+        ]
+    )
+    .is_empty());
+    assert_eq!(
+        check(
+            Type::Hole,
+            vec![both("true", "true"), both("false", "false")]
+        ),
+        ["missing { a: #true, b: #false }"]
+    );
+}
+
+#[test]
+fn numbers_and_strings() {
+    assert_eq!(check(Type::num(), vec![num(0.0), num(1.0)]), ["missing 2"]);
+    assert!(check(Type::num(), vec![num(0.0), var("n")]).is_empty());
+    assert_eq!(check(Type::string(), vec![string("")]), ["missing \"a\""]);
+    // Numbers and strings can't be the same value
+    assert_eq!(check(Type::Hole, vec![num(0.0), string("")]), ["missing _"]);
+}
+
+#[test]
+fn arrays() {
+    assert!(check(
+        Type::Hole,
+        vec![array(vec![]), array(vec![var("x"), spread("xs")])]
+    )
+    .is_empty());
+    assert_eq!(
+        check(Type::Hole, vec![array(vec![]), array(vec![var("x")])]),
+        ["missing [_, _]"]
+    );
+    assert_eq!(
+        check(Type::Hole, vec![array(vec![spread("init"), var("last")])]),
+        ["missing []"]
+    );
+    assert_eq!(
+        check(
+            Type::arr(Type::boolean()),
+            vec![
+                array(vec![]),
+                array(vec![tag("true", vec![]), spread("rest")]),
+            ]
+        ),
+        ["missing [#false]"]
+    );
+}
+
+#[test]
+fn unreachable_cases() {
+    assert_eq!(
+        check(Type::Hole, vec![var("x"), tag("a", vec![])]),
+        ["unreachable 1"]
+    );
+    assert_eq!(
+        check(
+            Type::Hole,
+            vec![
+                tag("just", vec![var("x")]),
+                tag("just", vec![num(1.0)]),
+                tag("nothing", vec![]),
+            ]
+        ),
+        ["unreachable 1"]
+    );
+    assert_eq!(
+        check(
+            Type::Hole,
+            vec![
+                array(vec![spread("xs")]),
+                array(vec![]),
+                array(vec![var("x")])
+            ]
+        ),
+        ["unreachable 1", "unreachable 2"]
+    );
+    assert_eq!(
+        check(
+            Type::Hole,
+            vec![
+                record(vec![("a", var("a"))]),
+                record(vec![("a", num(1.0)), ("b", var("b"))])
+            ]
+        ),
+        ["unreachable 1"]
+    );
+}
+
+#[test]
+fn guards_are_conservative() {
+    // A guarded case may not match, so it covers nothing
+    assert_eq!(
+        check_guarded(
+            Type::Hole,
+            vec![(tag("true", vec![]), true), (tag("false", vec![]), false)]
+        ),
+        ["missing #true"]
+    );
+    // But it is still unreachable after a case which matches everything
+    assert_eq!(
+        check_guarded(Type::Hole, vec![(var("x"), false), (var("y"), true)]),
+        ["unreachable 1"]
+    );
+}
+
+#[test]
+fn type_patterns_are_conservative() {
+    let number = || Pattern::Type("Number".to_string(), Box::new(var("n")));
+    assert_eq!(check(Type::num(), vec![number()]), ["missing _"]);
+    assert!(check(Type::Hole, vec![number(), var("x")]).is_empty());
+    assert_eq!(
+        check(Type::Hole, vec![var("x"), number()]),
+        ["unreachable 1"]
+    );
+}
+
+#[test]
+fn uses_inferred_types() {
+    // The variants `x` may be are only known once the module has been type checked
+    let typ = Type::fun(
+        [Type::sum([("a", Vec::<Type>::new()), ("b", Vec::new())])],
+        Type::num(),
+    );
+    let mut module = Module::new(
+        module::Meta::default(),
+        std::iter::empty(),
+        std::iter::once(Decl::local(
+            Some(typ),
+            ((1, 1), (1, 1)),
+            false,
+            "f",
+            Expr::lambda(
+                vec![var("x")],
+                Expr::switch(
+                    Expr::var("x"),
+                    vec![(tag("a", vec![]), None, Expr::literal(1))],
+                )
+                .with_span(((1, 9), (1, 36))),
+            ),
+        )),
+    );
+    assert!(check_module(&module).is_empty());
+    crate::infer::check_module(&mut module).unwrap();
+    assert_eq!(
+        check_module(&module)
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        ["1:9: switch does not match every value, e.g. `#b`"]
+    );
+}
+
+#[test]
+fn nested_switches() {
+    let inner = Expr::switch(
+        Expr::var("y"),
+        vec![
+            (Pattern::Any, None, Expr::literal(1)),
+            (
+                Pattern::Any,
+                None,
+                Expr::literal(2).with_span(((3, 10), (3, 11))),
+            ),
+        ],
+    );
+    let outer = Expr::switch(Expr::var("x"), vec![(Pattern::Any, None, inner)]);
+    assert_eq!(
+        check_expr(&outer)
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        ["3:10: unreachable case, earlier cases match every value it does"]
+    );
+}
@@ -0,0 +1,8 @@
+//! Helpers for building expressions in tests
+
+use super::Pattern;
+
+/// A pattern binding `name`
+pub(crate) fn var(name: &str) -> Pattern {
+    Pattern::Var(name.to_string())
+}
@@ -88,7 +88,7 @@ impl<T> StringParts<T> for Vec<StringPart<T>> {
     }
     fn as_simple(&self) -> Option<String> {
         if self.len() == 1 {
-            (&self[0]).text()
+            self[0].text()
         } else {
             None
         }
@@ -1,4 +1,3 @@
-use array_init::array_init;
 use ren_json_derive::RenJson;
 use serde::{Deserialize, Serialize};
 
@@ -11,11 +10,13 @@ pub mod pattern;
 // mod pattern_expanded;
 // pub use pattern_expanded::pattern;
 #[cfg(test)]
+pub(crate) mod fixtures;
+#[cfg(test)]
 mod tests;
 
 pub use literal::Literal;
 pub use literal::StringPart;
-pub use operator::Operator;
+pub use operator::{Associativity, Fixities, Fixity, Operator};
 pub use pattern::Pattern;
 
 #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
@@ -35,12 +36,22 @@ impl Meta {
     {
         self.span = span.into();
     }
+    pub fn get_span(&self) -> Span {
+        self.span
+    }
+    pub fn get_type(&self) -> &Type {
+        &self.typ
+    }
+    pub fn set_type(&mut self, typ: Type) {
+        self.typ = typ;
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, RenJson)]
 pub enum Expr {
     Access(Meta, Box<Expr>, String),
     Annotated(Meta, Box<Expr>, Type),
+    Assert(Meta, Box<Expr>, Box<Expr>),
     Binop(Meta, Box<Expr>, Operator, Box<Expr>),
     Call(Meta, Box<Expr>, Vec<Expr>),
     If(Meta, Box<Expr>, Box<Expr>, Box<Expr>),
@@ -51,8 +62,22 @@ pub enum Expr {
     Placeholder(Meta),
     Scoped(Meta, Vec<String>, String),
     Switch(Meta, Box<Expr>, Vec<(Pattern, Option<Expr>, Expr)>),
+    /// A copy of a record with some of its fields replaced, e.g. `{ rec | a: 1 }`. Each field
+    /// has its own span, covering both its name and value.
+    Update(Meta, Box<Expr>, Vec<(Span, String, Expr)>),
     Var(Meta, String),
 }
+/// The name of the `i`th parameter of a lambda made by [`Expr::replace_placeholders`]
+pub fn placeholder_name(i: usize) -> String {
+    format!("_{}", i)
+}
+/// Whether a variable is a parameter made by [`Expr::replace_placeholders`]. Identifiers in
+/// Ren source can't start with `_`, so no other variable has a name like this.
+pub fn is_placeholder_name(name: &str) -> bool {
+    name.strip_prefix('_')
+        .is_some_and(|i| !i.is_empty() && i.chars().all(|c| c.is_ascii_digit()))
+}
+
 impl<T: Into<Literal<Expr>>> From<T> for Expr {
     fn from(l: T) -> Self {
         Self::Literal(Meta::default(), l.into())
@@ -66,26 +91,29 @@ impl Default for Expr {
 impl crate::ASTLiteralType for Expr {}
 
 impl Expr {
-    // fn meta(&self) -> Meta {
-    //     match self {
-    //         Expr::Access(meta, _, _) => meta.clone(),
-    //         Expr::Annotated(meta, _, _) => meta.clone(),
-    //         Expr::Binop(meta, _, _, _) => meta.clone(),
-    //         Expr::Call(meta, _, _) => meta.clone(),
-    //         Expr::If(meta, _, _, _) => meta.clone(),
-    //         Expr::Lambda(meta, _, _) => meta.clone(),
-    //         Expr::Let(meta, _, _, _) => meta.clone(),
-    //         Expr::Literal(meta, _) => meta.clone(),
-    //         Expr::Placeholder(meta) => meta.clone(),
-    //         Expr::Scoped(meta, _, _) => meta.clone(),
-    //         Expr::Switch(meta, _, _) => meta.clone(),
-    //         Expr::Var(meta, _) => meta.clone(),
-    //     }
-    // }
-    fn meta_mut(&mut self) -> &mut Meta {
+    pub fn meta(&self) -> &Meta {
+        match self {
+            Expr::Access(meta, _, _) => meta,
+            Expr::Annotated(meta, _, _) => meta,
+            Expr::Assert(meta, _, _) => meta,
+            Expr::Binop(meta, _, _, _) => meta,
+            Expr::Call(meta, _, _) => meta,
+            Expr::If(meta, _, _, _) => meta,
+            Expr::Lambda(meta, _, _) => meta,
+            Expr::Let(meta, _, _, _) => meta,
+            Expr::Literal(meta, _) => meta,
+            Expr::Placeholder(meta) => meta,
+            Expr::Scoped(meta, _, _) => meta,
+            Expr::Switch(meta, _, _) => meta,
+            Expr::Update(meta, _, _) => meta,
+            Expr::Var(meta, _) => meta,
+        }
+    }
+    pub(crate) fn meta_mut(&mut self) -> &mut Meta {
         match self {
             Expr::Access(meta, _, _) => meta,
             Expr::Annotated(meta, _, _) => meta,
+            Expr::Assert(meta, _, _) => meta,
             Expr::Binop(meta, _, _, _) => meta,
             Expr::Call(meta, _, _) => meta,
             Expr::If(meta, _, _, _) => meta,
@@ -95,6 +123,7 @@ impl Expr {
             Expr::Placeholder(meta) => meta,
             Expr::Scoped(meta, _, _) => meta,
             Expr::Switch(meta, _, _) => meta,
+            Expr::Update(meta, _, _) => meta,
             Expr::Var(meta, _) => meta,
         }
     }
@@ -121,110 +150,63 @@ impl Expr {
     }
     //TODO fn references(&self) -> ...
     //TODO fn shadows(&self) -> ...
-    fn is_placeholder(&self) -> bool {
-        if let Self::Placeholder(_) = self {
-            true
-        } else {
-            false
-        }
+    pub fn is_placeholder(&self) -> bool {
+        matches!(self, Self::Placeholder(_))
     }
-    #[allow(dead_code)] //XXX
-    fn replace_placeholders(self) -> Self {
-        use Expr::*;
-        /// Creates a valid JavaScript variable name from a placholder.
-        fn name(i: usize) -> String {
-            format!("$temp{}", i)
-        }
-        fn map_placeholders(exprs: Vec<Expr>, expr_factory: &dyn Fn(Vec<Expr>) -> Expr) -> Expr {
-            let (names, args) = exprs.into_iter().enumerate().fold(
-                (Vec::new(), Vec::new()),
-                |(mut names, mut args), (i, e)| {
-                    args.push(if let Placeholder(meta) = e {
-                        let name = name(i);
-                        names.push(Pattern::Var(name.clone()));
-                        Var(meta, name)
-                    } else {
-                        e
-                    });
-                    (names, args)
-                },
-            );
-            if names.len() > 0 {
-                Expr::lambda(names, expr_factory(args))
-            } else {
-                expr_factory(args)
+    /// Replace the placeholders which are direct children of this expression with the
+    /// parameters of a lambda, in the order they appear. This is how operator sections and
+    /// partial application are written, e.g. `_ + 1` becomes `fun _0 -> _0 + 1` and
+    /// `f _ x _` becomes `fun _0 _1 -> f _0 x _1`.
+    ///
+    /// Placeholders may stand in for the operands of an operator, the function or arguments of
+    /// a call, the parts of an `if`, the subject of a `switch`, the record being accessed or
+    /// updated, the values of an update, and the items, fields, arguments and interpolated
+    /// values of a literal. A lone placeholder has nothing to abstract over, so is left alone.
+    ///
+    /// This is run on every expression as it is lowered from the syntax tree, from the inside
+    /// out, so each placeholder belongs to the innermost expression containing it: `f (_ + 1)`
+    /// is `f (fun _0 -> _0 + 1)`. The parameters can't be written in Ren source, so they never
+    /// capture or shadow a variable from the source, and as each lambda only refers to its own
+    /// parameters, nested lambdas can reuse the same names.
+    pub fn replace_placeholders(mut self) -> Self {
+        let mut params = Vec::new();
+        for child in self.placeholder_slots() {
+            if let Self::Placeholder(meta) = child {
+                let name = placeholder_name(params.len());
+                *child = Self::Var(std::mem::take(meta), name.clone());
+                params.push(Pattern::Var(name));
             }
         }
-        fn map_arr<const N: usize>(
-            exprs: [Expr; N],
-            expr_factory: &dyn Fn([Expr; N]) -> Expr,
-        ) -> Expr {
-            let (names, args) = IntoIterator::into_iter(exprs).enumerate().fold(
-                (Vec::new(), array_init(|_| Expr::default())),
-                |(mut names, mut args), (i, e)| {
-                    args[i] = if let Placeholder(meta) = e {
-                        let name = name(i);
-                        names.push(Pattern::Var(name.clone()));
-                        Var(meta, name)
-                    } else {
-                        e
-                    };
-                    (names, args)
-                },
-            );
-            if names.len() > 0 {
-                Expr::lambda(names, expr_factory(args))
-            } else {
-                expr_factory(args)
-            }
-        }
-        fn map_positional<const N: usize>(
-            exprs: [(usize, Expr); N],
-            expr_factory: &dyn Fn([Expr; N]) -> Expr,
-        ) -> Expr {
-            let (names, args) = IntoIterator::into_iter(exprs).enumerate().fold(
-                (Vec::new(), array_init(|_| Expr::default())),
-                |(mut names, mut args), (arg_index, (i, e))| {
-                    args[arg_index] = if let Placeholder(meta) = e {
-                        let name = name(i);
-                        names.push(Pattern::Var(name.clone()));
-                        Var(meta, name)
-                    } else {
-                        e
-                    };
-                    (names, args)
-                },
-            );
-            if names.len() > 0 {
-                Expr::lambda(names, expr_factory(args))
-            } else {
-                expr_factory(args)
-            }
+        if params.is_empty() {
+            self
+        } else {
+            Self::Lambda(self.meta().clone(), params, Box::new(self))
         }
+    }
+    /// The direct children of this expression which a placeholder may stand in for
+    fn placeholder_slots(&mut self) -> Vec<&mut Expr> {
         match self {
-            Access(meta, rec, key) if rec.is_placeholder() => Lambda(
-                meta,
-                vec![Pattern::Var(name(0))],
-                Box::new(Expr::access(Expr::var(name(0)), key)),
-            ),
-            Binop(_, lhs, op, rhs) => map_positional([(0, *lhs), (2, *rhs)], &|[lhs, rhs]| {
-                Expr::binop(lhs, op, rhs)
-            }),
-            Call(_, fun, args) => map_placeholders([vec![*fun], args].concat(), &|exprs| {
-                let mut iter = exprs.into_iter();
-                Expr::apply_many(iter.next().unwrap(), iter)
-            }),
-            If(_, cond, then_, else_) => {
-                map_arr([*cond, *then_, *else_], &|[cond, then_, else_]| {
-                    Expr::conditional(cond, then_, else_)
-                })
+            Self::Access(_, rec, _) | Self::Switch(_, rec, _) => vec![rec],
+            Self::Binop(_, lhs, _, rhs) => vec![lhs, rhs],
+            Self::Call(_, fun, args) => std::iter::once(fun.as_mut()).chain(args).collect(),
+            Self::If(_, cond, then_, else_) => vec![cond, then_, else_],
+            Self::Literal(_, Literal::Array(items) | Literal::Enum(_, items)) => {
+                items.iter_mut().collect()
+            }
+            Self::Literal(_, Literal::Record(fields)) => {
+                fields.iter_mut().map(|(_, value)| value).collect()
             }
-            Switch(meta, expr_, cases) if expr_.is_placeholder() => Lambda(
-                meta,
-                vec![Pattern::Var(name(0))],
-                Box::new(Expr::switch(Expr::var(name(0)), cases)),
-            ),
-            _ => self,
+            Self::Literal(_, Literal::LStr(parts)) => parts
+                .iter_mut()
+                .filter_map(|part| match part {
+                    StringPart::Value(value) => Some(value),
+                    StringPart::Text(_) => None,
+                })
+                .collect(),
+            Self::Update(_, rec, fields) => std::iter::once(rec.as_mut())
+                .chain(fields.iter_mut().map(|(_, _, value)| value))
+                .collect(),
+            _ => Vec::new(),
         }
     }
 
@@ -232,8 +214,13 @@ impl Expr {
     pub fn access<S: ToString>(obj: Expr, key: S) -> Self {
         Self::Access(Meta::default(), Box::new(obj), key.to_string())
     }
-    pub fn annotated() -> Self {
-        todo!()
+    /// An expression which must have the given type, e.g. `(x : Number)`
+    pub fn annotated(expr: Self, typ: Type) -> Self {
+        Self::Annotated(Meta::default(), Box::new(expr), typ)
+    }
+    /// Check that `condition` holds before evaluating `body`, e.g. `assert x > 0; x`
+    pub fn assert(condition: Self, body: Self) -> Self {
+        Self::Assert(Meta::default(), Box::new(condition), Box::new(body))
     }
     pub fn binop(lhs: Self, op: Operator, rhs: Self) -> Self {
         Self::Binop(Meta::default(), Box::new(lhs), op, Box::new(rhs))
@@ -291,6 +278,21 @@ impl Expr {
     pub fn switch(expr: Expr, arms: Vec<(Pattern, Option<Expr>, Expr)>) -> Self {
         Self::Switch(Meta::default(), Box::new(expr), arms)
     }
+    pub fn update<I, S, K>(rec: Expr, fields: I) -> Self
+    where
+        I: IntoIterator<Item = (S, K, Expr)>,
+        S: Into<Span>,
+        K: ToString,
+    {
+        Self::Update(
+            Meta::default(),
+            Box::new(rec),
+            fields
+                .into_iter()
+                .map(|(span, key, value)| (span.into(), key.to_string(), value))
+                .collect(),
+        )
+    }
     pub fn var<S: ToString>(name: S) -> Self {
         Self::Var(Meta::default(), name.to_string())
     }
@@ -1,67 +1,176 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Which way a chain of the same infix operator groups, e.g. `a - b - c` is `(a - b) - c`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Associativity {
+    Left,
+    Right,
+}
+
+/// How tightly an infix operator binds, from 0 to 9, and which way chains of it group. These
+/// are given by fixity declarations, e.g. `infixl 6 <+> = add`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fixity {
+    pub associativity: Associativity,
+    pub precedence: u8,
+}
+impl Fixity {
+    pub const MAX_PRECEDENCE: u8 = 9;
+    /// The fixity of an operator without a fixity declaration
+    pub const DEFAULT: Fixity = Fixity::new(Associativity::Left, Self::MAX_PRECEDENCE);
+
+    pub const fn new(associativity: Associativity, precedence: u8) -> Self {
+        Self {
+            associativity,
+            precedence,
+        }
+    }
+    /// The left and right binding powers of an operator with this fixity. An operator with a
+    /// lower right binding power than left binding power is right associative.
+    pub fn binding_power(&self) -> (u8, u8) {
+        let power = 2 * self.precedence + 1;
+        match self.associativity {
+            Associativity::Left => (power, power + 1),
+            Associativity::Right => (power + 1, power),
+        }
+    }
+}
+
+/// The fixities of user-defined infix operators. Operators without a fixity declaration have
+/// [`Fixity::DEFAULT`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Fixities(HashMap<String, Fixity>);
+
+impl Fixities {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn get(&self, op: &str) -> Fixity {
+        self.declared(op).unwrap_or(Fixity::DEFAULT)
+    }
+    /// The fixity of an operator, if one has been declared
+    pub fn declared(&self, op: &str) -> Option<Fixity> {
+        self.0.get(op).copied()
+    }
+    pub fn insert<S: Into<String>>(&mut self, op: S, fixity: Fixity) {
+        self.0.insert(op.into(), fixity);
+    }
+    /// Add every fixity from `other`, replacing any already declared for the same operator
+    pub fn extend(&mut self, other: &Fixities) {
+        self.0
+            .extend(other.0.iter().map(|(op, fixity)| (op.clone(), *fixity)));
+    }
+}
+impl<S: Into<String>> FromIterator<(S, Fixity)> for Fixities {
+    fn from_iter<I: IntoIterator<Item = (S, Fixity)>>(iter: I) -> Self {
+        Self(
+            iter.into_iter()
+                .map(|(op, fixity)| (op.into(), fixity))
+                .collect(),
+        )
+    }
+}
+
+/// The characters which user-defined operators are made of. Operators may not be spelled the
+/// same as the symbols `:`, `.`, `..`, `->` and `=`, nor as a built-in operator such as `::`.
+pub const OPERATOR_CHARS: &str = "!$%&*+-./:<=>^|~";
+
+/// Whether a name is made of symbols, so is written as an infix operator, e.g. `<+>`
+pub fn is_operator_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| OPERATOR_CHARS.contains(c))
+}
+
+/// Declare every built-in infix operator from a single table of its name in JSON, the symbol
+/// it is written as, and its fixity.
 macro_rules! make_operator {
-    ($(($op:ident, $name:literal, $symbol:literal)),+ $(,)?) => {
-        // static OPERATORS: &'static [(Operator, &'static str, &'static str)] = &[$((Operator::$op, $name, $symbol)),+];
-        #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+    ($(($op:ident, $name:literal, $symbol:literal, $assoc:ident, $prec:literal)),+ $(,)?) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
         pub enum Operator {$(
-            /// $symbol
+            #[doc = concat!("`", $symbol, "`")]
             $op,
         )+}
         impl Operator {
+            /// Every operator, in the order of the table
+            pub const ALL: &'static [Operator] = &[$(Self::$op),+];
+            const NAMES: &'static [&'static str] = &[$($name),+];
+
             pub fn from_symbol(symbol: &str) -> Option<Self> {
                 match symbol {
                     $($symbol => Some(Self::$op),)+
                     _ => None,
                 }
             }
+            pub fn symbol(&self) -> &'static str {
+                match self {
+                    $(Self::$op => $symbol,)+
+                }
+            }
+            pub fn from_name(name: &str) -> Option<Self> {
+                match name {
+                    $($name => Some(Self::$op),)+
+                    _ => None,
+                }
+            }
+            /// The name of the operator in JSON
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Self::$op => $name,)+
+                }
+            }
+            pub fn fixity(&self) -> Fixity {
+                match self {
+                    $(Self::$op => Fixity::new(Associativity::$assoc, $prec),)+
+                }
+            }
         }
     };
 }
-make_operator![
-    (Add, "add", "+"),
-    (And, "and", "and"),
-    (Concat, "concat", "<>"),
-    (Cons, "cons", "::"),
-    (Div, "div", "/"),
-    (Eq, "eq", "=="),
-    (Gte, "gte", ">="),
-    (Gt, "gt", ">"),
-    (Lte, "lte", "<="),
-    (Lt, "lt", "<"),
-    (Mod, "mod", "%"),
-    (Mul, "mul", "*"),
-    (Neq, "neq", "!="),
-    (Or, "or", "or"),
-    (Pipe, "pipe", "|>"),
-    (Sub, "sub", "-"),
-];
+/// Pass the table of built-in operators to the macro `$callback`, one
+/// `(variant, name, symbol, associativity, precedence)` entry per operator, so that other
+/// crates can be built from it too, e.g. the lexer's operator tokens
+#[macro_export]
+macro_rules! operator_table {
+    ($callback:ident) => {
+        $callback![
+            (Add, "Add", "+", Left, 6),
+            (And, "And", "&", Right, 3),
+            (Concat, "Concat", "<>", Right, 5),
+            (Cons, "Cons", "::", Right, 5),
+            (Div, "Div", "/", Left, 7),
+            (Eq, "Eq", "==", Left, 4),
+            (Gte, "Gte", ">=", Left, 4),
+            (Gt, "Gt", ">", Left, 4),
+            (Lte, "Lte", "<=", Left, 4),
+            (Lt, "Lt", "<", Left, 4),
+            (Mod, "Mod", "%", Left, 7),
+            (Mul, "Mul", "*", Left, 7),
+            (Neq, "Neq", "!=", Left, 4),
+            (Or, "Or", "|", Right, 2),
+            (Pipe, "Pipe", "|>", Left, 1),
+            (Pow, "Pow", "^", Right, 8),
+            (Sub, "Sub", "-", Left, 6),
+        ];
+    };
+}
+operator_table!(make_operator);
 
-// impl Operator {
-// fn from_name(name: &str) -> Option<Self> {
-//     OPERATORS_FULL
-//         .iter()
-//         .find(|(_, op_name, _)| *op_name == name)
-//         .map(|(op, ..)| *op)
-// }
-// pub fn from_symbol(symbol: &str) -> Option<Self> {
-//     OPERATORS_FULL
-//         .iter()
-//         .find(|(.., sym)| *sym == symbol)
-//         .map(|(op, ..)| *op)
-// }
-// fn find_data(&self) -> [&'static str; 2] {
-//     OPERATORS_FULL
-//         .iter()
-//         .find(|(op, ..)| op == self)
-//         .map(|(_, name, sym)| [*name, *sym])
-//         .expect(&format!(
-//             "OPERATOR {:?} is not in {:?}",
-//             self, OPERATORS_FULL
-//         ))
-// }
-// fn name(&self) -> &'static str {
-//     self.find_data()[0]
-// }
-// fn symbol(&self) -> &'static str {
-//     self.find_data()[1]
-// }
-// }
+impl Operator {
+    /// The left and right binding powers of the operator when used infix
+    pub fn infix_binding_power(&self) -> (u8, u8) {
+        self.fixity().binding_power()
+    }
+}
+
+impl Serialize for Operator {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+impl<'de> Deserialize<'de> for Operator {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        Self::from_name(&name).ok_or_else(|| serde::de::Error::unknown_variant(&name, Self::NAMES))
+    }
+}
@@ -8,6 +8,8 @@ use super::literal::Literal;
 pub enum Pattern {
     Any,
     Literal(Literal<Pattern>),
+    /// `..name`, binding the remaining items of an array, or the fields of a record which are
+    /// not matched by its other fields. In a record it is keyed by its own name.
     Spread(String),
     Type(String, Box<Pattern>),
     Var(String),
@@ -29,18 +31,14 @@ impl Pattern {
         }
     }
 
-    fn bindings(&self) -> HashSet<String> {
+    /// Every variable name bound by a pattern, including those bound by nested patterns
+    pub fn bindings(&self) -> HashSet<String> {
         match self {
-            Self::Literal(Literal::Array(items)) => {
-                items.into_iter().flat_map(Self::bindings).collect()
+            Self::Literal(Literal::Array(items)) => items.iter().flat_map(Self::bindings).collect(),
+            Self::Literal(Literal::Enum(_, args)) => args.iter().flat_map(Self::bindings).collect(),
+            Self::Literal(Literal::Record(fields)) => {
+                fields.iter().flat_map(|(_, pat)| pat.bindings()).collect()
             }
-            Self::Literal(Literal::Enum(_, args)) => {
-                args.into_iter().flat_map(Self::bindings).collect()
-            }
-            Self::Literal(Literal::Record(fields)) => fields
-                .into_iter()
-                .flat_map(|(_, pat)| pat.bindings())
-                .collect(),
             Self::Type(_, pat) => pat.bindings(),
             Self::Spread(name) | Self::Var(name) => {
                 let mut set = HashSet::new();
@@ -1,14 +1,25 @@
-use super::{Expr, Literal, Operator, Pattern};
+use super::{Expr, Literal, Operator, Pattern, StringPart};
 
 mod serde;
 
+#[test]
+fn operators_round_trip() {
+    for &op in Operator::ALL {
+        assert_eq!(Operator::from_symbol(op.symbol()), Some(op));
+        assert_eq!(Operator::from_name(op.name()), Some(op));
+        let json = serde_json::to_string(&op).unwrap();
+        assert_eq!(json, format!("{:?}", op.name()));
+        assert_eq!(serde_json::from_str::<Operator>(&json).unwrap(), op);
+    }
+}
+
 mod expand_placeholders {
     use super::*;
 
     fn check(body_factory: fn(&mut dyn FnMut(u8) -> Expr) -> Expr) {
         let mut vars = Vec::new();
         let mut var_f = |index: u8| {
-            let var = format!("$temp{}", index);
+            let var = format!("_{}", index);
             vars.push(Pattern::Var(var.clone()));
             Expr::var(var)
         };
@@ -29,11 +40,11 @@ mod expand_placeholders {
     }
     #[test]
     fn binop_rhs() {
-        check(|var| Expr::binop(Expr::var("foo"), Operator::Mul, var(2)));
+        check(|var| Expr::binop(Expr::var("foo"), Operator::Mul, var(0)));
     }
     #[test]
     fn binop_both() {
-        check(|var| Expr::binop(var(0), Operator::And, var(2)));
+        check(|var| Expr::binop(var(0), Operator::And, var(1)));
     }
     #[test]
     fn call_obj() {
@@ -41,7 +52,7 @@ mod expand_placeholders {
     }
     #[test]
     fn call_arg() {
-        check(|var| Expr::apply(Expr::var("foo"), var(1)));
+        check(|var| Expr::apply(Expr::var("foo"), var(0)));
     }
     #[test]
     fn conditional_cond() {
@@ -49,11 +60,11 @@ mod expand_placeholders {
     }
     #[test]
     fn conditional_then() {
-        check(|var| Expr::conditional(Expr::literal("true"), var(1), Expr::literal(3)));
+        check(|var| Expr::conditional(Expr::literal("true"), var(0), Expr::literal(3)));
     }
     #[test]
     fn conditional_else() {
-        check(|var| Expr::conditional(Expr::literal("false"), Expr::literal(4), var(2)));
+        check(|var| Expr::conditional(Expr::literal("false"), Expr::literal(4), var(0)));
     }
     #[test]
     fn conditional_all() {
@@ -71,4 +82,69 @@ mod expand_placeholders {
             )
         });
     }
+    #[test]
+    fn call_many() {
+        check(|var| Expr::apply_many(var(0), [Expr::var("x"), var(1), Expr::literal(2), var(2)]));
+    }
+    #[test]
+    fn array() {
+        check(|var| Expr::literal(vec![var(0), Expr::literal(1), var(1)]));
+    }
+    #[test]
+    fn record() {
+        check(|var| Expr::literal(vec![("a", var(0)), ("b", Expr::literal(1))]));
+    }
+    #[test]
+    fn enum_args() {
+        check(|var| Expr::literal(Literal::Enum("pair".to_string(), vec![var(0), var(1)])));
+    }
+    #[test]
+    fn string_interpolation() {
+        check(|var| {
+            Expr::literal(Literal::LStr(vec![
+                StringPart::Text("hello ".to_string()),
+                StringPart::Value(var(0)),
+                StringPart::Text(" and ".to_string()),
+                StringPart::Value(var(1)),
+            ]))
+        });
+    }
+    #[test]
+    fn update() {
+        check(|var| {
+            let span = crate::Span::default();
+            Expr::update(var(0), [(span, "a", var(1)), (span, "b", Expr::var("x"))])
+        });
+    }
+    #[test]
+    fn only_direct_children() {
+        // The placeholder belongs to the inner call, which isn't replaced by this call
+        let inner = Expr::apply(Expr::var("g"), Expr::placeholder());
+        let expr = Expr::apply(Expr::var("f"), inner);
+        assert_eq!(expr.clone().replace_placeholders(), expr);
+        assert_eq!(
+            Expr::placeholder().replace_placeholders(),
+            Expr::placeholder()
+        );
+        let lambda = Expr::lambda(vec![Pattern::Any], Expr::placeholder());
+        assert_eq!(lambda.clone().replace_placeholders(), lambda);
+    }
+    #[test]
+    fn keeps_spans() {
+        let expr = Expr::binop(
+            Expr::placeholder().with_span(((1, 1), (1, 2))),
+            Operator::Add,
+            Expr::literal(1),
+        )
+        .with_span(((1, 1), (1, 6)));
+        let Expr::Lambda(meta, _, body) = expr.replace_placeholders() else {
+            panic!("expected a lambda");
+        };
+        assert_eq!(meta.get_span(), ((1, 1), (1, 6)).into());
+        assert_eq!(body.meta().get_span(), ((1, 1), (1, 6)).into());
+        let Expr::Binop(_, lhs, _, _) = *body else {
+            panic!("expected a binop");
+        };
+        assert_eq!(*lhs, Expr::var("_0").with_span(((1, 1), (1, 2))));
+    }
 }
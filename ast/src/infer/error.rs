@@ -0,0 +1,62 @@
+use crate::{ren_type::Type, span::Span};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeErrorKind {
+    /// Two types that were required to be equal could not be unified
+    Mismatch { expected: Type, found: Type },
+    /// Unifying the two types would require a type to contain itself
+    InfiniteType(Type, Type),
+    /// A variable was used without being bound
+    UnboundVar(String),
+    /// A type declaration used a type variable which it does not declare
+    UndeclaredTypeVar { typ: String, var: String },
+    /// A type with a `forall` used a type variable which it does not quantify
+    UnquantifiedTypeVar(String),
+    /// A `forall` quantified a type variable which is not used
+    UnusedTypeVar(String),
+}
+
+/// An error found whilst inferring types, located at the expression that caused it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeError {
+    kind: TypeErrorKind,
+    span: Span,
+}
+impl TypeError {
+    pub(super) fn new(kind: TypeErrorKind, span: Span) -> Self {
+        Self { kind, span }
+    }
+    pub fn kind(&self) -> &TypeErrorKind {
+        &self.kind
+    }
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+impl core::fmt::Display for TypeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ((line, col), _): ((usize, usize), (usize, usize)) = self.span.into();
+        write!(f, "{}:{}: ", line, col)?;
+        match &self.kind {
+            TypeErrorKind::Mismatch { expected, found } => {
+                write!(f, "type mismatch: expected {}, found {}", expected, found)
+            }
+            TypeErrorKind::InfiniteType(t1, t2) => {
+                write!(f, "cannot construct the infinite type {} = {}", t1, t2)
+            }
+            TypeErrorKind::UnboundVar(name) => write!(f, "unbound variable `{}`", name),
+            TypeErrorKind::UndeclaredTypeVar { typ, var } => write!(
+                f,
+                "type variable `{}` is not declared by type `{}`",
+                var, typ
+            ),
+            TypeErrorKind::UnquantifiedTypeVar(var) => {
+                write!(f, "type variable `{}` is not quantified by `forall`", var)
+            }
+            TypeErrorKind::UnusedTypeVar(var) => {
+                write!(f, "type variable `{}` is quantified but never used", var)
+            }
+        }
+    }
+}
+impl std::error::Error for TypeError {}
@@ -0,0 +1,996 @@
+//! Hindley-Milner type inference, extended with row polymorphism so that records and
+//! variants may be open (e.g. `{x: a | r}` or `[#ok a | r]`).
+//!
+//! Inference writes the type of every expression back into its [`Meta`](crate::expr::Meta).
+//! `Type` has no way of naming a row variable, so an open row is written back with only the
+//! fields that are known to be present.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{
+    decl::Decl,
+    expr::{Expr, Literal, Operator, Pattern, StringPart},
+    module::Module,
+    ren_type::Type,
+    span::Span,
+};
+
+mod error;
+#[cfg(test)]
+mod tests;
+
+pub use error::{TypeError, TypeErrorKind};
+
+/// Infer the types of all the declarations in a module.
+///
+/// Declarations may refer to each other regardless of the order they are declared in, and are
+/// generalised one at a time in declaration order. The inferred type of a `let` declaration
+/// without an annotation is written into the declaration.
+pub fn check_module(module: &mut Module) -> Result<(), Vec<TypeError>> {
+    let mut checker = Checker::default();
+    for decl in module.declarations() {
+        if let Decl::Type {
+            meta,
+            name,
+            vars,
+            typ,
+            ..
+        } = decl
+        {
+            for var in typ.vars() {
+                if !vars.iter().any(|v| v == var) {
+                    checker.errors.push(TypeError::new(
+                        TypeErrorKind::UndeclaredTypeVar {
+                            typ: name.clone(),
+                            var: var.to_string(),
+                        },
+                        meta.get_span(),
+                    ));
+                }
+            }
+            checker
+                .aliases
+                .insert(name.clone(), (vars.clone(), typ.clone()));
+        }
+    }
+    for decl in module.declarations() {
+        if let Decl::Let { meta, .. } | Decl::Ext { meta, .. } = decl {
+            checker.check_quantifiers(meta.get_type(), meta.get_span());
+        }
+    }
+    // Names exposed by imports are checked in the module that declares them, so like qualified
+    // names they may have any type here. Declarations in this module shadow them.
+    for name in module
+        .all_imports()
+        .iter()
+        .flat_map(|import| &import.exposing)
+    {
+        let var = checker.fresh_var();
+        let scheme = Scheme {
+            vars: vec![var],
+            ty: Ty::Var(var),
+        };
+        checker.env.push((name.clone(), scheme));
+    }
+    // Every declaration is in scope before any of them are checked. `let` declarations are
+    // monomorphic until they have been checked themselves.
+    let slots = module
+        .declarations()
+        .iter()
+        .map(|decl| match decl {
+            Decl::Let { var, .. } | Decl::Infix { op: var, .. } => {
+                let ty = checker.fresh();
+                checker.env.push((var.clone(), Scheme::mono(ty)));
+                Some(checker.env.len() - 1)
+            }
+            Decl::Ext { meta, var, .. } => {
+                checker.enter_level();
+                let ty = checker.ty_from_type(meta.get_type(), &mut HashMap::new());
+                checker.exit_level();
+                let scheme = checker.generalise(&ty);
+                checker.env.push((var.clone(), scheme));
+                None
+            }
+            Decl::Type { .. } | Decl::Expect { .. } => None,
+        })
+        .collect::<Vec<_>>();
+    for (decl, slot) in module.declarations().iter().zip(&slots) {
+        if let (
+            Decl::Let {
+                meta, var, expr, ..
+            }
+            | Decl::Infix {
+                meta,
+                op: var,
+                expr,
+                ..
+            },
+            Some(index),
+        ) = (decl, slot)
+        {
+            let ty = checker.infer_binding(var, meta.get_type(), expr);
+            let scheme = checker.generalise(&ty);
+            let forward = checker.env[*index].1.ty.clone();
+            let instance = checker.instantiate(&scheme);
+            checker.unify_at(&forward, &instance, expr.meta().get_span());
+            checker.env[*index].1 = scheme;
+        } else if let Decl::Expect { expr, .. } = decl {
+            let found = checker.infer(expr);
+            checker.unify_at(&Ty::boolean(), &found, expr.meta().get_span());
+        }
+    }
+
+    let node_types = std::mem::take(&mut checker.node_types);
+    let mut node_types = node_types.iter();
+    for (decl, slot) in module.declarations_mut().iter_mut().zip(slots) {
+        match (decl, slot) {
+            (Decl::Let { meta, expr, .. } | Decl::Infix { meta, expr, .. }, Some(index)) => {
+                let mut names = HashMap::new();
+                if *meta.get_type() == Type::Hole {
+                    meta.set_type(checker.to_type(&checker.env[index].1.ty, &mut names));
+                }
+                checker.write_types(expr, &mut node_types, &mut names);
+            }
+            (Decl::Expect { meta, expr }, _) => {
+                meta.set_type(Type::boolean());
+                checker.write_types(expr, &mut node_types, &mut HashMap::new())
+            }
+            _ => {}
+        }
+    }
+    checker.finish(())
+}
+
+/// Infer the type of a standalone expression, writing the type of every sub-expression into
+/// its `Meta`.
+pub fn infer_expr(expr: &mut Expr) -> Result<Type, Vec<TypeError>> {
+    let mut checker = Checker::default();
+    checker.enter_level();
+    let ty = checker.infer(expr);
+    checker.exit_level();
+
+    let mut names = HashMap::new();
+    let typ = checker.to_type(&ty, &mut names);
+    let node_types = std::mem::take(&mut checker.node_types);
+    checker.write_types(expr, &mut node_types.iter(), &mut names);
+    checker.finish(typ)
+}
+
+type TVar = usize;
+
+/// The internal representation of types during inference. Unlike `Type`, records and sum
+/// types may have a row variable as a tail.
+#[derive(Debug, Clone, PartialEq)]
+enum Ty {
+    Any,
+    App(Box<Ty>, Vec<Ty>),
+    Con(String),
+    Fun(Box<Ty>, Box<Ty>),
+    Rec(Row),
+    Sum(Row),
+    Var(TVar),
+}
+impl Ty {
+    fn con(name: &str) -> Self {
+        Self::Con(name.to_string())
+    }
+    fn arr(typ: Ty) -> Self {
+        Self::App(Box::new(Self::con("Array")), vec![typ])
+    }
+    fn boolean() -> Self {
+        Self::Sum(Row {
+            fields: [("true", vec![]), ("false", vec![])]
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect(),
+            tail: None,
+        })
+    }
+    fn fun(arg: Ty, ret: Ty) -> Self {
+        Self::Fun(Box::new(arg), Box::new(ret))
+    }
+    fn num() -> Self {
+        Self::con("Number")
+    }
+    fn string() -> Self {
+        Self::con("String")
+    }
+}
+
+/// The fields of a record or the variants of a sum type. A row with a `tail` is open: it can be
+/// extended with more fields by binding the tail variable to another row.
+#[derive(Debug, Clone, PartialEq, Default)]
+struct Row {
+    fields: BTreeMap<String, Vec<Ty>>,
+    tail: Option<TVar>,
+}
+
+#[derive(Debug, Clone)]
+struct Scheme {
+    vars: Vec<TVar>,
+    ty: Ty,
+}
+impl Scheme {
+    fn mono(ty: Ty) -> Self {
+        Self {
+            vars: Vec::new(),
+            ty,
+        }
+    }
+}
+
+enum Failure {
+    Mismatch,
+    Infinite,
+}
+
+#[derive(Default)]
+struct Checker {
+    /// The type each type variable has been unified with, if any
+    bindings: Vec<Option<Ty>>,
+    /// The `let` nesting level each type variable was created at, used for generalisation
+    levels: Vec<usize>,
+    level: usize,
+    env: Vec<(String, Scheme)>,
+    /// Type declarations, by name: `(parameters, definition)`
+    aliases: HashMap<String, (Vec<String>, Type)>,
+    /// Aliases currently being expanded, to stop recursive types from expanding forever
+    expanding: Vec<String>,
+    /// The type of every expression visited, in pre-order
+    node_types: Vec<Ty>,
+    errors: Vec<TypeError>,
+}
+
+impl Checker {
+    fn finish<T>(self, value: T) -> Result<T, Vec<TypeError>> {
+        if self.errors.is_empty() {
+            Ok(value)
+        } else {
+            Err(self.errors)
+        }
+    }
+
+    // VARIABLES ---------------------------------------------------------------
+    fn fresh_var(&mut self) -> TVar {
+        self.bindings.push(None);
+        self.levels.push(self.level);
+        self.bindings.len() - 1
+    }
+    fn fresh(&mut self) -> Ty {
+        Ty::Var(self.fresh_var())
+    }
+    fn enter_level(&mut self) {
+        self.level += 1;
+    }
+    fn exit_level(&mut self) {
+        self.level -= 1;
+    }
+    fn lookup(&self, name: &str) -> Option<Scheme> {
+        self.env
+            .iter()
+            .rev()
+            .find(|(n, _)| n == name)
+            .map(|(_, scheme)| scheme.clone())
+    }
+    fn bind_var(&mut self, name: &str, ty: Ty) {
+        self.env.push((name.to_string(), Scheme::mono(ty)));
+    }
+
+    /// Follow bound type variables until reaching a type which is not a bound variable
+    fn resolve(&self, ty: &Ty) -> Ty {
+        let mut ty = ty.clone();
+        while let Ty::Var(var) = ty {
+            match &self.bindings[var] {
+                Some(bound) => ty = bound.clone(),
+                None => break,
+            }
+        }
+        ty
+    }
+    /// Collect all of the fields of a row, following the tail as far as it has been bound
+    fn flatten(&self, row: &Row) -> Row {
+        let mut fields = row.fields.clone();
+        let mut tail = row.tail;
+        while let Some(var) = tail {
+            match &self.bindings[var] {
+                Some(Ty::Rec(next) | Ty::Sum(next)) => {
+                    for (k, v) in &next.fields {
+                        fields.entry(k.clone()).or_insert_with(|| v.clone());
+                    }
+                    tail = next.tail;
+                }
+                Some(Ty::Var(next)) => tail = Some(*next),
+                Some(_) => tail = None,
+                None => break,
+            }
+        }
+        Row { fields, tail }
+    }
+    fn free_vars(&self, ty: &Ty, vars: &mut Vec<TVar>) {
+        match self.resolve(ty) {
+            Ty::Any | Ty::Con(_) => {}
+            Ty::App(fun, args) => {
+                self.free_vars(&fun, vars);
+                args.iter().for_each(|arg| self.free_vars(arg, vars));
+            }
+            Ty::Fun(arg, ret) => {
+                self.free_vars(&arg, vars);
+                self.free_vars(&ret, vars);
+            }
+            Ty::Rec(row) | Ty::Sum(row) => {
+                let row = self.flatten(&row);
+                row.fields
+                    .values()
+                    .flatten()
+                    .for_each(|t| self.free_vars(t, vars));
+                if let Some(tail) = row.tail {
+                    if !vars.contains(&tail) {
+                        vars.push(tail);
+                    }
+                }
+            }
+            Ty::Var(var) => {
+                if !vars.contains(&var) {
+                    vars.push(var);
+                }
+            }
+        }
+    }
+
+    // GENERALISATION ----------------------------------------------------------
+    fn generalise(&self, ty: &Ty) -> Scheme {
+        let mut vars = Vec::new();
+        self.free_vars(ty, &mut vars);
+        vars.retain(|var| self.levels[*var] > self.level);
+        Scheme {
+            vars,
+            ty: ty.clone(),
+        }
+    }
+    fn instantiate(&mut self, scheme: &Scheme) -> Ty {
+        if scheme.vars.is_empty() {
+            return scheme.ty.clone();
+        }
+        let vars = scheme
+            .vars
+            .iter()
+            .map(|var| (*var, self.fresh_var()))
+            .collect();
+        self.substitute(&scheme.ty, &vars)
+    }
+    fn substitute(&self, ty: &Ty, vars: &HashMap<TVar, TVar>) -> Ty {
+        let sub_row = |row: &Row| {
+            let row = self.flatten(row);
+            Row {
+                fields: row
+                    .fields
+                    .iter()
+                    .map(|(k, v)| {
+                        (
+                            k.clone(),
+                            v.iter().map(|t| self.substitute(t, vars)).collect(),
+                        )
+                    })
+                    .collect(),
+                tail: row.tail.map(|tail| *vars.get(&tail).unwrap_or(&tail)),
+            }
+        };
+        match self.resolve(ty) {
+            Ty::App(fun, args) => Ty::App(
+                Box::new(self.substitute(&fun, vars)),
+                args.iter().map(|arg| self.substitute(arg, vars)).collect(),
+            ),
+            Ty::Fun(arg, ret) => Ty::fun(self.substitute(&arg, vars), self.substitute(&ret, vars)),
+            Ty::Rec(row) => Ty::Rec(sub_row(&row)),
+            Ty::Sum(row) => Ty::Sum(sub_row(&row)),
+            Ty::Var(var) => Ty::Var(*vars.get(&var).unwrap_or(&var)),
+            ty => ty,
+        }
+    }
+
+    // UNIFICATION -------------------------------------------------------------
+    /// Unify two types, recording an error at `span` if they are incompatible
+    fn unify_at(&mut self, expected: &Ty, found: &Ty, span: Span) {
+        if let Err(failure) = self.unify(expected, found) {
+            let mut names = HashMap::new();
+            let expected = self.to_type(expected, &mut names);
+            let found = self.to_type(found, &mut names);
+            let kind = match failure {
+                Failure::Mismatch => TypeErrorKind::Mismatch { expected, found },
+                Failure::Infinite => TypeErrorKind::InfiniteType(expected, found),
+            };
+            self.errors.push(TypeError::new(kind, span));
+        }
+    }
+    fn unify(&mut self, t1: &Ty, t2: &Ty) -> Result<(), Failure> {
+        match (self.resolve(t1), self.resolve(t2)) {
+            (Ty::Var(v1), Ty::Var(v2)) if v1 == v2 => Ok(()),
+            (Ty::Var(var), ty) | (ty, Ty::Var(var)) => self.bind(var, ty),
+            (Ty::Any, _) | (_, Ty::Any) => Ok(()),
+            (Ty::Con(c1), Ty::Con(c2)) if c1 == c2 => Ok(()),
+            (Ty::App(f1, args1), Ty::App(f2, args2)) if args1.len() == args2.len() => {
+                self.unify(&f1, &f2)?;
+                args1
+                    .iter()
+                    .zip(&args2)
+                    .try_for_each(|(a1, a2)| self.unify(a1, a2))
+            }
+            (Ty::Fun(arg1, ret1), Ty::Fun(arg2, ret2)) => {
+                self.unify(&arg1, &arg2)?;
+                self.unify(&ret1, &ret2)
+            }
+            (Ty::Rec(r1), Ty::Rec(r2)) => self.unify_rows(&r1, &r2, Ty::Rec),
+            (Ty::Sum(r1), Ty::Sum(r2)) => self.unify_rows(&r1, &r2, Ty::Sum),
+            _ => Err(Failure::Mismatch),
+        }
+    }
+    fn unify_rows(&mut self, r1: &Row, r2: &Row, make: fn(Row) -> Ty) -> Result<(), Failure> {
+        let (r1, r2) = (self.flatten(r1), self.flatten(r2));
+        let mut missing1 = BTreeMap::new();
+        for (key, types) in &r2.fields {
+            match r1.fields.get(key) {
+                Some(other) if other.len() == types.len() => other
+                    .iter()
+                    .zip(types)
+                    .try_for_each(|(t1, t2)| self.unify(t1, t2))?,
+                Some(_) => return Err(Failure::Mismatch),
+                None => {
+                    missing1.insert(key.clone(), types.clone());
+                }
+            }
+        }
+        let missing2 = r1
+            .fields
+            .iter()
+            .filter(|(key, _)| !r2.fields.contains_key(*key))
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect::<BTreeMap<_, _>>();
+        match (r1.tail, r2.tail) {
+            (Some(t1), Some(t2)) if t1 == t2 => {
+                if missing1.is_empty() && missing2.is_empty() {
+                    Ok(())
+                } else {
+                    Err(Failure::Mismatch)
+                }
+            }
+            (Some(t1), Some(t2)) => {
+                let rest = Some(self.fresh_var());
+                self.bind(
+                    t1,
+                    make(Row {
+                        fields: missing1,
+                        tail: rest,
+                    }),
+                )?;
+                self.bind(
+                    t2,
+                    make(Row {
+                        fields: missing2,
+                        tail: rest,
+                    }),
+                )
+            }
+            (Some(t1), None) if missing2.is_empty() => self.bind(
+                t1,
+                make(Row {
+                    fields: missing1,
+                    tail: None,
+                }),
+            ),
+            (None, Some(t2)) if missing1.is_empty() => self.bind(
+                t2,
+                make(Row {
+                    fields: missing2,
+                    tail: None,
+                }),
+            ),
+            (None, None) if missing1.is_empty() && missing2.is_empty() => Ok(()),
+            _ => Err(Failure::Mismatch),
+        }
+    }
+    fn bind(&mut self, var: TVar, ty: Ty) -> Result<(), Failure> {
+        let mut free = Vec::new();
+        self.free_vars(&ty, &mut free);
+        if free.contains(&var) {
+            return Err(Failure::Infinite);
+        }
+        let level = self.levels[var];
+        for v in free {
+            self.levels[v] = self.levels[v].min(level);
+        }
+        self.bindings[var] = Some(ty);
+        Ok(())
+    }
+
+    // CONVERSION --------------------------------------------------------------
+    /// Check that a `forall` quantifies exactly the type variables used in its body
+    fn check_quantifiers(&mut self, typ: &Type, span: Span) {
+        if let Type::Forall(quantified, body) = typ {
+            let used = body.vars();
+            for var in quantified {
+                if !used.contains(&var.as_str()) {
+                    let kind = TypeErrorKind::UnusedTypeVar(var.clone());
+                    self.errors.push(TypeError::new(kind, span));
+                }
+            }
+            for var in used {
+                if !quantified.iter().any(|q| q == var) {
+                    let kind = TypeErrorKind::UnquantifiedTypeVar(var.to_string());
+                    self.errors.push(TypeError::new(kind, span));
+                }
+            }
+        }
+    }
+    /// Convert a type annotation, expanding any type declarations. Type variables with the
+    /// same name are mapped to the same variable via `vars`.
+    fn ty_from_type(&mut self, typ: &Type, vars: &mut HashMap<String, Ty>) -> Ty {
+        match typ {
+            Type::Any => Ty::Any,
+            Type::App(fun, args) => {
+                let args = args
+                    .iter()
+                    .map(|arg| self.ty_from_type(arg, vars))
+                    .collect();
+                match fun.as_ref() {
+                    Type::Con(name) => self.expand(name, args),
+                    fun => Ty::App(Box::new(self.ty_from_type(fun, vars)), args),
+                }
+            }
+            Type::Con(name) => self.expand(name, Vec::new()),
+            Type::Forall(quantified, typ) => {
+                let mut vars = vars.clone();
+                for var in quantified {
+                    vars.insert(var.clone(), self.fresh());
+                }
+                self.ty_from_type(typ, &mut vars)
+            }
+            Type::Fun(arg, ret) => {
+                let arg = self.ty_from_type(arg, vars);
+                Ty::fun(arg, self.ty_from_type(ret, vars))
+            }
+            Type::Hole => self.fresh(),
+            Type::Rec(row) => Ty::Rec(self.ty_from_row(row, vars)),
+            Type::Sum(row) => Ty::Sum(self.ty_from_row(row, vars)),
+            Type::Var(name) => match vars.get(name) {
+                Some(ty) => ty.clone(),
+                None => {
+                    let ty = self.fresh();
+                    vars.insert(name.clone(), ty.clone());
+                    ty
+                }
+            },
+        }
+    }
+    fn ty_from_row(
+        &mut self,
+        row: &HashMap<String, Vec<Type>>,
+        vars: &mut HashMap<String, Ty>,
+    ) -> Row {
+        Row {
+            fields: row
+                .iter()
+                .map(|(k, types)| {
+                    let types = types.iter().map(|t| self.ty_from_type(t, vars)).collect();
+                    (k.clone(), types)
+                })
+                .collect(),
+            tail: None,
+        }
+    }
+    fn expand(&mut self, name: &str, args: Vec<Ty>) -> Ty {
+        match self.aliases.get(name) {
+            Some((params, typ))
+                if params.len() == args.len() && !self.expanding.iter().any(|n| n == name) =>
+            {
+                let typ = typ.clone();
+                let mut vars = params.iter().cloned().zip(args).collect();
+                self.expanding.push(name.to_string());
+                let ty = self.ty_from_type(&typ, &mut vars);
+                self.expanding.pop();
+                ty
+            }
+            _ if args.is_empty() => Ty::con(name),
+            _ => Ty::App(Box::new(Ty::con(name)), args),
+        }
+    }
+    /// Convert an inferred type back to a `Type`, naming any unbound variables via `names`
+    fn to_type(&self, ty: &Ty, names: &mut HashMap<TVar, String>) -> Type {
+        match self.resolve(ty) {
+            Ty::Any => Type::Any,
+            Ty::App(fun, args) => Type::App(
+                Box::new(self.to_type(&fun, names)),
+                args.iter().map(|arg| self.to_type(arg, names)).collect(),
+            ),
+            Ty::Con(name) => Type::Con(name),
+            Ty::Fun(arg, ret) => Type::Fun(
+                Box::new(self.to_type(&arg, names)),
+                Box::new(self.to_type(&ret, names)),
+            ),
+            Ty::Rec(row) => Type::Rec(self.to_row(&row, names)),
+            Ty::Sum(row) => Type::Sum(self.to_row(&row, names)),
+            Ty::Var(var) => {
+                let next = names.len();
+                Type::Var(names.entry(var).or_insert_with(|| var_name(next)).clone())
+            }
+        }
+    }
+    fn to_row(&self, row: &Row, names: &mut HashMap<TVar, String>) -> HashMap<String, Vec<Type>> {
+        self.flatten(row)
+            .fields
+            .iter()
+            .map(|(k, types)| {
+                let types = types.iter().map(|t| self.to_type(t, names)).collect();
+                (k.clone(), types)
+            })
+            .collect()
+    }
+
+    // INFERENCE ---------------------------------------------------------------
+    /// Infer the type of a (possibly recursive) binding, checking it against its annotation.
+    /// The result has not been generalised.
+    fn infer_binding(&mut self, name: &str, annotation: &Type, value: &Expr) -> Ty {
+        self.enter_level();
+        let ty = self.ty_from_type(annotation, &mut HashMap::new());
+        // Explicitly quantified variables are rigid whilst checking the value, so that it has
+        // to be as polymorphic as its annotation says
+        let expected = match annotation {
+            Type::Forall(quantified, typ) => {
+                let mut rigid = quantified
+                    .iter()
+                    .map(|var| (var.clone(), Ty::con(var)))
+                    .collect();
+                self.ty_from_type(typ, &mut rigid)
+            }
+            _ => ty.clone(),
+        };
+        let mark = self.env.len();
+        self.bind_var(name, expected.clone());
+        let found = self.infer(value);
+        self.unify_at(&expected, &found, value.meta().get_span());
+        self.env.truncate(mark);
+        self.exit_level();
+        ty
+    }
+
+    /// Infer the type of an expression. The order in which sub-expressions are visited must
+    /// match [`Checker::write_types`].
+    fn infer(&mut self, expr: &Expr) -> Ty {
+        let index = self.node_types.len();
+        self.node_types.push(Ty::Any);
+        let span = expr.meta().get_span();
+        let ty = match expr {
+            Expr::Access(_, rec, key) => {
+                let found = self.infer(rec);
+                let field = self.fresh();
+                let expected = Ty::Rec(Row {
+                    fields: BTreeMap::from([(key.clone(), vec![field.clone()])]),
+                    tail: Some(self.fresh_var()),
+                });
+                self.unify_at(&expected, &found, rec.meta().get_span());
+                field
+            }
+            Expr::Annotated(_, expr, typ) => {
+                self.check_quantifiers(typ, span);
+                let expected = self.ty_from_type(typ, &mut HashMap::new());
+                let found = self.infer(expr);
+                self.unify_at(&expected, &found, span);
+                expected
+            }
+            Expr::Assert(_, cond, body) => {
+                let found = self.infer(cond);
+                self.unify_at(&Ty::boolean(), &found, cond.meta().get_span());
+                self.infer(body)
+            }
+            Expr::Binop(_, lhs, op, rhs) => {
+                let (lhs_ty, rhs_ty, ret) = self.operator(*op);
+                let found = self.infer(lhs);
+                self.unify_at(&lhs_ty, &found, lhs.meta().get_span());
+                let found = self.infer(rhs);
+                self.unify_at(&rhs_ty, &found, rhs.meta().get_span());
+                ret
+            }
+            Expr::Call(_, fun, args) => {
+                let mut fun_ty = self.infer(fun);
+                for arg in args {
+                    let (param, ret) = (self.fresh(), self.fresh());
+                    let expected = Ty::fun(param.clone(), ret.clone());
+                    self.unify_at(&expected, &fun_ty, fun.meta().get_span());
+                    let found = self.infer(arg);
+                    self.unify_at(&param, &found, arg.meta().get_span());
+                    fun_ty = ret;
+                }
+                fun_ty
+            }
+            Expr::If(_, cond, then_, else_) => {
+                let found = self.infer(cond);
+                self.unify_at(&Ty::boolean(), &found, cond.meta().get_span());
+                let ty = self.infer(then_);
+                let found = self.infer(else_);
+                self.unify_at(&ty, &found, else_.meta().get_span());
+                ty
+            }
+            Expr::Lambda(_, params, body) => {
+                let mark = self.env.len();
+                let params = params
+                    .iter()
+                    .map(|param| self.infer_pattern(param, span))
+                    .collect::<Vec<_>>();
+                let ret = self.infer(body);
+                self.env.truncate(mark);
+                params
+                    .into_iter()
+                    .rfold(ret, |ret, param| Ty::fun(param, ret))
+            }
+            Expr::Let(_, Pattern::Var(name), value, body) => {
+                let ty = self.infer_binding(name, &Type::Hole, value);
+                let scheme = self.generalise(&ty);
+                let mark = self.env.len();
+                self.env.push((name.clone(), scheme));
+                let ret = self.infer(body);
+                self.env.truncate(mark);
+                ret
+            }
+            Expr::Let(_, pattern, value, body) => {
+                let found = self.infer(value);
+                let mark = self.env.len();
+                let expected = self.infer_pattern(pattern, value.meta().get_span());
+                self.unify_at(&expected, &found, value.meta().get_span());
+                let ret = self.infer(body);
+                self.env.truncate(mark);
+                ret
+            }
+            Expr::Literal(_, literal) => self.infer_literal(literal),
+            // Placeholders should have been replaced by lambdas, and nothing is known about
+            // the contents of other modules
+            Expr::Placeholder(_) | Expr::Scoped(..) => self.fresh(),
+            Expr::Switch(_, expr, arms) => {
+                let found = self.infer(expr);
+                let ret = self.fresh();
+                for (pattern, guard, body) in arms {
+                    let mark = self.env.len();
+                    let expected = self.infer_pattern(pattern, expr.meta().get_span());
+                    self.unify_at(&expected, &found, expr.meta().get_span());
+                    if let Some(guard) = guard {
+                        let found = self.infer(guard);
+                        self.unify_at(&Ty::boolean(), &found, guard.meta().get_span());
+                    }
+                    let found = self.infer(body);
+                    self.unify_at(&ret, &found, body.meta().get_span());
+                    self.env.truncate(mark);
+                }
+                ret
+            }
+            Expr::Update(_, rec, fields) => {
+                // Each field must already be in the record, with the same type as its new value
+                let found = self.infer(rec);
+                for (field_span, key, value) in fields {
+                    let value = self.infer(value);
+                    let expected = Ty::Rec(Row {
+                        fields: BTreeMap::from([(key.clone(), vec![value])]),
+                        tail: Some(self.fresh_var()),
+                    });
+                    self.unify_at(&expected, &found, *field_span);
+                }
+                found
+            }
+            Expr::Var(_, name) => match self.lookup(name) {
+                Some(scheme) => self.instantiate(&scheme),
+                None => {
+                    self.errors.push(TypeError::new(
+                        TypeErrorKind::UnboundVar(name.clone()),
+                        span,
+                    ));
+                    self.fresh()
+                }
+            },
+        };
+        self.node_types[index] = ty.clone();
+        ty
+    }
+    fn infer_literal(&mut self, literal: &Literal<Expr>) -> Ty {
+        match literal {
+            Literal::Array(items) => {
+                let elem = self.fresh();
+                for item in items {
+                    let found = self.infer(item);
+                    self.unify_at(&elem, &found, item.meta().get_span());
+                }
+                Ty::arr(elem)
+            }
+            Literal::Enum(tag, args) => {
+                let args = args.iter().map(|arg| self.infer(arg)).collect();
+                Ty::Sum(Row {
+                    fields: BTreeMap::from([(tag.clone(), args)]),
+                    tail: Some(self.fresh_var()),
+                })
+            }
+            Literal::Number(_) => Ty::num(),
+            Literal::Record(fields) => Ty::Rec(Row {
+                fields: fields
+                    .iter()
+                    .map(|(key, value)| (key.clone(), vec![self.infer(value)]))
+                    .collect(),
+                tail: None,
+            }),
+            Literal::LStr(parts) => {
+                for part in parts {
+                    if let StringPart::Value(value) = part {
+                        self.infer(value);
+                    }
+                }
+                Ty::string()
+            }
+        }
+    }
+    /// Infer the type of the values matched by a pattern, binding any variables it introduces
+    fn infer_pattern(&mut self, pattern: &Pattern, span: Span) -> Ty {
+        match pattern {
+            Pattern::Any => self.fresh(),
+            Pattern::Literal(Literal::Array(items)) => {
+                let elem = self.fresh();
+                for item in items {
+                    if let Pattern::Spread(name) = item {
+                        self.bind_var(name, Ty::arr(elem.clone()));
+                    } else {
+                        let found = self.infer_pattern(item, span);
+                        self.unify_at(&elem, &found, span);
+                    }
+                }
+                Ty::arr(elem)
+            }
+            Pattern::Literal(Literal::Enum(tag, args)) => {
+                let args = args
+                    .iter()
+                    .map(|arg| self.infer_pattern(arg, span))
+                    .collect();
+                Ty::Sum(Row {
+                    fields: BTreeMap::from([(tag.clone(), args)]),
+                    tail: Some(self.fresh_var()),
+                })
+            }
+            Pattern::Literal(Literal::Number(_)) => Ty::num(),
+            Pattern::Literal(Literal::Record(fields)) => {
+                // A spread is the record's other fields, which are its tail
+                let tail = self.fresh_var();
+                let mut row = BTreeMap::new();
+                for (key, pattern) in fields {
+                    if let Pattern::Spread(name) = pattern {
+                        let rest = Ty::Rec(Row {
+                            fields: BTreeMap::new(),
+                            tail: Some(tail),
+                        });
+                        self.bind_var(name, rest);
+                    } else {
+                        row.insert(key.clone(), vec![self.infer_pattern(pattern, span)]);
+                    }
+                }
+                Ty::Rec(Row {
+                    fields: row,
+                    tail: Some(tail),
+                })
+            }
+            Pattern::Literal(Literal::LStr(parts)) => {
+                for part in parts {
+                    if let StringPart::Value(pattern) = part {
+                        let found = self.infer_pattern(pattern, span);
+                        self.unify_at(&Ty::string(), &found, span);
+                    }
+                }
+                Ty::string()
+            }
+            Pattern::Spread(name) | Pattern::Var(name) => {
+                let ty = self.fresh();
+                self.bind_var(name, ty.clone());
+                ty
+            }
+            // Type patterns are checked at runtime, so they can match a value of any type
+            Pattern::Type(name, pattern) => {
+                let expected = match name.as_str() {
+                    "Array" => Ty::arr(self.fresh()),
+                    _ => self.expand(name, Vec::new()),
+                };
+                let found = self.infer_pattern(pattern, span);
+                self.unify_at(&expected, &found, span);
+                self.fresh()
+            }
+        }
+    }
+    /// The types of the left operand, right operand and result of a binary operator
+    fn operator(&mut self, op: Operator) -> (Ty, Ty, Ty) {
+        use Operator::*;
+        match op {
+            Add | Sub | Mul | Div | Mod | Pow => (Ty::num(), Ty::num(), Ty::num()),
+            Gt | Gte | Lt | Lte => (Ty::num(), Ty::num(), Ty::boolean()),
+            Eq | Neq => {
+                let a = self.fresh();
+                (a.clone(), a, Ty::boolean())
+            }
+            And | Or => (Ty::boolean(), Ty::boolean(), Ty::boolean()),
+            Concat => {
+                let a = self.fresh();
+                (a.clone(), a.clone(), a)
+            }
+            Cons => {
+                let a = self.fresh();
+                (a.clone(), Ty::arr(a.clone()), Ty::arr(a))
+            }
+            Pipe => {
+                let (a, b) = (self.fresh(), self.fresh());
+                (a.clone(), Ty::fun(a, b.clone()), b)
+            }
+        }
+    }
+
+    // WRITING BACK ------------------------------------------------------------
+    /// Write the inferred types into each expression's `Meta`, visiting sub-expressions in the
+    /// same order as [`Checker::infer`].
+    fn write_types(
+        &self,
+        expr: &mut Expr,
+        types: &mut std::slice::Iter<Ty>,
+        names: &mut HashMap<TVar, String>,
+    ) {
+        let ty = types
+            .next()
+            .expect("every expression should have been visited during inference");
+        expr.meta_mut().set_type(self.to_type(ty, names));
+        match expr {
+            Expr::Access(_, expr, _) | Expr::Annotated(_, expr, _) | Expr::Lambda(_, _, expr) => {
+                self.write_types(expr, types, names)
+            }
+            Expr::Assert(_, lhs, rhs) | Expr::Binop(_, lhs, _, rhs) | Expr::Let(_, _, lhs, rhs) => {
+                self.write_types(lhs, types, names);
+                self.write_types(rhs, types, names);
+            }
+            Expr::Call(_, fun, args) => {
+                self.write_types(fun, types, names);
+                args.iter_mut()
+                    .for_each(|arg| self.write_types(arg, types, names));
+            }
+            Expr::If(_, cond, then_, else_) => {
+                self.write_types(cond, types, names);
+                self.write_types(then_, types, names);
+                self.write_types(else_, types, names);
+            }
+            Expr::Literal(_, literal) => match literal {
+                Literal::Array(items) | Literal::Enum(_, items) => items
+                    .iter_mut()
+                    .for_each(|item| self.write_types(item, types, names)),
+                Literal::Number(_) => {}
+                Literal::Record(fields) => fields
+                    .iter_mut()
+                    .for_each(|(_, value)| self.write_types(value, types, names)),
+                Literal::LStr(parts) => parts.iter_mut().for_each(|part| {
+                    if let StringPart::Value(value) = part {
+                        self.write_types(value, types, names)
+                    }
+                }),
+            },
+            Expr::Placeholder(_) | Expr::Scoped(..) | Expr::Var(..) => {}
+            Expr::Switch(_, expr, arms) => {
+                self.write_types(expr, types, names);
+                for (_, guard, body) in arms {
+                    if let Some(guard) = guard {
+                        self.write_types(guard, types, names);
+                    }
+                    self.write_types(body, types, names);
+                }
+            }
+            Expr::Update(_, rec, fields) => {
+                self.write_types(rec, types, names);
+                fields
+                    .iter_mut()
+                    .for_each(|(_, _, value)| self.write_types(value, types, names));
+            }
+        }
+    }
+}
+
+/// Name type variables `a` to `z`, then `a1` to `z1` and so on
+fn var_name(index: usize) -> String {
+    let letter = char::from(b'a' + (index % 26) as u8);
+    match index / 26 {
+        0 => letter.to_string(),
+        n => format!("{}{}", letter, n),
+    }
+}
@@ -0,0 +1,666 @@
+use super::{check_module, infer_expr, TypeErrorKind};
+use crate::{
+    expr::{Literal, Meta},
+    module, Decl, Expr, Module, Operator, Pattern, Type,
+};
+
+fn infer(mut expr: Expr) -> Type {
+    infer_expr(&mut expr).unwrap_or_else(|errors| panic!("Unexpected errors: {:?}", errors))
+}
+fn infer_errors(mut expr: Expr) -> Vec<TypeErrorKind> {
+    infer_expr(&mut expr)
+        .expect_err("Expected a type error")
+        .into_iter()
+        .map(|e| e.kind().clone())
+        .collect()
+}
+fn lambda(param: &str, body: Expr) -> Expr {
+    Expr::lambda(vec![Pattern::Var(param.to_string())], body)
+}
+
+#[test]
+fn literals() {
+    assert_eq!(infer(Expr::literal(1)), Type::num());
+    assert_eq!(infer(Expr::literal("hello")), Type::string());
+    assert_eq!(
+        infer(Expr::literal(vec![Expr::literal(1), Expr::literal(2)])),
+        Type::arr(Type::num())
+    );
+    assert_eq!(
+        infer(Expr::literal(vec![
+            ("a", Expr::literal(1)),
+            ("b", Expr::literal("b")),
+        ])),
+        Type::rec([("a", Type::num()), ("b", Type::string())])
+    );
+}
+
+#[test]
+fn identity() {
+    assert_eq!(
+        infer(lambda("x", Expr::var("x"))),
+        Type::fun([Type::var("a")], Type::var("a"))
+    );
+}
+
+#[test]
+fn placeholders() {
+    let section = Expr::binop(Expr::placeholder(), Operator::Add, Expr::literal(1));
+    assert_eq!(
+        infer(section.replace_placeholders()),
+        Type::fun([Type::num()], Type::num())
+    );
+    let pair = Expr::literal(vec![Expr::placeholder(), Expr::placeholder()]);
+    assert_eq!(
+        infer(pair.replace_placeholders()),
+        Type::fun([Type::var("a"), Type::var("a")], Type::arr(Type::var("a")))
+    );
+}
+
+#[test]
+fn let_polymorphism() {
+    let expr = Expr::binding(
+        Pattern::Var("id".to_string()),
+        lambda("x", Expr::var("x")),
+        Expr::literal(vec![
+            ("n", Expr::apply(Expr::var("id"), Expr::literal(1))),
+            ("s", Expr::apply(Expr::var("id"), Expr::literal("s"))),
+        ]),
+    );
+    assert_eq!(
+        infer(expr),
+        Type::rec([("n", Type::num()), ("s", Type::string())])
+    );
+}
+
+#[test]
+fn lambda_params_are_monomorphic() {
+    let expr = lambda(
+        "f",
+        Expr::literal(vec![
+            Expr::apply(Expr::var("f"), Expr::literal(1)),
+            Expr::apply(Expr::var("f"), Expr::literal("s")),
+        ]),
+    );
+    assert!(matches!(
+        infer_errors(expr).as_slice(),
+        [TypeErrorKind::Mismatch { .. }]
+    ));
+}
+
+#[test]
+fn access_is_row_polymorphic() {
+    assert_eq!(
+        infer(lambda("r", Expr::access(Expr::var("r"), "x"))),
+        Type::access("x")
+    );
+    let get_x = lambda("r", Expr::access(Expr::var("r"), "x"));
+    let expr = Expr::apply(
+        get_x,
+        Expr::literal(vec![("x", Expr::literal(1)), ("y", Expr::literal("y"))]),
+    );
+    assert_eq!(infer(expr), Type::num());
+}
+
+#[test]
+fn access_missing_field() {
+    let expr = Expr::access(Expr::literal(vec![("y", Expr::literal(1))]), "x");
+    assert!(matches!(
+        infer_errors(expr).as_slice(),
+        [TypeErrorKind::Mismatch { .. }]
+    ));
+}
+
+#[test]
+fn open_variants() {
+    let expr = lambda(
+        "v",
+        Expr::switch(
+            Expr::var("v"),
+            vec![
+                (
+                    Literal::Enum("ok".to_string(), vec![Pattern::Var("a".to_string())]).into(),
+                    None,
+                    Expr::var("a"),
+                ),
+                (
+                    Literal::Enum("err".to_string(), vec![]).into(),
+                    None,
+                    Expr::literal(0),
+                ),
+            ],
+        ),
+    );
+    assert_eq!(
+        infer(expr),
+        Type::fun(
+            [Type::sum([("ok", vec![Type::num()]), ("err", vec![])])],
+            Type::num()
+        )
+    );
+}
+
+#[test]
+fn if_condition_must_be_boolean() {
+    let expr = Expr::conditional(Expr::literal(1), Expr::literal(2), Expr::literal(3));
+    assert_eq!(
+        infer_errors(expr),
+        vec![TypeErrorKind::Mismatch {
+            expected: Type::boolean(),
+            found: Type::num(),
+        }]
+    );
+    let expr = Expr::conditional(
+        Expr::binop(Expr::literal(1), Operator::Lt, Expr::literal(2)),
+        Expr::literal(2),
+        Expr::literal(3),
+    );
+    assert_eq!(infer(expr), Type::num());
+}
+
+#[test]
+fn annotation() {
+    let expr = Expr::Annotated(
+        Meta::default(),
+        Box::new(lambda("x", Expr::var("x"))),
+        Type::fun([Type::num()], Type::var("a")),
+    );
+    assert_eq!(infer(expr), Type::fun([Type::num()], Type::num()));
+    let expr = Expr::Annotated(Meta::default(), Box::new(Expr::literal("s")), Type::num());
+    assert!(matches!(
+        infer_errors(expr).as_slice(),
+        [TypeErrorKind::Mismatch { .. }]
+    ));
+}
+
+#[test]
+fn unbound_var() {
+    let expr = Expr::var("x").with_span(((2, 3), (2, 4)));
+    let mut errors = infer_expr(&mut expr.clone()).unwrap_err();
+    let error = errors.remove(0);
+    assert_eq!(error.kind(), &TypeErrorKind::UnboundVar("x".to_string()));
+    assert_eq!(error.to_string(), "2:3: unbound variable `x`");
+}
+
+#[test]
+fn infinite_type() {
+    let expr = lambda("x", Expr::apply(Expr::var("x"), Expr::var("x")));
+    assert!(matches!(
+        infer_errors(expr).as_slice(),
+        [TypeErrorKind::InfiniteType(..)]
+    ));
+}
+
+#[test]
+fn writes_types_into_meta() {
+    let mut expr = Expr::binding(
+        Pattern::Var("y".to_string()),
+        Expr::literal(2),
+        Expr::binop(Expr::literal(1), Operator::Add, Expr::var("y")),
+    );
+    infer_expr(&mut expr).unwrap();
+    let Expr::Let(meta, _, value, body) = &expr else {
+        unreachable!()
+    };
+    assert_eq!(meta.get_type(), &Type::num());
+    assert_eq!(value.meta().get_type(), &Type::num());
+    let Expr::Binop(meta, lhs, _, rhs) = body.as_ref() else {
+        unreachable!()
+    };
+    assert_eq!(meta.get_type(), &Type::num());
+    assert_eq!(lhs.meta().get_type(), &Type::num());
+    assert_eq!(rhs.meta().get_type(), &Type::num());
+}
+
+#[test]
+fn record_spreads() {
+    // (fun { a, ..rest } -> rest) { a: 1, b: "b" }
+    let expr = Expr::apply(
+        Expr::lambda(
+            vec![Literal::Record(vec![
+                ("a".to_string(), Pattern::Var("a".to_string())),
+                ("rest".to_string(), Pattern::Spread("rest".to_string())),
+            ])
+            .into()],
+            Expr::var("rest"),
+        ),
+        Expr::literal(Literal::Record(vec![
+            ("a".to_string(), Expr::literal(1)),
+            ("b".to_string(), Expr::literal("b")),
+        ])),
+    );
+    assert_eq!(infer(expr), Type::rec([("b", Type::string())]));
+}
+
+#[test]
+fn record_updates() {
+    let rec = || {
+        Expr::literal(Literal::Record(vec![
+            ("a".to_string(), Expr::literal(1)),
+            ("b".to_string(), Expr::literal("b")),
+        ]))
+    };
+    let span = crate::Span::from(((1, 5), (1, 9)));
+    assert_eq!(
+        infer(Expr::update(rec(), [(span, "a", Expr::literal(2))])),
+        Type::rec([("a", Type::num()), ("b", Type::string())])
+    );
+    // The field must keep its type, and errors are reported at the field
+    let mut expr = Expr::update(rec(), [(span, "a", Expr::literal("a"))]);
+    let errors = infer_expr(&mut expr).expect_err("Expected a type error");
+    assert!(matches!(
+        errors.as_slice(),
+        [e] if matches!(e.kind(), TypeErrorKind::Mismatch { .. }) && e.span() == span
+    ));
+    // Only fields already in the record can be updated
+    assert!(matches!(
+        infer_errors(Expr::update(rec(), [(span, "c", Expr::literal(2))])).as_slice(),
+        [TypeErrorKind::Mismatch { .. }]
+    ));
+}
+
+#[test]
+fn annotations() {
+    let id = || lambda("x", Expr::var("x"));
+    assert_eq!(
+        infer(Expr::annotated(id(), Type::fun([Type::num()], Type::num()))),
+        Type::fun([Type::num()], Type::num())
+    );
+    assert!(matches!(
+        infer_errors(Expr::annotated(Expr::literal(1), Type::string())).as_slice(),
+        [TypeErrorKind::Mismatch { .. }]
+    ));
+}
+
+#[test]
+fn assertions() {
+    let assertion = |cond| Expr::assert(cond, Expr::literal(1));
+    assert_eq!(
+        infer(assertion(Expr::binop(
+            Expr::literal(1),
+            Operator::Gt,
+            Expr::literal(0)
+        ))),
+        Type::num()
+    );
+    assert!(matches!(
+        infer_errors(assertion(Expr::literal(1))).as_slice(),
+        [TypeErrorKind::Mismatch { .. }]
+    ));
+}
+
+mod modules {
+    use super::*;
+
+    fn check(decls: Vec<Decl>) -> Module {
+        let mut module = Module::new(
+            module::Meta::default(),
+            std::iter::empty(),
+            decls.into_iter(),
+        );
+        check_module(&mut module)
+            .unwrap_or_else(|errors| panic!("Unexpected errors: {:?}", errors));
+        module
+    }
+    fn decl_type(module: &Module, name: &str) -> Type {
+        match module.declarations().iter().find(|d| d.name() == name) {
+            Some(decl) => decl.meta().get_type().clone(),
+            None => panic!("No declaration named {}", name),
+        }
+    }
+
+    #[test]
+    fn forward_references() {
+        let module = check(vec![
+            Decl::local(None, ((1, 1), (1, 1)), false, "a", Expr::var("b")),
+            Decl::local(None, ((2, 1), (2, 1)), false, "b", Expr::literal(1)),
+        ]);
+        assert_eq!(decl_type(&module, "a"), Type::num());
+        assert_eq!(decl_type(&module, "b"), Type::num());
+    }
+
+    #[test]
+    fn generalises_declarations() {
+        let module = check(vec![
+            Decl::local(
+                None,
+                ((1, 1), (1, 1)),
+                false,
+                "id",
+                lambda("x", Expr::var("x")),
+            ),
+            Decl::local(
+                None,
+                ((2, 1), (2, 1)),
+                false,
+                "pair",
+                Expr::literal(vec![
+                    ("n", Expr::apply(Expr::var("id"), Expr::literal(1))),
+                    ("s", Expr::apply(Expr::var("id"), Expr::literal("s"))),
+                ]),
+            ),
+        ]);
+        assert_eq!(
+            decl_type(&module, "id"),
+            Type::fun([Type::var("a")], Type::var("a"))
+        );
+    }
+
+    #[test]
+    fn recursion() {
+        // let length = xs => switch xs on [] -> 0 | [_, ..rest] -> 1 + length rest
+        let module = check(vec![Decl::local(
+            None,
+            ((1, 1), (1, 1)),
+            false,
+            "length",
+            lambda(
+                "xs",
+                Expr::switch(
+                    Expr::var("xs"),
+                    vec![
+                        (Literal::Array(vec![]).into(), None, Expr::literal(0)),
+                        (
+                            Literal::Array(vec![Pattern::Any, Pattern::Spread("rest".to_string())])
+                                .into(),
+                            None,
+                            Expr::binop(
+                                Expr::literal(1),
+                                Operator::Add,
+                                Expr::apply(Expr::var("length"), Expr::var("rest")),
+                            ),
+                        ),
+                    ],
+                ),
+            ),
+        )]);
+        assert_eq!(
+            decl_type(&module, "length"),
+            Type::fun([Type::arr(Type::var("a"))], Type::num())
+        );
+    }
+
+    #[test]
+    fn external_annotations() {
+        let module = check(vec![
+            Decl::external(
+                Some(Type::fun([Type::num()], Type::string())),
+                ((1, 1), (1, 1)),
+                false,
+                "show",
+                "show",
+            ),
+            Decl::local(
+                None,
+                ((2, 1), (2, 1)),
+                false,
+                "s",
+                Expr::apply(Expr::var("show"), Expr::literal(1)),
+            ),
+        ]);
+        assert_eq!(decl_type(&module, "s"), Type::string());
+    }
+
+    #[test]
+    fn type_declarations_are_expanded() {
+        let module = check(vec![
+            Decl::typ(
+                Type::rec([("x", Type::num())]),
+                ((1, 1), (1, 1)),
+                false,
+                "Point",
+            ),
+            Decl::local(
+                Some(Type::fun([Type::Con("Point".to_string())], Type::num())),
+                ((2, 1), (2, 1)),
+                false,
+                "getX",
+                lambda("p", Expr::access(Expr::var("p"), "x")),
+            ),
+        ]);
+        assert_eq!(
+            decl_type(&module, "getX"),
+            Type::fun([Type::Con("Point".to_string())], Type::num())
+        );
+    }
+
+    #[test]
+    fn generic_type_declarations_are_expanded() {
+        let module = check(vec![
+            Decl::generic_typ(
+                Type::sum([("just", vec![Type::var("a")]), ("nothing", vec![])]),
+                ((1, 1), (1, 1)),
+                false,
+                "Maybe",
+                ["a"],
+            ),
+            Decl::local(
+                Some(Type::fun(
+                    [Type::App(
+                        Box::new(Type::Con("Maybe".to_string())),
+                        vec![Type::num()],
+                    )],
+                    Type::num(),
+                )),
+                ((2, 1), (2, 1)),
+                false,
+                "withDefault",
+                lambda(
+                    "m",
+                    Expr::switch(
+                        Expr::var("m"),
+                        vec![
+                            (
+                                Pattern::Literal(Literal::Enum(
+                                    "just".to_string(),
+                                    vec![Pattern::Var("x".to_string())],
+                                )),
+                                None,
+                                Expr::var("x"),
+                            ),
+                            (Pattern::Any, None, Expr::literal(0)),
+                        ],
+                    ),
+                ),
+            ),
+        ]);
+        assert_eq!(
+            decl_type(&module, "withDefault"),
+            Type::fun(
+                [Type::App(
+                    Box::new(Type::Con("Maybe".to_string())),
+                    vec![Type::num()]
+                )],
+                Type::num()
+            )
+        );
+    }
+
+    #[test]
+    fn reports_undeclared_type_vars() {
+        let mut module = Module::new(
+            module::Meta::default(),
+            std::iter::empty(),
+            vec![Decl::generic_typ(
+                Type::sum([("ok", vec![Type::var("a")]), ("err", vec![Type::var("e")])]),
+                ((1, 1), (1, 30)),
+                false,
+                "Result",
+                ["a"],
+            )]
+            .into_iter(),
+        );
+        let errors = check_module(&mut module).unwrap_err();
+        assert_eq!(
+            errors.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec!["1:1: type variable `e` is not declared by type `Result`"]
+        );
+    }
+
+    #[test]
+    fn forall_annotations() {
+        let module = check(vec![Decl::local(
+            Some(Type::forall(
+                ["a", "b"],
+                Type::fun([Type::var("a"), Type::var("b")], Type::var("a")),
+            )),
+            ((1, 1), (1, 1)),
+            false,
+            "const",
+            Expr::lambda(
+                vec![Pattern::Var("x".to_string()), Pattern::Any],
+                Expr::var("x"),
+            ),
+        )]);
+        assert_eq!(
+            decl_type(&module, "const"),
+            Type::forall(
+                ["a", "b"],
+                Type::fun([Type::var("a"), Type::var("b")], Type::var("a")),
+            )
+        );
+    }
+
+    #[test]
+    fn reports_forall_errors() {
+        let decl = |typ, expr| Decl::local(Some(typ), ((1, 1), (1, 1)), false, "f", expr);
+        let errors = |decl| {
+            let mut module = Module::new(
+                module::Meta::default(),
+                std::iter::empty(),
+                std::iter::once(decl),
+            );
+            check_module(&mut module)
+                .unwrap_err()
+                .into_iter()
+                .map(|e| e.kind().clone())
+                .collect::<Vec<_>>()
+        };
+        let id = || lambda("x", Expr::var("x"));
+        assert_eq!(
+            errors(decl(
+                Type::forall(["a", "b"], Type::fun([Type::var("a")], Type::var("a"))),
+                id(),
+            )),
+            vec![TypeErrorKind::UnusedTypeVar("b".to_string())]
+        );
+        assert_eq!(
+            errors(decl(
+                Type::forall(["a"], Type::fun([Type::var("a")], Type::var("b"))),
+                Expr::var("f"),
+            )),
+            vec![TypeErrorKind::UnquantifiedTypeVar("b".to_string())]
+        );
+        // Quantified variables can't be instantiated by the value
+        assert!(matches!(
+            errors(decl(
+                Type::forall(["a"], Type::fun([Type::var("a")], Type::var("a"))),
+                lambda(
+                    "x",
+                    Expr::binop(Expr::var("x"), Operator::Add, Expr::literal(1))
+                ),
+            ))
+            .as_slice(),
+            [TypeErrorKind::Mismatch { .. }]
+        ));
+    }
+
+    #[test]
+    fn expectations_are_boolean() {
+        let expect = |expr| Decl::expect(((1, 1), (1, 1)), expr);
+        let module = check(vec![
+            Decl::local(None, ((1, 1), (1, 1)), false, "a", Expr::literal(1)),
+            expect(Expr::binop(Expr::var("a"), Operator::Eq, Expr::literal(1))),
+        ]);
+        assert_eq!(module.declarations()[1].meta().get_type(), &Type::boolean());
+        let mut module = Module::new(
+            module::Meta::default(),
+            std::iter::empty(),
+            std::iter::once(expect(Expr::literal("a"))),
+        );
+        assert!(matches!(
+            check_module(&mut module).unwrap_err()[0].kind(),
+            TypeErrorKind::Mismatch { .. }
+        ));
+    }
+
+    #[test]
+    fn infix_declarations() {
+        let module = check(vec![
+            Decl::infix(
+                ((1, 1), (1, 1)),
+                false,
+                crate::Fixity::DEFAULT,
+                "<+>",
+                lambda(
+                    "a",
+                    lambda("b", Expr::literal(vec![Expr::var("a"), Expr::var("b")])),
+                ),
+            ),
+            Decl::local(
+                None,
+                ((2, 1), (2, 1)),
+                false,
+                "x",
+                Expr::apply_many(Expr::var("<+>"), [Expr::literal(1), Expr::literal(2)]),
+            ),
+        ]);
+        assert_eq!(
+            decl_type(&module, "<+>"),
+            Type::fun([Type::var("a"), Type::var("a")], Type::arr(Type::var("a")))
+        );
+        assert_eq!(decl_type(&module, "x"), Type::arr(Type::num()));
+    }
+
+    #[test]
+    fn exposed_names() {
+        let mut module = Module::new(
+            module::Meta::default(),
+            std::iter::once(crate::module::import::Import::project(
+                "./list".to_string(),
+                vec![],
+                vec!["map".to_string(), "size".to_string()],
+            )),
+            vec![
+                // Each use of an exposed name may have a different type
+                Decl::local(
+                    None,
+                    ((1, 1), (1, 1)),
+                    false,
+                    "a",
+                    Expr::literal(vec![
+                        Expr::apply(Expr::var("map"), Expr::literal(1)),
+                        Expr::apply(Expr::var("map"), Expr::literal("a")),
+                    ]),
+                ),
+                // Declarations shadow exposed names
+                Decl::local(None, ((2, 1), (2, 1)), false, "size", Expr::literal(1)),
+                Decl::local(None, ((3, 1), (3, 1)), false, "b", Expr::var("size")),
+            ]
+            .into_iter(),
+        );
+        check_module(&mut module).unwrap();
+        assert_eq!(decl_type(&module, "b"), Type::num());
+    }
+
+    #[test]
+    fn reports_errors_in_declarations() {
+        let mut module = Module::new(
+            module::Meta::default(),
+            std::iter::empty(),
+            vec![Decl::local(
+                Some(Type::string()),
+                ((1, 1), (1, 10)),
+                false,
+                "a",
+                Expr::literal(1).with_span(((1, 9), (1, 10))),
+            )]
+            .into_iter(),
+        );
+        let errors = check_module(&mut module).unwrap_err();
+        assert_eq!(
+            errors.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec!["1:9: type mismatch: expected String, found Number"]
+        );
+    }
+}
@@ -1,11 +1,16 @@
+pub mod codegen;
 pub mod decl;
+pub mod exhaustiveness;
 pub mod expr;
+pub mod infer;
 pub mod module;
+pub mod pretty;
 mod ren_type;
+pub mod resolve;
 mod span;
 
 pub use decl::Decl;
-pub use expr::{Expr, Literal, Operator, Pattern};
+pub use expr::{Associativity, Expr, Fixities, Fixity, Literal, Operator, Pattern};
 pub use module::{import::Import, Module};
 pub use ren_type::Type;
 pub use span::Span;
@@ -1,14 +1,17 @@
 #![allow(dead_code)] //XXX
 use ren_json_derive::RenJson;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Import {
     pub source: Source,
     pub path: String,
+    #[serde(rename = "name")]
     pub alias: Vec<String>,
-    // pub unqualified: Vec<String>,
+    /// Names brought into scope unqualified, e.g. `map` in `import "./list" exposing { map }`
+    #[serde(default)]
+    pub exposing: Vec<String>,
 }
-//TODO: Import tagged serialise
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, RenJson)]
 pub enum Source {
@@ -19,30 +22,30 @@ pub enum Source {
 
 impl Import {
     // CONSTRUCTORS ----------------------------------------------------------------
-    pub fn project(path: String, alias: Vec<String> /* , unqualified: Vec<String>*/) -> Self {
+    pub fn project(path: String, alias: Vec<String>, exposing: Vec<String>) -> Self {
         Self {
             source: Source::Project,
             path,
             alias,
-            // unqualified,
+            exposing,
         }
     }
 
-    pub fn package(path: String, alias: Vec<String> /* , unqualified: Vec<String>*/) -> Self {
+    pub fn package(path: String, alias: Vec<String>, exposing: Vec<String>) -> Self {
         Self {
             source: Source::Package,
             path,
             alias,
-            // unqualified,
+            exposing,
         }
     }
 
-    pub fn external(path: String, alias: Vec<String> /* , unqualified: Vec<String>*/) -> Self {
+    pub fn external(path: String, alias: Vec<String>, exposing: Vec<String>) -> Self {
         Self {
             source: Source::External,
             path,
             alias,
-            // unqualified,
+            exposing,
         }
     }
 
@@ -73,35 +76,29 @@ impl Import {
     //         a
 
     // CONVERSION ------------------------------------------------------------------
-    pub fn to_json_str(&self, _pretty: bool) -> ::serde_json::Result<String> {
-        Ok("TODO: serialise Import to json".to_string())
-        /*
+    pub fn to_json_str(&self, pretty: bool) -> ::serde_json::Result<String> {
         if pretty {
             ::serde_json::to_string_pretty(self)
         } else {
             ::serde_json::to_string(self)
-        }*/
+        }
     }
-    pub fn to_json_bytes(&self, _pretty: bool) -> ::serde_json::Result<Vec<u8>> {
-        Ok("TODO: serialise Import to json".bytes().collect())
-        /*
+    pub fn to_json_bytes(&self, pretty: bool) -> ::serde_json::Result<Vec<u8>> {
         if pretty {
-            ::serde_json::to_string_pretty(self)
+            ::serde_json::to_vec_pretty(self)
         } else {
-            ::serde_json::to_string(self)
-        }*/
+            ::serde_json::to_vec(self)
+        }
     }
     pub fn to_json_writer<W: std::io::Write>(
         &self,
-        mut _w: W,
-        _pretty: bool,
+        w: W,
+        pretty: bool,
     ) -> ::serde_json::Result<()> {
-        Ok(())
-        /*TODO: serialise Import to json
         if pretty {
-            ::serde_json::to_writer_pretty(&mut w, self)
+            ::serde_json::to_writer_pretty(w, self)
         } else {
-            ::serde_json::to_writer(&mut w, self)
-        }*/
+            ::serde_json::to_writer(w, self)
+        }
     }
 }
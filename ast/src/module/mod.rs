@@ -4,6 +4,8 @@ use crate::decl::Decl;
 
 pub mod import;
 use import::Import;
+#[cfg(test)]
+mod tests;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Meta {
@@ -24,10 +26,39 @@ impl Default for Meta {
         }
     }
 }
+impl Meta {
+    pub fn new<N, P>(name: N, path: P) -> Self
+    where
+        N: ToString,
+        P: ToString,
+    {
+        Self {
+            name: name.to_string(),
+            path: path.to_string(),
+            ..Default::default()
+        }
+    }
+    /// Set the directory package imports are resolved in, relative to the module
+    pub fn with_pkg_path<S: ToString>(mut self, pkg_path: S) -> Self {
+        self.pkg_path = pkg_path.to_string();
+        self
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+    pub fn pkg_path(&self) -> &str {
+        &self.pkg_path
+    }
+    pub fn uses_ffi(&self) -> bool {
+        self.uses_ffi
+    }
+}
 
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Module(Meta, Vec<Import>, Vec<Decl>);
-//TODO: Module serialise
 
 impl Module {
     pub fn new<I, D>(mut meta: Meta, imports: I, declarations: D) -> Self
@@ -46,6 +77,15 @@ impl Module {
     pub fn meta_mut(&mut self) -> &mut Meta {
         &mut self.0
     }
+    pub fn all_imports(&self) -> &[Import] {
+        &self.1
+    }
+    pub fn declarations(&self) -> &[Decl] {
+        &self.2
+    }
+    pub fn declarations_mut(&mut self) -> &mut [Decl] {
+        &mut self.2
+    }
 
     pub fn imports(&self, name: &str) -> bool {
         self.1.iter().any(|imp| imp.path == name)
@@ -97,6 +137,13 @@ impl Module {
     }
 
     // MANIPULATIONS ---------------------------------------------------------------
+    /// Replace the metadata of the module, such as its name and path. Whether the module uses
+    /// the FFI is kept, as it depends on the declarations.
+    pub fn with_meta(mut self, mut meta: Meta) -> Self {
+        meta.uses_ffi = self.0.uses_ffi;
+        self.0 = meta;
+        self
+    }
     // fn push_import(&mut self, import: Import) {
     //     self.1.push(import);
     // }
@@ -109,4 +156,31 @@ impl Module {
     // fn push_decl<I>(&mut self, decls: I) where I: IntoIterator<Item = Decl> {
     //     self.2.extend(imports);
     // }
+
+    // CONVERSION ------------------------------------------------------------------
+    pub fn to_json_str(&self, pretty: bool) -> ::serde_json::Result<String> {
+        if pretty {
+            ::serde_json::to_string_pretty(self)
+        } else {
+            ::serde_json::to_string(self)
+        }
+    }
+    pub fn to_json_bytes(&self, pretty: bool) -> ::serde_json::Result<Vec<u8>> {
+        if pretty {
+            ::serde_json::to_vec_pretty(self)
+        } else {
+            ::serde_json::to_vec(self)
+        }
+    }
+    pub fn to_json_writer<W: std::io::Write>(
+        &self,
+        w: W,
+        pretty: bool,
+    ) -> ::serde_json::Result<()> {
+        if pretty {
+            ::serde_json::to_writer_pretty(w, self)
+        } else {
+            ::serde_json::to_writer(w, self)
+        }
+    }
 }
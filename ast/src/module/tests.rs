@@ -0,0 +1,145 @@
+use expect_test::expect;
+
+use super::{import::Import, Meta, Module};
+use crate::{Decl, Expr, Type};
+
+fn sample() -> Module {
+    Module::new(
+        Meta::new("Main", "src/Main.ren"),
+        vec![Import::package(
+            "ren/array".to_string(),
+            vec!["Array".to_string()],
+            vec!["map".to_string()],
+        )]
+        .into_iter(),
+        vec![
+            Decl::external(Some(Type::num()), ((1, 1), (1, 20)), false, "now", "now"),
+            Decl::local(None, ((2, 1), (2, 12)), true, "x", Expr::var("now")),
+        ]
+        .into_iter(),
+    )
+}
+
+#[test]
+fn serialise() {
+    expect![[r#"
+        [
+          {
+            "name": "Main",
+            "path": "src/Main.ren",
+            "pkgPath": ".pkg/",
+            "usesFFI": true
+          },
+          [
+            {
+              "source": [
+                {
+                  "$": "Package"
+                }
+              ],
+              "path": "ren/array",
+              "name": [
+                "Array"
+              ],
+              "exposing": [
+                "map"
+              ]
+            }
+          ],
+          [
+            [
+              {
+                "$": "Ext",
+                "type": [
+                  {
+                    "$": "Con"
+                  },
+                  "Number"
+                ],
+                "inferred": false,
+                "span": [
+                  [
+                    1,
+                    1
+                  ],
+                  [
+                    1,
+                    20
+                  ]
+                ],
+                "comment": []
+              },
+              [
+                false,
+                "now",
+                "now"
+              ]
+            ],
+            [
+              {
+                "$": "Let",
+                "type": [
+                  {
+                    "$": "Hole"
+                  }
+                ],
+                "inferred": true,
+                "span": [
+                  [
+                    2,
+                    1
+                  ],
+                  [
+                    2,
+                    12
+                  ]
+                ],
+                "comment": []
+              },
+              [
+                true,
+                "x",
+                [
+                  {
+                    "$": "Var",
+                    "type": [
+                      {
+                        "$": "Hole"
+                      }
+                    ],
+                    "span": [
+                      [
+                        1,
+                        1
+                      ],
+                      [
+                        1,
+                        1
+                      ]
+                    ],
+                    "comment": []
+                  },
+                  "now"
+                ]
+              ]
+            ]
+          ]
+        ]"#]]
+    .assert_eq(&sample().to_json_str(true).unwrap());
+}
+
+#[test]
+fn round_trip() {
+    let module = sample();
+    let json = module.to_json_str(false).unwrap();
+    assert_eq!(serde_json::from_str::<Module>(&json).unwrap(), module);
+    for decl in module.declarations() {
+        let json = decl.to_json_str(false).unwrap();
+        assert_eq!(&serde_json::from_str::<Decl>(&json).unwrap(), decl);
+    }
+    let import = &module.all_imports()[0];
+    assert_eq!(
+        &serde_json::from_str::<Import>(&import.to_json_str(false).unwrap()).unwrap(),
+        import
+    );
+}
@@ -0,0 +1,175 @@
+/// A document in Wadler's "prettier printer" algebra.
+///
+/// Documents are built from text and line breaks, which can be grouped. When a group is
+/// rendered, all of the line breaks in it are rendered flat (as a space or nothing) if the
+/// group fits in the remaining width, otherwise they are all rendered as newlines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Doc {
+    Nil,
+    Text(String),
+    /// A space when flat, otherwise a newline
+    Line,
+    /// Nothing when flat, otherwise a newline
+    SoftLine,
+    /// Always a newline. Any group containing one can never be flat.
+    HardLine,
+    /// Increase the indentation of any newlines within the document
+    Nest(usize, Box<Doc>),
+    Concat(Vec<Doc>),
+    Group(Box<Doc>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Flat,
+    Break,
+}
+
+impl Doc {
+    // CONSTRUCTORS ----------------------------------------------------------------
+    pub fn text<S: ToString>(text: S) -> Self {
+        Self::Text(text.to_string())
+    }
+    pub fn concat<I>(docs: I) -> Self
+    where
+        I: IntoIterator<Item = Doc>,
+    {
+        Self::Concat(docs.into_iter().collect())
+    }
+    /// Concatenate the documents, with `sep` between each of them
+    pub fn join<I>(docs: I, sep: Doc) -> Self
+    where
+        I: IntoIterator<Item = Doc>,
+    {
+        let mut parts = Vec::new();
+        for doc in docs {
+            if !parts.is_empty() {
+                parts.push(sep.clone());
+            }
+            parts.push(doc);
+        }
+        Self::Concat(parts)
+    }
+
+    // MANIPULATIONS ---------------------------------------------------------------
+    pub fn append<D: Into<Doc>>(self, doc: D) -> Self {
+        match self {
+            Self::Concat(mut parts) => {
+                parts.push(doc.into());
+                Self::Concat(parts)
+            }
+            _ => Self::Concat(vec![self, doc.into()]),
+        }
+    }
+    pub fn nest(self, indent: usize) -> Self {
+        Self::Nest(indent, Box::new(self))
+    }
+    pub fn group(self) -> Self {
+        Self::Group(Box::new(self))
+    }
+    /// Wrap the document in `open` and `close`, with the contents indented on their own lines
+    /// if the group does not fit on a single line
+    pub fn bracket(self, open: &str, close: &str, indent: usize) -> Self {
+        Doc::text(open)
+            .append(Doc::SoftLine.append(self).nest(indent))
+            .append(Doc::SoftLine)
+            .append(Doc::text(close))
+            .group()
+    }
+
+    // CONVERSION ------------------------------------------------------------------
+    /// Render the document, trying to keep lines no longer than `width`
+    pub fn render(&self, width: usize) -> String {
+        let mut out = String::new();
+        let mut column = 0;
+        // Indentation is only written once there is text on the line, so that blank lines
+        // have no trailing whitespace
+        let mut pending_indent = None;
+        let mut stack = vec![(0, Mode::Break, self)];
+        while let Some((indent, mode, doc)) = stack.pop() {
+            match doc {
+                Doc::Nil => {}
+                Doc::Text(text) if text == " " && pending_indent.is_some() => {}
+                Doc::Text(text) => {
+                    if let Some(indent) = pending_indent.take() {
+                        out.extend(std::iter::repeat_n(' ', indent));
+                    }
+                    out.push_str(text);
+                    column += text.chars().count();
+                }
+                Doc::Line if mode == Mode::Flat => {
+                    out.push(' ');
+                    column += 1;
+                }
+                Doc::SoftLine if mode == Mode::Flat => {}
+                // A break straight after a newline, e.g. one ending a comment, only changes
+                // the indentation of the line
+                Doc::Line | Doc::SoftLine if pending_indent.is_some() => {
+                    pending_indent = Some(indent);
+                    column = indent;
+                }
+                Doc::Line | Doc::SoftLine | Doc::HardLine => {
+                    out.push('\n');
+                    pending_indent = Some(indent);
+                    column = indent;
+                }
+                Doc::Nest(extra, doc) => stack.push((indent + extra, mode, doc)),
+                Doc::Concat(docs) => stack.extend(docs.iter().rev().map(|doc| (indent, mode, doc))),
+                Doc::Group(doc) => {
+                    let mode =
+                        if mode == Mode::Flat || fits(width.saturating_sub(column), doc, &stack) {
+                            Mode::Flat
+                        } else {
+                            Mode::Break
+                        };
+                    stack.push((indent, mode, doc));
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Whether `doc` fits in `width` when rendered flat, along with the rest of the line after it
+fn fits(width: usize, doc: &Doc, rest: &[(usize, Mode, &Doc)]) -> bool {
+    let mut remaining = width;
+    let mut stack = vec![(Mode::Flat, doc)];
+    let mut rest = rest.iter().rev();
+    loop {
+        let (mode, doc) = match stack.pop() {
+            Some(item) => item,
+            None => match rest.next() {
+                Some((_, mode, doc)) => (*mode, *doc),
+                None => return true,
+            },
+        };
+        match doc {
+            Doc::Nil | Doc::SoftLine if mode == Mode::Flat => {}
+            Doc::Nil => {}
+            Doc::Text(text) => match remaining.checked_sub(text.chars().count()) {
+                Some(r) => remaining = r,
+                None => return false,
+            },
+            Doc::Line if mode == Mode::Flat => match remaining.checked_sub(1) {
+                Some(r) => remaining = r,
+                None => return false,
+            },
+            // A newline in the rest of the document ends the line being measured
+            Doc::Line | Doc::SoftLine => return true,
+            Doc::HardLine => return mode == Mode::Break,
+            Doc::Nest(_, doc) | Doc::Group(doc) => stack.push((mode, doc)),
+            Doc::Concat(docs) => stack.extend(docs.iter().rev().map(|doc| (mode, doc))),
+        }
+    }
+}
+
+impl From<&str> for Doc {
+    fn from(text: &str) -> Self {
+        Self::text(text)
+    }
+}
+impl From<String> for Doc {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
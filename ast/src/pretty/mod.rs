@@ -0,0 +1,781 @@
+//! Print the AST as Ren source code.
+//!
+//! The AST is first converted to a [`Doc`], which is then rendered to fit a given width,
+//! breaking groups across multiple lines only when they do not fit on one. Parentheses are
+//! only added where they are needed for the output to parse back to the same AST, based on
+//! the binding powers of the operators (see [`Operator::infix_binding_power`]).
+
+use std::fmt::{Display, Formatter};
+
+use crate::{
+    decl::Decl,
+    expr::{
+        is_placeholder_name, operator::is_operator_name, Associativity, Expr, Fixities, Fixity,
+        Literal, Operator, Pattern, StringPart,
+    },
+    module::{
+        import::{Import, Source},
+        Module,
+    },
+    ren_type::Type,
+};
+
+mod doc;
+#[cfg(test)]
+mod tests;
+
+pub use doc::Doc;
+
+/// The width used when printing with [`Display`] if no width is specified, e.g. `{:60}`
+pub const DEFAULT_WIDTH: usize = 80;
+const INDENT: usize = 4;
+
+pub trait Pretty {
+    /// Convert to a document, laying out user-defined operators by the given fixities.
+    /// Operators without a declared fixity are always parenthesised.
+    fn to_doc_with(&self, fixities: &Fixities) -> Doc;
+
+    fn to_doc(&self) -> Doc {
+        self.to_doc_with(&Fixities::new())
+    }
+    /// Print as Ren source, trying to keep lines no longer than `width`
+    fn pretty(&self, width: usize) -> String {
+        self.to_doc().render(width)
+    }
+    /// Print as Ren source, laying out user-defined operators by the given fixities
+    fn pretty_with(&self, width: usize, fixities: &Fixities) -> String {
+        self.to_doc_with(fixities).render(width)
+    }
+}
+
+/// The position an expression or pattern is printed in, which decides whether it needs to be
+/// wrapped in parentheses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Prec {
+    /// An operand of an infix operator. `min` is the binding power an operator needs to
+    /// continue the surrounding expression, `next` is the operator after this operand, if
+    /// there is one, and `lhs` is whether this is the left operand.
+    Operand {
+        min: u8,
+        next: Option<Infix>,
+        lhs: bool,
+    },
+    /// The function being called, or the record being accessed
+    Callee,
+    /// An argument to a function or enum constructor
+    Arg,
+}
+
+/// Anywhere a whole expression is allowed, e.g. at the top level or inside brackets
+const TOP: Prec = Prec::Operand {
+    min: 0,
+    next: None,
+    lhs: false,
+};
+
+/// An infix operator whose binding power is known: a built-in operator, or a user-defined one
+/// with a declared fixity
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Infix {
+    Builtin(Operator),
+    Custom(Fixity),
+}
+impl Infix {
+    fn binding_power(&self) -> (u8, u8) {
+        match self {
+            Infix::Builtin(op) => op.infix_binding_power(),
+            Infix::Custom(fixity) => fixity.binding_power(),
+        }
+    }
+}
+
+fn parens(doc: Doc) -> Doc {
+    doc.bracket("(", ")", INDENT)
+}
+
+/// Things that can be the items of a [`Literal`]
+trait Term {
+    fn doc(&self, prec: Prec, fixities: &Fixities) -> Doc;
+    /// The variable name, if this is just a variable. Used to print record fields like
+    /// `{ a: a }` as `{ a }`.
+    fn var_name(&self) -> Option<&str>;
+    /// Whether this is a `..name` spread, which is printed without its key in a record
+    fn is_spread(&self) -> bool {
+        false
+    }
+}
+
+// EXPRESSIONS -----------------------------------------------------------------
+
+impl Term for Expr {
+    fn doc(&self, prec: Prec, fixities: &Fixities) -> Doc {
+        if expr_needs_parens(self, prec, fixities) {
+            return parens(self.doc(TOP, fixities));
+        }
+        match self {
+            Expr::Access(_, obj, key) => obj
+                .doc(Prec::Callee, fixities)
+                .append(".")
+                .append(key.as_str()),
+            Expr::Annotated(_, expr, typ) => parens(
+                expr.doc(TOP, fixities)
+                    .append(" :")
+                    .append(Doc::Line.append(type_doc(typ, TypePrec::Top)).nest(INDENT)),
+            ),
+            Expr::Assert(_, cond, body) => Doc::text("assert ")
+                .append(cond.doc(TOP, fixities))
+                .append(";")
+                .append(Doc::Line)
+                .append(body.doc(TOP, fixities))
+                .group(),
+            Expr::Binop(..) => infix_doc(self, prec, fixities),
+            Expr::Call(..) if infix(self, fixities).is_some() => infix_doc(self, prec, fixities),
+            Expr::Call(_, fun, args) => match operator_call(self) {
+                Some((op, lhs, rhs)) => lhs.doc(Prec::Arg, fixities).append(
+                    Doc::Line
+                        .append(op)
+                        .append(" ")
+                        .append(rhs.doc(Prec::Arg, fixities))
+                        .nest(INDENT),
+                ),
+                None => fun.doc(Prec::Callee, fixities).append(
+                    Doc::concat(
+                        args.iter()
+                            .map(|arg| Doc::Line.append(arg.doc(Prec::Arg, fixities))),
+                    )
+                    .nest(INDENT),
+                ),
+            }
+            .group(),
+            Expr::If(_, cond, then, els) => Doc::text("if ")
+                .append(cond.doc(TOP, fixities))
+                .append(" then")
+                .append(Doc::Line.append(then.doc(TOP, fixities)).nest(INDENT))
+                .append(Doc::Line)
+                .append("else")
+                .append(match els.as_ref() {
+                    // Keep `else if` chains at the same indentation
+                    Expr::If(..) => Doc::text(" ").append(els.doc(TOP, fixities)),
+                    _ => Doc::Line.append(els.doc(TOP, fixities)).nest(INDENT),
+                })
+                .group(),
+            // Lambdas made from placeholders are printed as they were written, e.g. `_ + 1`
+            Expr::Lambda(_, params, body) if is_placeholder_lambda(params) => {
+                body.doc(prec, fixities)
+            }
+            Expr::Lambda(_, params, body) => Doc::text("fun ")
+                .append(params_doc(params, fixities))
+                .append(" ->")
+                .append(Doc::Line.append(body.doc(TOP, fixities)).nest(INDENT))
+                .group(),
+            Expr::Let(_, pattern, value, body) => Doc::text("let ")
+                .append(pattern.doc(TOP, fixities))
+                .append(" =")
+                .append(binding_doc(value, fixities))
+                .append(";")
+                .append(Doc::Line)
+                .append(body.doc(TOP, fixities))
+                .group(),
+            Expr::Literal(_, lit) => literal_doc(lit, fixities),
+            Expr::Placeholder(_) => Doc::text("_"),
+            Expr::Scoped(_, namespace, name) => {
+                Doc::text(format!("{}.{}", namespace.join("."), name))
+            }
+            Expr::Switch(_, scrutinee, branches) => {
+                Doc::text("switch ")
+                    .append(scrutinee.doc(TOP, fixities))
+                    .append(" on")
+                    .append(
+                        Doc::concat(branches.iter().enumerate().map(
+                            |(i, (pattern, guard, body))| {
+                                let last = i + 1 == branches.len();
+                                let mut branch =
+                                    Doc::Line.append("case ").append(pattern.doc(TOP, fixities));
+                                if let Some(guard) = guard {
+                                    branch = branch
+                                        .append(" if ")
+                                        .append(switch_part_doc(guard, false, fixities));
+                                }
+                                branch.append(" ->").append(
+                                    Doc::Line
+                                        .append(switch_part_doc(body, last, fixities))
+                                        .nest(INDENT)
+                                        .group(),
+                                )
+                            },
+                        ))
+                        .nest(INDENT),
+                    )
+                    .group()
+            }
+            Expr::Update(_, rec, fields) => {
+                // Only a variable can be updated without parentheses
+                let rec = match rec.as_ref() {
+                    Expr::Var(_, name) if !is_placeholder_name(name) => Doc::text(name),
+                    Expr::Annotated(..) => rec.doc(TOP, fixities),
+                    _ => parens(rec.doc(TOP, fixities)),
+                };
+                Doc::text("{ ")
+                    .append(rec)
+                    .append(" |")
+                    .append(
+                        Doc::Line
+                            .append(Doc::join(
+                                fields
+                                    .iter()
+                                    .map(|(_, key, value)| field_doc(key, value, fixities)),
+                                Doc::text(",").append(Doc::Line),
+                            ))
+                            .nest(INDENT),
+                    )
+                    .append(Doc::Line)
+                    .append("}")
+                    .group()
+            }
+            Expr::Var(_, name) if is_placeholder_name(name) => Doc::text("_"),
+            Expr::Var(_, name) => Doc::text(name),
+        }
+    }
+
+    fn var_name(&self) -> Option<&str> {
+        match self {
+            Expr::Var(_, name) => Some(name),
+            _ => None,
+        }
+    }
+}
+
+fn expr_needs_parens(expr: &Expr, prec: Prec, fixities: &Fixities) -> bool {
+    if let (Some((_, _, op, _)), Prec::Operand { min, next, .. }) = (infix(expr, fixities), prec) {
+        let (left, right) = op.binding_power();
+        return left < min || next.is_some_and(|next| next.binding_power().0 >= right);
+    }
+    match (expr, prec) {
+        (Expr::Annotated(..) | Expr::Placeholder(_) | Expr::Scoped(..) | Expr::Var(..), _) => false,
+        (Expr::Lambda(_, params, body), _) if is_placeholder_lambda(params) => {
+            expr_needs_parens(body, prec, fixities)
+        }
+        (Expr::Literal(_, Literal::Number(n)), Prec::Callee | Prec::Arg) => n.is_sign_negative(),
+        // Enum constructors can only be applied at the start of an expression
+        (Expr::Literal(_, Literal::Enum(..)), _) => prec != TOP,
+        (Expr::Literal(..) | Expr::Update(..), _) => false,
+        // Without a declared fixity, the grouping of user-defined operators isn't known, so
+        // they're always parenthesised unless they're the whole expression
+        (Expr::Call(..), _) if operator_call(expr).is_some() => prec != TOP,
+        // Calls and accesses end the expression they start, so they can't be followed by an
+        // operator if they're on the left. On the right, a following `-` would be parsed as
+        // a negative argument.
+        (Expr::Access(..) | Expr::Call(..), Prec::Operand { next, lhs, .. }) => {
+            lhs || next == Some(Infix::Builtin(Operator::Sub))
+        }
+        (Expr::Access(..), Prec::Callee) => false,
+        (Expr::Access(..) | Expr::Call(..), _) => true,
+        (Expr::Binop(..), _) => true,
+        (
+            Expr::Assert(..) | Expr::If(..) | Expr::Lambda(..) | Expr::Let(..) | Expr::Switch(..),
+            _,
+        ) => prec != TOP,
+    }
+}
+
+/// Whether a lambda was made by [`Expr::replace_placeholders`]
+fn is_placeholder_lambda(params: &[Pattern]) -> bool {
+    !params.is_empty()
+        && params
+            .iter()
+            .all(|param| matches!(param, Pattern::Var(name) if is_placeholder_name(name)))
+}
+
+/// The operator and operands of a call to a user-defined infix operator, e.g. `a <+> b`
+fn operator_call(expr: &Expr) -> Option<(&str, &Expr, &Expr)> {
+    match expr {
+        Expr::Call(_, fun, args) => match (fun.as_ref(), args.as_slice()) {
+            (Expr::Var(_, op), [lhs, rhs]) if is_operator_name(op) => Some((op, lhs, rhs)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The left operand, symbol, operator and right operand of an expression using an infix
+/// operator whose binding power is known
+fn infix<'e>(expr: &'e Expr, fixities: &Fixities) -> Option<(&'e Expr, &'e str, Infix, &'e Expr)> {
+    match expr {
+        Expr::Binop(_, lhs, op, rhs) => Some((lhs, op.symbol(), Infix::Builtin(*op), rhs)),
+        _ => {
+            let (op, lhs, rhs) = operator_call(expr)?;
+            Some((lhs, op, Infix::Custom(fixities.declared(op)?), rhs))
+        }
+    }
+}
+
+fn infix_doc(expr: &Expr, prec: Prec, fixities: &Fixities) -> Doc {
+    match (infix(expr, fixities), prec) {
+        (Some((lhs, symbol, op, rhs)), Prec::Operand { min, next, .. }) => {
+            binop_doc(lhs, symbol, op, rhs, min, next, fixities).group()
+        }
+        _ => unreachable!("{:?} in {:?} should be parenthesised", expr, prec),
+    }
+}
+
+/// The operands of a chain of operators with the same binding power, without a group, so
+/// that `a |> f |> g` breaks before every operator
+fn binop_doc(
+    lhs: &Expr,
+    symbol: &str,
+    op: Infix,
+    rhs: &Expr,
+    min: u8,
+    next: Option<Infix>,
+    fixities: &Fixities,
+) -> Doc {
+    let (left, right) = op.binding_power();
+    let lhs_prec = Prec::Operand {
+        min,
+        next: Some(op),
+        lhs: true,
+    };
+    let lhs = match infix(lhs, fixities) {
+        Some((l, lsymbol, lop, r))
+            if lop.binding_power().0 == left && !expr_needs_parens(lhs, lhs_prec, fixities) =>
+        {
+            binop_doc(l, lsymbol, lop, r, min, Some(op), fixities)
+        }
+        _ => lhs.doc(lhs_prec, fixities),
+    };
+    let rhs_prec = Prec::Operand {
+        min: right,
+        next,
+        lhs: false,
+    };
+    lhs.append(
+        Doc::Line
+            .append(symbol)
+            .append(" ")
+            .append(rhs.doc(rhs_prec, fixities))
+            .nest(INDENT),
+    )
+}
+
+/// The value bound by a `let`. Functions start on the same line, so that only their body is
+/// indented.
+fn binding_doc(expr: &Expr, fixities: &Fixities) -> Doc {
+    match expr {
+        Expr::Lambda(..) => Doc::text(" ").append(expr.doc(TOP, fixities)),
+        _ => Doc::Line
+            .append(expr.doc(TOP, fixities))
+            .group()
+            .nest(INDENT),
+    }
+}
+
+/// Every parameter but the last is an argument, so must be parenthesised if it could take
+/// arguments itself
+fn params_doc(params: &[Pattern], fixities: &Fixities) -> Doc {
+    Doc::join(
+        params.iter().enumerate().map(|(i, param)| {
+            param.doc(
+                if i + 1 == params.len() {
+                    TOP
+                } else {
+                    Prec::Arg
+                },
+                fixities,
+            )
+        }),
+        Doc::text(" "),
+    )
+}
+
+/// A guard or branch of a switch. A nested switch at the end would take all of the following
+/// branches, so has to be parenthesised unless this is the last branch.
+fn switch_part_doc(expr: &Expr, last: bool, fixities: &Fixities) -> Doc {
+    fn ends_with_switch(expr: &Expr) -> bool {
+        match expr {
+            Expr::Switch(..) => true,
+            Expr::Assert(_, _, body)
+            | Expr::If(_, _, _, body)
+            | Expr::Lambda(_, _, body)
+            | Expr::Let(_, _, _, body) => ends_with_switch(body),
+            _ => false,
+        }
+    }
+    if !last && ends_with_switch(expr) {
+        parens(expr.doc(TOP, fixities))
+    } else {
+        expr.doc(TOP, fixities)
+    }
+}
+
+// PATTERNS --------------------------------------------------------------------
+
+impl Term for Pattern {
+    fn doc(&self, prec: Prec, fixities: &Fixities) -> Doc {
+        let doc = match self {
+            Pattern::Any => return Doc::text("_"),
+            Pattern::Literal(lit) => literal_doc(lit, fixities),
+            Pattern::Spread(name) => return Doc::text(format!("..{}", name)),
+            Pattern::Type(name, pattern) => Doc::text(format!("@{} ", name))
+                .append(pattern.doc(Prec::Arg, fixities))
+                .group(),
+            Pattern::Var(name) => return Doc::text(name),
+        };
+        let needs_parens = prec == Prec::Arg
+            && match self {
+                Pattern::Literal(Literal::Enum(..)) | Pattern::Type(..) => true,
+                Pattern::Literal(Literal::Number(n)) => n.is_sign_negative(),
+                _ => false,
+            };
+        if needs_parens {
+            parens(doc)
+        } else {
+            doc
+        }
+    }
+
+    fn var_name(&self) -> Option<&str> {
+        match self {
+            Pattern::Var(name) => Some(name),
+            _ => None,
+        }
+    }
+    fn is_spread(&self) -> bool {
+        matches!(self, Pattern::Spread(_))
+    }
+}
+
+// LITERALS --------------------------------------------------------------------
+
+fn literal_doc<T: Term>(lit: &Literal<T>, fixities: &Fixities) -> Doc {
+    match lit {
+        Literal::Array(items) => Doc::join(
+            items.iter().map(|item| item.doc(TOP, fixities)),
+            Doc::text(",").append(Doc::Line),
+        )
+        .bracket("[", "]", INDENT),
+        Literal::Enum(tag, args) => Doc::text(format!("#{}", tag))
+            .append(
+                Doc::concat(
+                    args.iter()
+                        .map(|arg| Doc::Line.append(arg.doc(Prec::Arg, fixities))),
+                )
+                .nest(INDENT),
+            )
+            .group(),
+        Literal::Number(n) => Doc::text(n),
+        Literal::Record(fields) if fields.is_empty() => Doc::text("{}"),
+        Literal::Record(fields) => Doc::text("{")
+            .append(
+                Doc::Line
+                    .append(Doc::join(
+                        fields
+                            .iter()
+                            .map(|(key, value)| field_doc(key, value, fixities)),
+                        Doc::text(",").append(Doc::Line),
+                    ))
+                    .nest(INDENT),
+            )
+            .append(Doc::Line)
+            .append("}")
+            .group(),
+        Literal::LStr(parts) => {
+            let mut s = String::from("\"");
+            for part in parts {
+                match part {
+                    StringPart::Text(text) => {
+                        for c in text.chars() {
+                            match c {
+                                '\\' => s.push_str("\\\\"),
+                                '"' => s.push_str("\\\""),
+                                '$' => s.push_str("\\$"),
+                                '\n' => s.push_str("\\n"),
+                                '\r' => s.push_str("\\r"),
+                                '\t' => s.push_str("\\t"),
+                                c => s.push(c),
+                            }
+                        }
+                    }
+                    // Strings are always kept on a single line
+                    StringPart::Value(value) => {
+                        s.push_str("${");
+                        s.push_str(&value.doc(TOP, fixities).render(usize::MAX));
+                        s.push('}');
+                    }
+                }
+            }
+            s.push('"');
+            Doc::Text(s)
+        }
+    }
+}
+
+/// A field of a record literal or update, which is printed as just its name if its value is a
+/// variable of the same name
+fn field_doc<T: Term>(key: &str, value: &T, fixities: &Fixities) -> Doc {
+    match value.var_name() {
+        Some(name) if name == key => Doc::text(key),
+        _ if value.is_spread() => value.doc(TOP, fixities),
+        _ => Doc::text(format!("{}: ", key)).append(value.doc(TOP, fixities)),
+    }
+}
+
+// TYPES -----------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TypePrec {
+    Top,
+    /// The argument of a function type
+    FunArg,
+    /// The argument of a type application or sum variant
+    AppArg,
+}
+
+fn type_doc(typ: &Type, prec: TypePrec) -> Doc {
+    fn sorted<V>(row: &std::collections::HashMap<String, V>) -> Vec<(&String, &V)> {
+        let mut row = row.iter().collect::<Vec<_>>();
+        row.sort_by_key(|(k, _)| *k);
+        row
+    }
+    fn args_doc(args: &[Type]) -> Doc {
+        Doc::concat(
+            args.iter()
+                .map(|arg| Doc::Line.append(type_doc(arg, TypePrec::AppArg))),
+        )
+        .nest(INDENT)
+    }
+
+    let (doc, needs_parens) = match typ {
+        Type::Any => (Doc::text("*"), false),
+        Type::App(fun, args) => (
+            type_doc(fun, TypePrec::AppArg)
+                .append(args_doc(args))
+                .group(),
+            prec == TypePrec::AppArg,
+        ),
+        Type::Con(name) | Type::Var(name) => (Doc::text(name), false),
+        Type::Forall(vars, typ) => (
+            Doc::text(format!("forall {}.", vars.join(" ")))
+                .append(Doc::Line.append(type_doc(typ, TypePrec::Top)).nest(INDENT))
+                .group(),
+            prec != TypePrec::Top,
+        ),
+        Type::Fun(arg, ret) => (
+            type_doc(arg, TypePrec::FunArg)
+                .append(" ->")
+                .append(Doc::Line)
+                .append(type_doc(ret, TypePrec::Top))
+                .group(),
+            prec != TypePrec::Top,
+        ),
+        Type::Hole => (Doc::text("?"), false),
+        Type::Rec(row) if row.is_empty() => (Doc::text("{}"), false),
+        Type::Rec(row) => (
+            Doc::text("{")
+                .append(
+                    Doc::Line
+                        .append(Doc::join(
+                            sorted(row).into_iter().map(|(key, types)| {
+                                Doc::text(format!("{}:", key)).append(Doc::concat(
+                                    types
+                                        .iter()
+                                        .map(|t| Doc::text(" ").append(type_doc(t, TypePrec::Top))),
+                                ))
+                            }),
+                            Doc::text(",").append(Doc::Line),
+                        ))
+                        .nest(INDENT),
+                )
+                .append(Doc::Line)
+                .append("}")
+                .group(),
+            false,
+        ),
+        Type::Sum(row) => (
+            Doc::join(
+                sorted(row)
+                    .into_iter()
+                    .map(|(tag, args)| Doc::text(format!("#{}", tag)).append(args_doc(args))),
+                Doc::Line.append("| "),
+            )
+            .group(),
+            prec != TypePrec::Top,
+        ),
+    };
+    if needs_parens {
+        parens(doc)
+    } else {
+        doc
+    }
+}
+
+// DECLARATIONS ----------------------------------------------------------------
+
+fn annotation_doc(meta: &crate::decl::Meta) -> Doc {
+    if meta.is_inferred() {
+        Doc::Nil
+    } else {
+        Doc::text(" :").append(
+            Doc::Line
+                .append(type_doc(meta.get_type(), TypePrec::Top))
+                .nest(INDENT),
+        )
+    }
+}
+
+impl Pretty for Decl {
+    fn to_doc_with(&self, fixities: &Fixities) -> Doc {
+        let meta = self.meta();
+        let mut doc = Doc::Nil;
+        if self.is_exposed() {
+            doc = doc.append("pub ");
+        }
+        let doc = match self {
+            Decl::Let { var, expr, .. } => doc
+                .append(format!("let {}", var))
+                .append(annotation_doc(meta))
+                .append(" =")
+                .append(binding_doc(expr, fixities)),
+            Decl::Infix {
+                fixity, op, expr, ..
+            } => doc
+                .append(match fixity.associativity {
+                    Associativity::Left => "infixl ",
+                    Associativity::Right => "infixr ",
+                })
+                .append(format!("{} {} =", fixity.precedence, op))
+                .append(binding_doc(expr, fixities)),
+            Decl::Ext { var, name, .. } => doc
+                .append(format!("ext {}", var))
+                .append(annotation_doc(meta))
+                .append(" =")
+                .append(
+                    Doc::Line
+                        .append(literal_doc::<Expr>(
+                            &Literal::LStr(vec![name.as_str().into()]),
+                            fixities,
+                        ))
+                        .nest(INDENT),
+                ),
+            Decl::Type {
+                name, vars, typ, ..
+            } => doc
+                .append(format!("type {}", name))
+                .append(Doc::concat(
+                    vars.iter().map(|var| Doc::text(format!(" {}", var))),
+                ))
+                .append(" =")
+                .append(Doc::Line.append(type_doc(typ, TypePrec::Top)).nest(INDENT)),
+            Decl::Expect { expr, .. } => doc
+                .append("expect")
+                .append(Doc::Line.append(expr.doc(TOP, fixities)).nest(INDENT)),
+        };
+        doc.group()
+    }
+}
+
+// IMPORTS ---------------------------------------------------------------------
+
+impl Pretty for Import {
+    fn to_doc_with(&self, fixities: &Fixities) -> Doc {
+        let mut doc = Doc::text("import ");
+        doc = match self.source {
+            Source::External => doc.append("ext "),
+            Source::Package => doc.append("pkg "),
+            Source::Project => doc,
+        };
+        doc = doc.append(literal_doc::<Expr>(
+            &Literal::LStr(vec![self.path.as_str().into()]),
+            fixities,
+        ));
+        if !self.alias.is_empty() {
+            doc = doc.append(format!(" as {}", self.alias.join(".")));
+        }
+        if !self.exposing.is_empty() {
+            doc = doc.append(format!(" exposing {{ {} }}", self.exposing.join(", ")));
+        }
+        doc
+    }
+}
+
+// MODULES ---------------------------------------------------------------------
+
+impl Pretty for Module {
+    /// Operators declared in the module are laid out by their fixity, which takes precedence
+    /// over any given one
+    fn to_doc_with(&self, fixities: &Fixities) -> Doc {
+        let mut fixities = fixities.clone();
+        fixities.extend(
+            &self
+                .declarations()
+                .iter()
+                .filter_map(|decl| match decl {
+                    Decl::Infix { fixity, op, .. } => Some((op.as_str(), *fixity)),
+                    _ => None,
+                })
+                .collect(),
+        );
+        let imports = Doc::concat(
+            self.all_imports()
+                .iter()
+                .map(|import| import.to_doc_with(&fixities).append(Doc::HardLine)),
+        );
+        let decls = Doc::join(
+            self.declarations()
+                .iter()
+                .map(|decl| decl.to_doc_with(&fixities).append(Doc::HardLine)),
+            Doc::HardLine,
+        );
+        if self.all_imports().is_empty() || self.declarations().is_empty() {
+            imports.append(decls)
+        } else {
+            imports.append(Doc::HardLine).append(decls)
+        }
+    }
+}
+
+// OTHER -----------------------------------------------------------------------
+
+impl Pretty for Expr {
+    fn to_doc_with(&self, fixities: &Fixities) -> Doc {
+        self.doc(TOP, fixities)
+    }
+}
+impl Pretty for Pattern {
+    fn to_doc_with(&self, fixities: &Fixities) -> Doc {
+        self.doc(TOP, fixities)
+    }
+}
+impl Pretty for Literal<Expr> {
+    fn to_doc_with(&self, fixities: &Fixities) -> Doc {
+        literal_doc(self, fixities)
+    }
+}
+impl Pretty for Literal<Pattern> {
+    fn to_doc_with(&self, fixities: &Fixities) -> Doc {
+        literal_doc(self, fixities)
+    }
+}
+impl Pretty for Type {
+    fn to_doc_with(&self, _: &Fixities) -> Doc {
+        type_doc(self, TypePrec::Top)
+    }
+}
+
+macro_rules! display_pretty {
+    ($($t:ty),+ $(,)?) => {$(
+        impl Display for $t {
+            fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{}", self.pretty(f.width().unwrap_or(DEFAULT_WIDTH)))
+            }
+        }
+    )+};
+}
+display_pretty![
+    Expr,
+    Pattern,
+    Literal<Expr>,
+    Literal<Pattern>,
+    Decl,
+    Import,
+    Module,
+];
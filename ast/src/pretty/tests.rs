@@ -0,0 +1,290 @@
+use expect_test::{expect, Expect};
+
+use super::{Doc, Pretty};
+use crate::{
+    expr::{fixtures::var, Literal, StringPart},
+    module, Decl, Expr, Import, Module, Operator, Pattern, Span, Type,
+};
+
+fn check<T: Pretty>(value: &T, width: usize, expected: Expect) {
+    expected.assert_eq(&value.pretty(width));
+}
+fn add(lhs: Expr, rhs: Expr) -> Expr {
+    Expr::binop(lhs, Operator::Add, rhs)
+}
+
+#[test]
+fn groups_break_when_too_wide() {
+    let doc = Doc::text("[")
+        .append(
+            Doc::SoftLine
+                .append(Doc::join(
+                    ["one", "two", "three"].map(Doc::text),
+                    Doc::text(",").append(Doc::Line),
+                ))
+                .nest(2),
+        )
+        .append(Doc::SoftLine)
+        .append("]")
+        .group();
+    expect!["[one, two, three]"].assert_eq(&doc.render(17));
+    expect![[r#"
+        [
+          one,
+          two,
+          three
+        ]"#]]
+    .assert_eq(&doc.render(16));
+}
+
+#[test]
+fn minimal_parentheses() {
+    let (a, b, c) = (Expr::var("a"), Expr::var("b"), Expr::var("c"));
+    check(
+        &Expr::binop(add(a.clone(), b.clone()), Operator::Mul, c.clone()),
+        80,
+        expect!["(a + b) * c"],
+    );
+    check(
+        &add(a.clone(), Expr::binop(b.clone(), Operator::Mul, c.clone())),
+        80,
+        expect!["a + b * c"],
+    );
+    check(
+        &Expr::binop(
+            a.clone(),
+            Operator::Sub,
+            Expr::binop(b.clone(), Operator::Sub, c.clone()),
+        ),
+        80,
+        expect!["a - (b - c)"],
+    );
+    check(
+        &Expr::binop(
+            Expr::binop(a.clone(), Operator::Concat, b.clone()),
+            Operator::Concat,
+            c.clone(),
+        ),
+        80,
+        expect!["(a <> b) <> c"],
+    );
+    check(
+        &Expr::binop(
+            a.clone(),
+            Operator::Concat,
+            Expr::binop(b.clone(), Operator::Concat, c.clone()),
+        ),
+        80,
+        expect!["a <> b <> c"],
+    );
+    check(
+        &add(
+            Expr::apply(Expr::var("f"), a.clone()),
+            Expr::apply(Expr::var("g"), b.clone()),
+        ),
+        80,
+        expect!["(f a) + g b"],
+    );
+    check(
+        &Expr::apply_many(
+            Expr::var("f"),
+            [
+                Expr::apply(Expr::var("g"), a.clone()),
+                Expr::literal(-1),
+                Expr::literal(Literal::Enum("just".to_string(), vec![b.clone()])),
+                Expr::access(c.clone(), "d"),
+            ],
+        ),
+        80,
+        expect!["f (g a) (-1) (#just b) (c.d)"],
+    );
+    check(
+        &Expr::lambda(
+            vec![
+                Pattern::Literal(Literal::Enum("just".to_string(), vec![var("x")])),
+                Pattern::Literal(Literal::Enum("just".to_string(), vec![var("y")])),
+            ],
+            add(a, b),
+        ),
+        80,
+        expect!["fun (#just x) #just y -> a + b"],
+    );
+}
+
+#[test]
+fn expressions_break_across_lines() {
+    let pipeline = [("map", 2), ("filter", 3), ("sum", 0)].into_iter().fold(
+        Expr::var("numbers"),
+        |acc, (f, n)| {
+            let f = Expr::scoped(vec!["List".to_string()], f.to_string());
+            let f = if n > 0 {
+                Expr::apply(f, Expr::literal(n))
+            } else {
+                f
+            };
+            Expr::binop(acc, Operator::Pipe, f)
+        },
+    );
+    check(
+        &pipeline,
+        80,
+        expect!["numbers |> List.map 2 |> List.filter 3 |> List.sum"],
+    );
+    check(
+        &pipeline,
+        30,
+        expect![[r#"
+            numbers
+                |> List.map 2
+                |> List.filter 3
+                |> List.sum"#]],
+    );
+    let conditional = Expr::conditional(
+        Expr::binop(Expr::var("count"), Operator::Gt, Expr::literal(10)),
+        Expr::literal(Literal::LStr(vec![
+            StringPart::Text("many \"things\": ".to_string()),
+            StringPart::Value(Expr::var("count")),
+        ])),
+        Expr::binding(
+            var("x"),
+            Expr::literal(1),
+            add(Expr::var("x"), Expr::var("count")),
+        ),
+    );
+    check(
+        &conditional,
+        30,
+        expect![[r#"
+            if count > 10 then
+                "many \"things\": ${count}"
+            else
+                let x = 1; x + count"#]],
+    );
+    check(
+        &Expr::switch(
+            Expr::var("x"),
+            vec![
+                (
+                    Pattern::Literal(Literal::Record(vec![
+                        ("a".to_string(), var("a")),
+                        ("b".to_string(), Pattern::Any),
+                    ])),
+                    Some(Expr::var("a")),
+                    Expr::literal(vec![Expr::var("a"), Expr::literal(2)]),
+                ),
+                (Pattern::Any, None, Expr::literal(())),
+            ],
+        ),
+        40,
+        expect![[r#"
+            switch x on
+                case { a, b: _ } if a -> [a, 2]
+                case _ -> #undefined"#]],
+    );
+}
+
+#[test]
+fn modules() {
+    let module = Module::new(
+        module::Meta::new("Main", "src/Main.ren"),
+        [
+            Import::package(
+                "std/list".to_string(),
+                vec!["List".to_string()],
+                vec!["map".to_string()],
+            ),
+            Import::external("./ffi.js".to_string(), vec![], vec![]),
+        ]
+        .into_iter(),
+        [
+            Decl::local(
+                Some(Type::fun(
+                    [Type::num(), Type::arr(Type::var("a"))],
+                    Type::num(),
+                )),
+                Span::default(),
+                true,
+                "f",
+                Expr::lambda(vec![var("n"), Pattern::Any], Expr::var("n")),
+            ),
+            Decl::external(
+                Some(Type::fun([Type::string()], Type::Hole)),
+                Span::default(),
+                false,
+                "log",
+                "console.log",
+            ),
+            Decl::typ(
+                Type::rec([("x", Type::num()), ("y", Type::num())]),
+                Span::default(),
+                true,
+                "Point",
+            ),
+        ]
+        .into_iter(),
+    );
+    check(
+        &module,
+        80,
+        expect![[r#"
+            import pkg "std/list" as List exposing { map }
+            import ext "./ffi.js"
+
+            pub let f : Number -> Array a -> Number = fun n _ -> n
+
+            ext log : String -> ? = "console.log"
+
+            pub type Point = { x: Number, y: Number }
+        "#]],
+    );
+}
+
+#[test]
+fn user_defined_operators() {
+    use crate::{Associativity, Fixities, Fixity};
+    let call = |op: &str, lhs: Expr, rhs: Expr| Expr::apply_many(Expr::var(op), [lhs, rhs]);
+    let (a, b, c) = (Expr::var("a"), Expr::var("b"), Expr::var("c"));
+    let left = call(
+        "<+>",
+        call("<+>", a.clone(), b.clone()),
+        add(c.clone(), a.clone()),
+    );
+    let right = call("<+>", a.clone(), call("<+>", b.clone(), c.clone()));
+    // Without a fixity, every call but the outermost is parenthesised
+    check(&left, 80, expect!["(a <+> b) <+> (c + a)"]);
+    check(&right, 80, expect!["a <+> (b <+> c)"]);
+
+    let mut fixities = Fixities::new();
+    fixities.insert("<+>", Fixity::new(Associativity::Left, 5));
+    expect!["a <+> b <+> c + a"].assert_eq(&left.pretty_with(80, &fixities));
+    expect!["a <+> (b <+> c)"].assert_eq(&right.pretty_with(80, &fixities));
+    expect!["(a <+> (b <+> c)) * a"].assert_eq(
+        &Expr::binop(right.clone(), Operator::Mul, a.clone()).pretty_with(80, &fixities),
+    );
+
+    // Modules lay out the operators they declare by their fixity
+    let module = Module::new(
+        module::Meta::default(),
+        std::iter::empty(),
+        [
+            Decl::infix(
+                Span::default(),
+                false,
+                Fixity::new(Associativity::Right, 5),
+                "<+>",
+                Expr::var("add"),
+            ),
+            Decl::local(None, Span::default(), false, "x", right),
+        ]
+        .into_iter(),
+    );
+    check(
+        &module,
+        80,
+        expect![[r#"
+            infixr 5 <+> = add
+
+            let x = a <+> b <+> c
+        "#]],
+    );
+}
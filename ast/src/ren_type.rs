@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use ren_json_derive::RenJson;
 
-#[derive(Debug, Clone, PartialEq, Eq, RenJson)]
+#[derive(Debug, Clone, PartialEq, Eq, RenJson, Default)]
 pub enum Type {
     /// any type, e.g. "*"
     Any,
@@ -10,9 +10,12 @@ pub enum Type {
     App(Box<Type>, Vec<Type>),
     /// concrete type constructor, e.g. "Number"
     Con(String),
+    /// explicitly quantified type, e.g. "forall a. a -> a"
+    Forall(Vec<String>, Box<Type>),
     /// function type, e.g. "Number -> Number"
     Fun(Box<Type>, Box<Type>),
     /// unknown (to the user) type, e.g. "?"
+    #[default]
     Hole,
     /// record type, e.g. "{x: Number, y: Number}"
     Rec(Row),
@@ -21,11 +24,6 @@ pub enum Type {
     /// type variable, e.g. "a"
     Var(String),
 }
-impl Default for Type {
-    fn default() -> Self {
-        Self::Hole
-    }
-}
 
 type Row = HashMap<String, Vec<Type>>;
 
@@ -40,6 +38,16 @@ impl Type {
     pub fn boolean() -> Self {
         Self::sum([("true", []), ("false", [])])
     }
+    pub fn forall<I, S>(vars: I, typ: Type) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: ToString,
+    {
+        Self::Forall(
+            vars.into_iter().map(|v| v.to_string()).collect(),
+            Box::new(typ),
+        )
+    }
     pub fn fun<T>(args: T, ret: Type) -> Self
     where
         T: IntoIterator<Item = Type>,
@@ -87,6 +95,47 @@ impl Type {
         Self::Var(v.to_string())
     }
 
+    // QUERIES ---------------------------------------------------------------------
+    /// The names of the free type variables in the type, in the order they first appear.
+    /// Record fields and variants are visited in name order.
+    pub fn vars(&self) -> Vec<&str> {
+        fn collect<'t>(typ: &'t Type, vars: &mut Vec<&'t str>) {
+            match typ {
+                Type::Any | Type::Con(_) | Type::Hole => {}
+                Type::App(fun, args) => {
+                    collect(fun, vars);
+                    args.iter().for_each(|arg| collect(arg, vars));
+                }
+                Type::Forall(quantified, typ) => {
+                    for var in typ.vars() {
+                        if !quantified.iter().any(|q| q == var) && !vars.contains(&var) {
+                            vars.push(var);
+                        }
+                    }
+                }
+                Type::Fun(arg, ret) => {
+                    collect(arg, vars);
+                    collect(ret, vars);
+                }
+                Type::Rec(row) | Type::Sum(row) => {
+                    let mut keys = row.keys().collect::<Vec<_>>();
+                    keys.sort();
+                    keys.into_iter()
+                        .flat_map(|k| &row[k])
+                        .for_each(|t| collect(t, vars));
+                }
+                Type::Var(v) => {
+                    if !vars.contains(&v.as_str()) {
+                        vars.push(v);
+                    }
+                }
+            }
+        }
+        let mut vars = Vec::new();
+        collect(self, &mut vars);
+        vars
+    }
+
     // fn fresh(n: u8) -> String {
     //     if n >= 26 {
     //         format!("{}{}", Self::fresh((n / 26) - 1), Self::fresh(n % 26))
@@ -100,7 +149,7 @@ impl core::fmt::Display for Type {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         fn parens(t: &Type) -> String {
             match t {
-                Type::App(..) | Type::Fun(..) => format!("({})", t),
+                Type::App(..) | Type::Forall(..) | Type::Fun(..) => format!("({})", t),
                 _ => t.to_string(),
             }
         }
@@ -109,11 +158,12 @@ impl core::fmt::Display for Type {
             Type::App(t1, tn) => write!(
                 f,
                 "{} {}",
-                parens(&*t1),
+                parens(t1),
                 tn.iter().map(parens).collect::<Vec<_>>().join(" ")
             ),
             Type::Con(c) => write!(f, "{}", c),
-            Type::Fun(t1, t2) => write!(f, "{} → {}", parens(&*t1), *t2),
+            Type::Forall(vars, t) => write!(f, "∀{}. {}", vars.join(" "), t),
+            Type::Fun(t1, t2) => write!(f, "{} → {}", parens(t1), *t2),
             Type::Hole => write!(f, "?"),
             Type::Rec(r) => {
                 write!(f, "{{")?;
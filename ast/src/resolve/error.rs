@@ -0,0 +1,42 @@
+use crate::span::Span;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolveErrorKind {
+    /// A variable was used without being bound
+    UnboundVar(String),
+    /// A scoped variable used a namespace which no import is aliased as
+    UnknownNamespace(Vec<String>),
+}
+
+/// A variable which could not be resolved, located at its use.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolveError {
+    kind: ResolveErrorKind,
+    span: Span,
+}
+impl ResolveError {
+    pub(super) fn new(kind: ResolveErrorKind, span: Span) -> Self {
+        Self { kind, span }
+    }
+    pub fn kind(&self) -> &ResolveErrorKind {
+        &self.kind
+    }
+    pub fn span(&self) -> Span {
+        self.span
+    }
+}
+impl core::fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ((line, col), _): ((usize, usize), (usize, usize)) = self.span.into();
+        write!(f, "{}:{}: ", line, col)?;
+        match &self.kind {
+            ResolveErrorKind::UnboundVar(name) => write!(f, "unbound variable `{}`", name),
+            ResolveErrorKind::UnknownNamespace(namespace) => write!(
+                f,
+                "unknown namespace `{}`, no import is aliased as it",
+                namespace.join(".")
+            ),
+        }
+    }
+}
+impl std::error::Error for ResolveError {}
@@ -0,0 +1,233 @@
+//! Name resolution, linking every use of a variable to the declaration, lambda parameter,
+//! `let` pattern, case pattern or import which binds it.
+//!
+//! Scoping follows the rest of the compiler: every declaration is in scope throughout the
+//! module, shadowing any name exposed by an import, and a `let` which binds a single variable
+//! may refer to itself.
+
+use crate::{
+    decl::Decl,
+    expr::{Expr, Literal, Pattern, StringPart},
+    module::Module,
+    span::Span,
+};
+
+mod error;
+#[cfg(test)]
+mod tests;
+
+pub use error::{ResolveError, ResolveErrorKind};
+
+/// Where a variable is bound
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Binding {
+    /// A declaration, by its index in [`Module::declarations`]
+    Decl(usize),
+    /// A name exposed by an import, by the index of the import in [`Module::all_imports`]
+    Exposed(usize),
+    /// The alias of an import used as a namespace, by the index of the import in
+    /// [`Module::all_imports`]. Nothing is known about the names the other module declares.
+    Namespace(usize),
+    /// A parameter of the lambda with this span
+    Param(Span),
+    /// The pattern of the `let` expression with this span
+    Let(Span),
+    /// The pattern of a case, by the span of its `switch` and the index of the case
+    Case(Span, usize),
+}
+
+/// A use of a variable and the binding it refers to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reference {
+    pub span: Span,
+    /// The namespace of a scoped variable, e.g. `List` in `List.map`, which is empty for an
+    /// unqualified variable
+    pub namespace: Vec<String>,
+    pub name: String,
+    pub binding: Binding,
+}
+
+/// The references found in a module, and the uses which could not be resolved
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Resolution {
+    references: Vec<Reference>,
+    errors: Vec<ResolveError>,
+}
+impl Resolution {
+    /// Every resolved reference, in the order they appear in the module
+    pub fn references(&self) -> &[Reference] {
+        &self.references
+    }
+    pub fn errors(&self) -> &[ResolveError] {
+        &self.errors
+    }
+    pub fn has_errors(&self) -> bool {
+        !self.errors.is_empty()
+    }
+    /// The binding of the variable used at `span`, if it was resolved
+    pub fn binding_at(&self, span: Span) -> Option<Binding> {
+        self.references
+            .iter()
+            .find(|reference| reference.span == span)
+            .map(|reference| reference.binding)
+    }
+    /// Every use of a binding
+    pub fn references_to(&self, binding: Binding) -> impl Iterator<Item = &Reference> {
+        self.references
+            .iter()
+            .filter(move |reference| reference.binding == binding)
+    }
+}
+
+/// Resolve every variable in a module. Resolution carries on past unbound variables, so
+/// that the references which can be resolved are still found.
+pub fn resolve_module(module: &Module) -> Resolution {
+    let mut resolver = Resolver {
+        namespaces: module
+            .all_imports()
+            .iter()
+            .map(|import| import.alias.clone())
+            .collect(),
+        ..Default::default()
+    };
+    for (index, import) in module.all_imports().iter().enumerate() {
+        for name in &import.exposing {
+            resolver.scope.push((name.clone(), Binding::Exposed(index)));
+        }
+    }
+    for (index, decl) in module.declarations().iter().enumerate() {
+        if let Decl::Let { var, .. } | Decl::Ext { var, .. } | Decl::Infix { op: var, .. } = decl {
+            resolver.scope.push((var.clone(), Binding::Decl(index)));
+        }
+    }
+    for decl in module.declarations() {
+        if let Decl::Let { expr, .. } | Decl::Infix { expr, .. } | Decl::Expect { expr, .. } = decl
+        {
+            resolver.expr(expr);
+        }
+    }
+    resolver.resolution
+}
+
+#[derive(Default)]
+struct Resolver {
+    /// The alias of each import, which is empty for an import without one
+    namespaces: Vec<Vec<String>>,
+    /// The variables in scope, innermost last
+    scope: Vec<(String, Binding)>,
+    resolution: Resolution,
+}
+
+impl Resolver {
+    fn lookup(&self, name: &str) -> Option<Binding> {
+        self.scope
+            .iter()
+            .rev()
+            .find(|(n, _)| n == name)
+            .map(|(_, binding)| *binding)
+    }
+    fn bind(&mut self, pattern: &Pattern, binding: Binding) {
+        self.scope
+            .extend(pattern.bindings().into_iter().map(|name| (name, binding)));
+    }
+    fn reference(&mut self, span: Span, namespace: &[String], name: &str, binding: Binding) {
+        self.resolution.references.push(Reference {
+            span,
+            namespace: namespace.to_vec(),
+            name: name.to_string(),
+            binding,
+        });
+    }
+    fn error(&mut self, kind: ResolveErrorKind, span: Span) {
+        self.resolution.errors.push(ResolveError::new(kind, span));
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        let span = expr.meta().get_span();
+        match expr {
+            Expr::Access(_, expr, _) | Expr::Annotated(_, expr, _) => self.expr(expr),
+            Expr::Assert(_, condition, body) => {
+                self.expr(condition);
+                self.expr(body);
+            }
+            Expr::Binop(_, lhs, _, rhs) => {
+                self.expr(lhs);
+                self.expr(rhs);
+            }
+            Expr::Call(_, func, args) => {
+                self.expr(func);
+                args.iter().for_each(|arg| self.expr(arg));
+            }
+            Expr::If(_, condition, then, otherwise) => {
+                self.expr(condition);
+                self.expr(then);
+                self.expr(otherwise);
+            }
+            Expr::Lambda(_, params, body) => {
+                let mark = self.scope.len();
+                for param in params {
+                    self.bind(param, Binding::Param(span));
+                }
+                self.expr(body);
+                self.scope.truncate(mark);
+            }
+            // A `let` of a single variable may be recursive, so it is in scope in its value
+            Expr::Let(_, pattern @ Pattern::Var(_), value, body) => {
+                let mark = self.scope.len();
+                self.bind(pattern, Binding::Let(span));
+                self.expr(value);
+                self.expr(body);
+                self.scope.truncate(mark);
+            }
+            Expr::Let(_, pattern, value, body) => {
+                self.expr(value);
+                let mark = self.scope.len();
+                self.bind(pattern, Binding::Let(span));
+                self.expr(body);
+                self.scope.truncate(mark);
+            }
+            Expr::Literal(_, literal) => self.literal(literal),
+            Expr::Placeholder(_) => {}
+            Expr::Scoped(_, namespace, name) => {
+                match self.namespaces.iter().position(|alias| alias == namespace) {
+                    Some(index) => self.reference(span, namespace, name, Binding::Namespace(index)),
+                    None => self.error(ResolveErrorKind::UnknownNamespace(namespace.clone()), span),
+                }
+            }
+            Expr::Switch(_, subject, cases) => {
+                self.expr(subject);
+                for (index, (pattern, guard, body)) in cases.iter().enumerate() {
+                    let mark = self.scope.len();
+                    self.bind(pattern, Binding::Case(span, index));
+                    if let Some(guard) = guard {
+                        self.expr(guard);
+                    }
+                    self.expr(body);
+                    self.scope.truncate(mark);
+                }
+            }
+            Expr::Update(_, target, fields) => {
+                self.expr(target);
+                fields.iter().for_each(|(_, _, value)| self.expr(value));
+            }
+            Expr::Var(_, name) => match self.lookup(name) {
+                Some(binding) => self.reference(span, &[], name, binding),
+                None => self.error(ResolveErrorKind::UnboundVar(name.clone()), span),
+            },
+        }
+    }
+    fn literal(&mut self, literal: &Literal<Expr>) {
+        match literal {
+            Literal::Array(items) | Literal::Enum(_, items) => {
+                items.iter().for_each(|item| self.expr(item))
+            }
+            Literal::Number(_) => {}
+            Literal::Record(fields) => fields.iter().for_each(|(_, value)| self.expr(value)),
+            Literal::LStr(parts) => parts.iter().for_each(|part| {
+                if let StringPart::Value(value) = part {
+                    self.expr(value);
+                }
+            }),
+        }
+    }
+}
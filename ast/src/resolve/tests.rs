@@ -0,0 +1,257 @@
+use super::{resolve_module, Binding, Resolution, ResolveErrorKind};
+use crate::{
+    expr::{fixtures::var as pvar, Literal},
+    module, Decl, Expr, Import, Module, Operator, Pattern, Span,
+};
+
+fn resolve(imports: Vec<Import>, decls: Vec<Decl>) -> Resolution {
+    let module = Module::new(
+        module::Meta::default(),
+        imports.into_iter(),
+        decls.into_iter(),
+    );
+    resolve_module(&module)
+}
+fn local(line: usize, name: &str, expr: Expr) -> Decl {
+    Decl::local(None, ((line, 1), (line, 1)), false, name, expr)
+}
+/// A span on its own line, so that each use can be told apart
+fn at(line: usize) -> Span {
+    ((line, 1), (line, 2)).into()
+}
+fn var(name: &str, line: usize) -> Expr {
+    Expr::var(name).with_span(at(line))
+}
+fn errors(resolution: &Resolution) -> Vec<ResolveErrorKind> {
+    resolution
+        .errors()
+        .iter()
+        .map(|e| e.kind().clone())
+        .collect()
+}
+
+#[test]
+fn declarations() {
+    // Declarations may be used before they are declared, and may refer to themselves
+    let resolution = resolve(
+        vec![],
+        vec![
+            local(1, "a", var("b", 10)),
+            local(2, "b", Expr::literal(1)),
+            Decl::external(None, ((3, 1), (3, 1)), false, "log", "log"),
+            local(4, "f", Expr::apply(var("log", 11), var("f", 12))),
+            Decl::expect(((5, 1), (5, 1)), var("a", 13)),
+        ],
+    );
+    assert!(!resolution.has_errors(), "{:?}", resolution.errors());
+    assert_eq!(resolution.binding_at(at(10)), Some(Binding::Decl(1)));
+    assert_eq!(resolution.binding_at(at(11)), Some(Binding::Decl(2)));
+    assert_eq!(resolution.binding_at(at(12)), Some(Binding::Decl(3)));
+    assert_eq!(resolution.binding_at(at(13)), Some(Binding::Decl(0)));
+}
+
+#[test]
+fn locals() {
+    let lambda_span = at(20);
+    let let_span = at(21);
+    let switch_span = at(22);
+    let resolution = resolve(
+        vec![],
+        vec![local(
+            1,
+            "f",
+            Expr::lambda(
+                vec![
+                    pvar("x"),
+                    Pattern::Literal(Literal::Record(vec![("y".to_string(), pvar("y"))])),
+                ],
+                Expr::binding(
+                    Pattern::Literal(Literal::Array(vec![
+                        pvar("a"),
+                        Pattern::Spread("rest".to_string()),
+                    ])),
+                    var("y", 10),
+                    Expr::switch(
+                        var("x", 11),
+                        vec![
+                            (
+                                Pattern::Literal(Literal::Enum(
+                                    "just".to_string(),
+                                    vec![pvar("v")],
+                                )),
+                                Some(var("v", 12)),
+                                var("rest", 13),
+                            ),
+                            (pvar("a"), None, var("a", 14)),
+                        ],
+                    )
+                    .with_span(switch_span),
+                )
+                .with_span(let_span),
+            )
+            .with_span(lambda_span),
+        )],
+    );
+    assert!(!resolution.has_errors(), "{:?}", resolution.errors());
+    assert_eq!(
+        resolution.binding_at(at(10)),
+        Some(Binding::Param(lambda_span))
+    );
+    assert_eq!(
+        resolution.binding_at(at(11)),
+        Some(Binding::Param(lambda_span))
+    );
+    assert_eq!(
+        resolution.binding_at(at(12)),
+        Some(Binding::Case(switch_span, 0))
+    );
+    assert_eq!(resolution.binding_at(at(13)), Some(Binding::Let(let_span)));
+    // A case's pattern shadows the `let`
+    assert_eq!(
+        resolution.binding_at(at(14)),
+        Some(Binding::Case(switch_span, 1))
+    );
+    assert_eq!(
+        resolution
+            .references_to(Binding::Param(lambda_span))
+            .map(|r| r.name.as_str())
+            .collect::<Vec<_>>(),
+        ["y", "x"]
+    );
+}
+
+#[test]
+fn recursive_lets() {
+    // A `let` of a single variable is in scope in its own value, but a destructuring one isn't
+    let resolution = resolve(
+        vec![],
+        vec![local(
+            1,
+            "a",
+            Expr::binding(
+                pvar("go"),
+                Expr::lambda(vec![pvar("n")], Expr::apply(var("go", 10), var("n", 11))),
+                Expr::binding(
+                    Pattern::Literal(Literal::Array(vec![pvar("xs")])),
+                    var("xs", 12),
+                    var("go", 13),
+                ),
+            )
+            .with_span(at(20)),
+        )],
+    );
+    assert_eq!(resolution.binding_at(at(10)), Some(Binding::Let(at(20))));
+    assert_eq!(resolution.binding_at(at(13)), Some(Binding::Let(at(20))));
+    assert_eq!(resolution.binding_at(at(12)), None);
+    assert_eq!(
+        errors(&resolution),
+        [ResolveErrorKind::UnboundVar("xs".to_string())]
+    );
+}
+
+#[test]
+fn imports() {
+    let resolution = resolve(
+        vec![
+            Import::project(
+                "./list".to_string(),
+                vec!["List".to_string()],
+                vec!["map".to_string(), "size".to_string()],
+            ),
+            Import::package(
+                "ren/array".to_string(),
+                vec!["Data".to_string(), "Array".to_string()],
+                vec![],
+            ),
+        ],
+        vec![
+            local(
+                1,
+                "a",
+                Expr::literal(vec![
+                    var("map", 10),
+                    var("size", 11),
+                    Expr::scoped(vec!["List".to_string()], "filter".to_string()).with_span(at(12)),
+                    Expr::scoped(
+                        vec!["Data".to_string(), "Array".to_string()],
+                        "length".to_string(),
+                    )
+                    .with_span(at(13)),
+                    Expr::scoped(vec!["Array".to_string()], "length".to_string()).with_span(at(14)),
+                ]),
+            ),
+            // Declarations shadow exposed names
+            local(2, "size", Expr::literal(1)),
+        ],
+    );
+    assert_eq!(resolution.binding_at(at(10)), Some(Binding::Exposed(0)));
+    assert_eq!(resolution.binding_at(at(11)), Some(Binding::Decl(1)));
+    assert_eq!(resolution.binding_at(at(12)), Some(Binding::Namespace(0)));
+    assert_eq!(resolution.binding_at(at(13)), Some(Binding::Namespace(1)));
+    assert_eq!(resolution.references()[2].namespace, ["List"]);
+    assert_eq!(
+        errors(&resolution),
+        [ResolveErrorKind::UnknownNamespace(
+            vec!["Array".to_string()]
+        )]
+    );
+}
+
+#[test]
+fn operators() {
+    let resolution = resolve(
+        vec![],
+        vec![
+            Decl::infix(
+                ((1, 1), (1, 1)),
+                false,
+                crate::Fixity::DEFAULT,
+                "<+>",
+                Expr::lambda(
+                    vec![pvar("a"), pvar("b")],
+                    Expr::binop(var("a", 10), Operator::Add, var("b", 11)),
+                ),
+            ),
+            local(
+                2,
+                "x",
+                Expr::apply_many(var("<+>", 12), [Expr::literal(1), var("<->", 13)]),
+            ),
+        ],
+    );
+    assert_eq!(resolution.binding_at(at(12)), Some(Binding::Decl(0)));
+    assert_eq!(
+        errors(&resolution),
+        [ResolveErrorKind::UnboundVar("<->".to_string())]
+    );
+}
+
+#[test]
+fn reports_errors() {
+    let resolution = resolve(
+        vec![],
+        vec![local(
+            1,
+            "a",
+            Expr::lambda(
+                vec![pvar("x")],
+                Expr::apply(
+                    Expr::var("missing").with_span(((1, 14), (1, 21))),
+                    Expr::scoped(vec!["List".to_string()], "map".to_string())
+                        .with_span(((1, 22), (1, 30))),
+                ),
+            ),
+        )],
+    );
+    assert_eq!(
+        resolution
+            .errors()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        [
+            "1:14: unbound variable `missing`",
+            "1:22: unknown namespace `List`, no import is aliased as it",
+        ]
+    );
+}
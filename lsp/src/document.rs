@@ -0,0 +1,66 @@
+use lsp_types::{Position, Range, TextDocumentContentChangeEvent};
+use parser::{Parsed, TextEdit};
+
+/// An open text document. LSP positions count UTF-16 code units, so they are converted to and
+/// from byte offsets here.
+pub(crate) struct Document {
+    pub version: i32,
+    pub text: String,
+    /// The parse of the text, kept so that an edit only reparses the block it changes
+    pub parsed: Parsed<'static>,
+}
+impl Document {
+    pub fn new(version: i32, text: String) -> Self {
+        let parsed = parser::parse_module(&text).into_owned();
+        Self {
+            version,
+            text,
+            parsed,
+        }
+    }
+    pub fn apply_change(&mut self, change: TextDocumentContentChangeEvent) {
+        match change.range {
+            Some(Range { start, end }) => {
+                let start = self.offset_at(start);
+                let end = self.offset_at(end).max(start);
+                let edit = TextEdit::new(start..end, change.text);
+                self.text = edit.apply(&self.text);
+                self.parsed = parser::reparse_module(&self.parsed, &edit, &self.text).into_owned();
+            }
+            None => {
+                self.text = change.text;
+                self.parsed = parser::parse_module(&self.text).into_owned();
+            }
+        }
+    }
+    /// The byte offset of a position. Positions past the end of a line (or of the document)
+    /// are clamped to the end of that line (or document).
+    pub fn offset_at(&self, position: Position) -> usize {
+        let mut line_start = 0;
+        for _ in 0..position.line {
+            match self.text[line_start..].find('\n') {
+                Some(i) => line_start += i + 1,
+                None => return self.text.len(),
+            }
+        }
+        let mut units = 0;
+        for (i, c) in self.text[line_start..].char_indices() {
+            if units >= position.character as usize || c == '\n' {
+                return line_start + i;
+            }
+            units += c.len_utf16();
+        }
+        self.text.len()
+    }
+    pub fn position_at(&self, offset: usize) -> Position {
+        let before = &self.text[..offset.min(self.text.len())];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        Position::new(
+            before.matches('\n').count() as u32,
+            before[line_start..].encode_utf16().count() as u32,
+        )
+    }
+    pub fn range_at(&self, range: std::ops::Range<usize>) -> Range {
+        Range::new(self.position_at(range.start), self.position_at(range.end))
+    }
+}
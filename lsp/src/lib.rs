@@ -1,2 +1,223 @@
-use lsp_types::{notification::Notification, *};
-use serde::{Deserialize, Serialize};
+//! A language server for Ren, speaking JSON-RPC over stdio.
+//!
+//! Documents are synchronised incrementally. Each change reparses only the declaration, import
+//! or brace block it edits where it can, and any syntax errors are published as diagnostics.
+
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, Write},
+};
+
+use lsp_types::{
+    notification::{self, Notification},
+    request::{self, Request},
+    Diagnostic, DiagnosticSeverity, DidChangeTextDocumentParams, DidCloseTextDocumentParams,
+    DidOpenTextDocumentParams, InitializeParams, InitializeResult, PublishDiagnosticsParams,
+    ServerCapabilities, ServerInfo, TextDocumentSyncCapability, TextDocumentSyncKind, Url,
+};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+mod document;
+#[cfg(test)]
+mod tests;
+mod transport;
+
+use document::Document;
+use transport::{error_codes, Message, ResponseError};
+
+/// Run the language server until the client sends the `exit` notification or closes the input.
+/// Exiting without first receiving a `shutdown` request is an error.
+pub fn serve<R, W>(input: R, output: W) -> io::Result<()>
+where
+    R: BufRead,
+    W: Write,
+{
+    Server::new(output).run(input)
+}
+
+/// Run the language server over stdin and stdout
+pub fn serve_stdio() -> io::Result<()> {
+    serve(io::stdin().lock(), io::stdout().lock())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Uninitialized,
+    Running,
+    ShutDown,
+}
+
+struct Server<W> {
+    output: W,
+    state: State,
+    documents: HashMap<Url, Document>,
+}
+
+impl<W: Write> Server<W> {
+    fn new(output: W) -> Self {
+        Self {
+            output,
+            state: State::Uninitialized,
+            documents: HashMap::new(),
+        }
+    }
+    fn run<R: BufRead>(mut self, mut input: R) -> io::Result<()> {
+        while let Some(content) = transport::read_message(&mut input)? {
+            let message = match serde_json::from_str::<Message>(&content) {
+                Ok(message) => message,
+                Err(e) => {
+                    let error = ResponseError {
+                        code: error_codes::PARSE_ERROR,
+                        message: e.to_string(),
+                    };
+                    self.send(transport::response(Value::Null, Err(error)))?;
+                    continue;
+                }
+            };
+            match (message.id, message.method) {
+                (Some(id), Some(method)) => {
+                    let result = self.handle_request(&method, message.params);
+                    self.send(transport::response(id, result))?;
+                }
+                (None, Some(method)) if method == notification::Exit::METHOD => {
+                    return if self.state == State::ShutDown {
+                        Ok(())
+                    } else {
+                        Err(io::Error::other(
+                            "received exit notification before shutdown",
+                        ))
+                    };
+                }
+                (None, Some(method)) => self.handle_notification(&method, message.params)?,
+                // Responses to requests sent by the server
+                (_, None) => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_request(&mut self, method: &str, params: Value) -> Result<Value, ResponseError> {
+        match (self.state, method) {
+            (State::Uninitialized, request::Initialize::METHOD) => {
+                let _params: InitializeParams = parse_params(params)?;
+                self.state = State::Running;
+                let result = InitializeResult {
+                    capabilities: ServerCapabilities {
+                        text_document_sync: Some(TextDocumentSyncCapability::Kind(
+                            TextDocumentSyncKind::INCREMENTAL,
+                        )),
+                        ..Default::default()
+                    },
+                    server_info: Some(ServerInfo {
+                        name: "ren-lsp".to_string(),
+                        version: Some(env!("CARGO_PKG_VERSION").to_string()),
+                    }),
+                };
+                Ok(serde_json::to_value(result).expect("InitializeResult should serialise"))
+            }
+            (State::Uninitialized, _) => Err(ResponseError {
+                code: error_codes::SERVER_NOT_INITIALIZED,
+                message: "the server has not been initialized".to_string(),
+            }),
+            (State::ShutDown, _) => Err(ResponseError {
+                code: error_codes::INVALID_REQUEST,
+                message: "the server has been shut down".to_string(),
+            }),
+            (State::Running, request::Initialize::METHOD) => Err(ResponseError {
+                code: error_codes::INVALID_REQUEST,
+                message: "the server has already been initialized".to_string(),
+            }),
+            (State::Running, request::Shutdown::METHOD) => {
+                self.state = State::ShutDown;
+                self.documents.clear();
+                Ok(Value::Null)
+            }
+            (State::Running, _) => Err(ResponseError {
+                code: error_codes::METHOD_NOT_FOUND,
+                message: format!("unsupported method `{}`", method),
+            }),
+        }
+    }
+
+    /// Handle a notification from the client. Notifications cannot be replied to, so any which
+    /// are malformed, unsupported or sent before initialisation are dropped.
+    fn handle_notification(&mut self, method: &str, params: Value) -> io::Result<()> {
+        if self.state != State::Running {
+            return Ok(());
+        }
+        match method {
+            notification::DidOpenTextDocument::METHOD => {
+                if let Ok(DidOpenTextDocumentParams { text_document }) = parse_params(params) {
+                    let document = Document::new(text_document.version, text_document.text);
+                    self.documents.insert(text_document.uri.clone(), document);
+                    self.publish_diagnostics(text_document.uri)?;
+                }
+            }
+            notification::DidChangeTextDocument::METHOD => {
+                if let Ok(DidChangeTextDocumentParams {
+                    text_document,
+                    content_changes,
+                }) = parse_params(params)
+                {
+                    if let Some(document) = self.documents.get_mut(&text_document.uri) {
+                        document.version = text_document.version;
+                        for change in content_changes {
+                            document.apply_change(change);
+                        }
+                        self.publish_diagnostics(text_document.uri)?;
+                    }
+                }
+            }
+            notification::DidCloseTextDocument::METHOD => {
+                if let Ok(DidCloseTextDocumentParams { text_document }) = parse_params(params) {
+                    self.documents.remove(&text_document.uri);
+                    // Clear any diagnostics for the closed document
+                    let params = PublishDiagnosticsParams::new(text_document.uri, Vec::new(), None);
+                    self.send(transport::notification(
+                        notification::PublishDiagnostics::METHOD,
+                        params,
+                    ))?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn publish_diagnostics(&mut self, uri: Url) -> io::Result<()> {
+        let document = &self.documents[&uri];
+        let params =
+            PublishDiagnosticsParams::new(uri, diagnostics(document), Some(document.version));
+        self.send(transport::notification(
+            notification::PublishDiagnostics::METHOD,
+            params,
+        ))
+    }
+
+    fn send(&mut self, message: Value) -> io::Result<()> {
+        transport::write_message(&mut self.output, &message)
+    }
+}
+
+fn parse_params<P: DeserializeOwned>(params: Value) -> Result<P, ResponseError> {
+    serde_json::from_value(params).map_err(|e| ResponseError {
+        code: error_codes::INVALID_PARAMS,
+        message: e.to_string(),
+    })
+}
+
+fn diagnostics(document: &Document) -> Vec<Diagnostic> {
+    document
+        .parsed
+        .errors()
+        .iter()
+        .map(|error| Diagnostic {
+            range: document.range_at(error.range()),
+            severity: Some(DiagnosticSeverity::ERROR),
+            source: Some("ren".to_string()),
+            message: error.message(),
+            ..Default::default()
+        })
+        .collect()
+}
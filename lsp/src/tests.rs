@@ -0,0 +1,181 @@
+use lsp_types::{Position, Range, TextDocumentContentChangeEvent};
+use serde_json::{json, Value};
+
+use crate::{document::Document, serve, transport};
+
+const URI: &str = "file:///project/src/Main.ren";
+
+/// Frame the messages, run the server over them, then return the result and all the messages
+/// the server sent back
+fn run(messages: &[Value]) -> (std::io::Result<()>, Vec<Value>) {
+    let input = messages
+        .iter()
+        .map(|message| {
+            let content = message.to_string();
+            format!("Content-Length: {}\r\n\r\n{}", content.len(), content)
+        })
+        .collect::<String>();
+    let mut output = Vec::new();
+    let result = serve(input.as_bytes(), &mut output);
+    let mut output = output.as_slice();
+    let mut responses = Vec::new();
+    while let Some(content) = transport::read_message(&mut output).unwrap() {
+        responses.push(serde_json::from_str(&content).unwrap());
+    }
+    (result, responses)
+}
+
+fn initialize() -> Vec<Value> {
+    vec![
+        json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {"capabilities": {}}}),
+        json!({"jsonrpc": "2.0", "method": "initialized", "params": {}}),
+    ]
+}
+fn shutdown() -> Vec<Value> {
+    vec![
+        json!({"jsonrpc": "2.0", "id": 99, "method": "shutdown"}),
+        json!({"jsonrpc": "2.0", "method": "exit"}),
+    ]
+}
+fn session(messages: Vec<Value>) -> Vec<Value> {
+    let (result, responses) = run(&[initialize(), messages, shutdown()].concat());
+    result.expect("Server should exit cleanly");
+    assert_eq!(responses[0]["id"], 1);
+    assert_eq!(
+        responses.last().unwrap(),
+        &json!({"jsonrpc": "2.0", "id": 99, "result": null})
+    );
+    responses[1..responses.len() - 1].to_vec()
+}
+
+fn did_open(text: &str) -> Value {
+    json!({"jsonrpc": "2.0", "method": "textDocument/didOpen", "params": {
+        "textDocument": {"uri": URI, "languageId": "ren", "version": 1, "text": text},
+    }})
+}
+
+#[test]
+fn initialize_and_shutdown() {
+    let (result, responses) = run(&[initialize(), shutdown()].concat());
+    assert!(result.is_ok());
+    assert_eq!(responses.len(), 2);
+    assert_eq!(
+        responses[0]["result"]["capabilities"]["textDocumentSync"],
+        json!(2)
+    );
+    assert_eq!(responses[0]["result"]["serverInfo"]["name"], "ren-lsp");
+}
+
+#[test]
+fn publishes_diagnostics() {
+    let responses = session(vec![
+        did_open("let a = ]\nlet b = 2"),
+        json!({"jsonrpc": "2.0", "method": "textDocument/didChange", "params": {
+            "textDocument": {"uri": URI, "version": 2},
+            "contentChanges": [{
+                "range": {"start": {"line": 0, "character": 8}, "end": {"line": 0, "character": 9}},
+                "text": "1",
+            }],
+        }}),
+        json!({"jsonrpc": "2.0", "method": "textDocument/didClose", "params": {
+            "textDocument": {"uri": URI},
+        }}),
+    ]);
+    assert_eq!(
+        responses,
+        vec![
+            json!({"jsonrpc": "2.0", "method": "textDocument/publishDiagnostics", "params": {
+                "uri": URI,
+                "version": 1,
+                "diagnostics": [{
+                    "range": {"start": {"line": 0, "character": 8}, "end": {"line": 0, "character": 9}},
+                    "severity": 1,
+                    "source": "ren",
+                    "message": "expected an expression, found \"]\"",
+                }],
+            }}),
+            json!({"jsonrpc": "2.0", "method": "textDocument/publishDiagnostics", "params": {
+                "uri": URI,
+                "version": 2,
+                "diagnostics": [],
+            }}),
+            json!({"jsonrpc": "2.0", "method": "textDocument/publishDiagnostics", "params": {
+                "uri": URI,
+                "diagnostics": [],
+            }}),
+        ]
+    );
+}
+
+#[test]
+fn requests_before_initialize() {
+    let (result, responses) = run(&[
+        json!({"jsonrpc": "2.0", "id": 1, "method": "shutdown"}),
+        did_open("let a = "),
+    ]);
+    assert!(result.is_ok());
+    assert_eq!(responses.len(), 1);
+    assert_eq!(responses[0]["error"]["code"], -32002);
+}
+
+#[test]
+fn unknown_request() {
+    let responses = session(vec![
+        json!({"jsonrpc": "2.0", "id": 2, "method": "textDocument/hover", "params": {}}),
+        json!({"jsonrpc": "2.0", "method": "$/unknownNotification", "params": {}}),
+    ]);
+    assert_eq!(responses.len(), 1);
+    assert_eq!(responses[0]["id"], 2);
+    assert_eq!(responses[0]["error"]["code"], -32601);
+}
+
+#[test]
+fn exit_without_shutdown() {
+    let (result, _) = run(&[
+        initialize(),
+        vec![json!({"jsonrpc": "2.0", "method": "exit"})],
+    ]
+    .concat());
+    assert!(result.is_err());
+}
+
+#[test]
+fn document_positions() {
+    let mut document = Document::new(1, "let a = \"😀\"\nlet b = 2".to_string());
+    // The emoji is 2 UTF-16 code units, but 4 bytes
+    assert_eq!(document.offset_at(Position::new(0, 11)), 13);
+    assert_eq!(document.position_at(13), Position::new(0, 11));
+    assert_eq!(document.offset_at(Position::new(1, 4)), 19);
+    assert_eq!(document.position_at(19), Position::new(1, 4));
+    // Clamped to the end of the line
+    assert_eq!(document.offset_at(Position::new(0, 50)), 14);
+    document.apply_change(TextDocumentContentChangeEvent {
+        range: Some(Range::new(Position::new(1, 4), Position::new(1, 5))),
+        range_length: None,
+        text: "c".to_string(),
+    });
+    assert_eq!(document.text, "let a = \"😀\"\nlet c = 2");
+}
+
+#[test]
+fn document_reparses_changes() {
+    let mut document = Document::new(1, "let a = 1\nlet b = 2\n".to_string());
+    assert!(!document.parsed.has_errors());
+    document.apply_change(TextDocumentContentChangeEvent {
+        range: Some(Range::new(Position::new(1, 8), Position::new(1, 9))),
+        range_length: None,
+        text: "(2".to_string(),
+    });
+    assert_eq!(
+        document.parsed.debug_tree(),
+        parser::parse_module(&document.text).debug_tree()
+    );
+    assert_eq!(document.parsed.errors().len(), 1);
+    assert_eq!(document.parsed.errors()[0].range(), 21..21);
+    document.apply_change(TextDocumentContentChangeEvent {
+        range: None,
+        range_length: None,
+        text: "let a = 1\n".to_string(),
+    });
+    assert!(!document.parsed.has_errors());
+}
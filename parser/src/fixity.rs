@@ -0,0 +1,66 @@
+use higher_ast::{Associativity, Fixities, Fixity};
+
+use crate::syntax::{lexer::Lexer, Token, TokenType};
+
+/// Every fixity declared in a module, whether or not it is public. The parser needs these
+/// before it reaches any use of the operators, and declarations may come after them.
+pub fn declared_in(input: &str) -> Fixities {
+    declarations(input)
+        .map(|(_, op, fixity)| (op, fixity))
+        .collect()
+}
+/// The fixities declared by `pub` declarations in a module, which are brought into scope by
+/// importing it
+pub fn exposed_by(input: &str) -> Fixities {
+    declarations(input)
+        .filter(|(exposed, _, _)| *exposed)
+        .map(|(_, op, fixity)| (op, fixity))
+        .collect()
+}
+
+/// Scan the tokens of a module for `[pub] infixl|infixr <precedence> <op>`, without parsing it.
+/// Declarations with an invalid precedence are skipped here and reported by the parser.
+fn declarations(input: &str) -> impl Iterator<Item = (bool, String, Fixity)> + '_ {
+    let mut tokens = Lexer::new(input)
+        .filter(|(ty, _)| !matches!(ty, TokenType::Token(Token::Whitespace | Token::Comment)))
+        .peekable();
+    let mut exposed = false;
+    std::iter::from_fn(move || loop {
+        let (ty, _) = tokens.next()?;
+        let associativity = match ty {
+            TokenType::Token(Token::KWPub) => {
+                exposed = true;
+                continue;
+            }
+            TokenType::Token(Token::KWInfixl) => Associativity::Left,
+            TokenType::Token(Token::KWInfixr) => Associativity::Right,
+            _ => {
+                exposed = false;
+                continue;
+            }
+        };
+        let is_exposed = std::mem::take(&mut exposed);
+        let Some((TokenType::Token(Token::Number), precedence)) = tokens.peek().copied() else {
+            continue;
+        };
+        tokens.next();
+        let Some(precedence) = parse_precedence(precedence) else {
+            continue;
+        };
+        if let Some((TokenType::Token(Token::OpCustom), op)) = tokens.peek().copied() {
+            tokens.next();
+            return Some((
+                is_exposed,
+                op.to_string(),
+                Fixity::new(associativity, precedence),
+            ));
+        }
+    })
+}
+
+/// A precedence from 0 to [`Fixity::MAX_PRECEDENCE`]
+pub(crate) fn parse_precedence(text: &str) -> Option<u8> {
+    text.parse()
+        .ok()
+        .filter(|precedence| *precedence <= Fixity::MAX_PRECEDENCE)
+}
@@ -0,0 +1,560 @@
+//! Format Ren source code.
+//!
+//! Unlike printing the higher AST (see [`higher_ast::pretty`]), formatting works directly on
+//! the syntax tree, so comments are kept, as are any redundant parentheses. The layout is the
+//! same as the pretty-printer's: groups are kept on one line if they fit, otherwise `switch`
+//! branches, `if` branches, record fields and array items each go on their own line.
+//!
+//! Comments are attached to the token they are next to. A comment on its own line is printed
+//! on its own line before the following token, and a comment at the end of a line stays at
+//! the end of the line after the preceding token.
+
+use std::{cell::Cell, collections::HashMap};
+
+use higher_ast::{pretty::Doc, Operator};
+use rowan::{NodeOrToken, TextSize};
+
+use crate::{
+    syntax::{Context, RenLang, SyntaxNode, SyntaxPart, Token},
+    ParseError,
+};
+
+#[cfg(test)]
+mod tests;
+
+type SyntaxElement = rowan::SyntaxElement<RenLang>;
+type SyntaxToken = rowan::SyntaxToken<RenLang>;
+
+const INDENT: usize = 4;
+
+/// Format a whole module, trying to keep lines no longer than `width`. Input with syntax
+/// errors is not formatted.
+pub fn format_module(input: &str, width: usize) -> Result<String, Vec<ParseError>> {
+    let parsed = crate::parse_module(input);
+    if parsed.has_errors() {
+        return Err(parsed.errors().to_vec());
+    }
+    let root = parsed.syntax();
+    Ok(Formatter::new(&root).module(&root).render(width))
+}
+
+#[derive(Debug, Default)]
+struct Comments {
+    /// Comments on their own lines before a token, keyed by the start of the token
+    leading: HashMap<TextSize, Vec<String>>,
+    /// Comments at the end of the line after a token, keyed by the start of the token
+    trailing: HashMap<TextSize, String>,
+    /// Comments after the last token
+    end: Vec<String>,
+}
+impl Comments {
+    fn new(root: &SyntaxNode) -> Self {
+        let mut comments = Self::default();
+        let mut previous = None;
+        let mut newline = true;
+        let mut pending = Vec::new();
+        for token in root
+            .descendants_with_tokens()
+            .filter_map(|e| e.into_token())
+        {
+            match token.kind() {
+                SyntaxPart::Token(Token::Whitespace) => newline |= token.text().contains('\n'),
+                SyntaxPart::Token(Token::Comment) => {
+                    let comment = token.text().trim_end().to_string();
+                    match previous {
+                        Some(previous) if !newline => {
+                            comments.trailing.insert(previous, comment);
+                        }
+                        _ => pending.push(comment),
+                    }
+                    // Comments run to the end of the line
+                    newline = true;
+                }
+                _ => {
+                    let start = token.text_range().start();
+                    if !pending.is_empty() {
+                        comments.leading.insert(start, std::mem::take(&mut pending));
+                    }
+                    previous = Some(start);
+                    newline = false;
+                }
+            }
+        }
+        comments.end = pending;
+        comments
+    }
+}
+
+struct Formatter {
+    comments: Comments,
+    /// A token whose trailing comment is being printed by the caller rather than straight
+    /// after the token, so that it doesn't force the surrounding groups onto multiple lines
+    deferred: Cell<Option<TextSize>>,
+}
+
+/// The children of a node, without whitespace and comments
+fn significant_children(node: &SyntaxNode) -> Vec<SyntaxElement> {
+    node.children_with_tokens()
+        .filter(|e| {
+            !matches!(
+                e.kind(),
+                SyntaxPart::Token(Token::Whitespace | Token::Comment)
+            )
+        })
+        .collect()
+}
+
+fn context_of(element: &SyntaxElement) -> Option<Context> {
+    match element.kind() {
+        SyntaxPart::Context(context) => Some(context),
+        _ => None,
+    }
+}
+
+/// The contents of an `Expr` node, which wraps nested expressions
+fn unwrap_expr(element: &SyntaxElement) -> SyntaxElement {
+    match element {
+        NodeOrToken::Node(node) if node.kind() == SyntaxPart::Context(Context::Expr) => {
+            match significant_children(node).as_slice() {
+                [inner] => unwrap_expr(inner),
+                _ => element.clone(),
+            }
+        }
+        _ => element.clone(),
+    }
+}
+
+/// The left binding power of the operator of a `BinOp` node
+fn binding_power(node: &SyntaxNode) -> Option<u8> {
+    significant_children(node)
+        .get(1)?
+        .as_token()
+        .and_then(|op| Operator::from_symbol(op.text()))
+        .map(|op| op.infix_binding_power().0)
+}
+
+impl Formatter {
+    fn new(root: &SyntaxNode) -> Self {
+        Self {
+            comments: Comments::new(root),
+            deferred: Cell::new(None),
+        }
+    }
+
+    fn token(&self, token: &SyntaxToken) -> Doc {
+        let start = token.text_range().start();
+        let mut doc = Doc::Nil;
+        for comment in self.comments.leading.get(&start).into_iter().flatten() {
+            doc = doc.append(comment.as_str()).append(Doc::HardLine);
+        }
+        doc = doc.append(token.text());
+        match self.comments.trailing.get(&start) {
+            Some(comment) if self.deferred.get() != Some(start) => doc
+                .append(" ")
+                .append(comment.as_str())
+                .append(Doc::HardLine),
+            _ => doc,
+        }
+    }
+
+    fn element(&self, element: &SyntaxElement) -> Doc {
+        match element {
+            NodeOrToken::Node(node) => self.node(node),
+            NodeOrToken::Token(token) => self.token(token),
+        }
+    }
+
+    fn concat(&self, elements: &[SyntaxElement]) -> Doc {
+        Doc::concat(elements.iter().map(|e| self.element(e)))
+    }
+
+    fn spaced(&self, elements: &[SyntaxElement]) -> Doc {
+        Doc::join(elements.iter().map(|e| self.element(e)), Doc::text(" "))
+    }
+
+    /// Format a top-level item, printing any comment at the end of it after the group, so
+    /// that `let x = 1 // comment` can stay on one line
+    fn item(&self, node: &SyntaxNode) -> Doc {
+        let last = node
+            .descendants_with_tokens()
+            .filter_map(|e| e.into_token())
+            .filter(|t| {
+                !matches!(
+                    t.kind(),
+                    SyntaxPart::Token(Token::Whitespace | Token::Comment)
+                )
+            })
+            .last()
+            .map(|t| t.text_range().start());
+        self.deferred.set(last);
+        let doc = self.node(node).group();
+        self.deferred.set(None);
+        match last.and_then(|last| self.comments.trailing.get(&last)) {
+            Some(comment) => doc.append(" ").append(comment.as_str()),
+            None => doc,
+        }
+    }
+
+    fn module(&self, node: &SyntaxNode) -> Doc {
+        let mut sections = Vec::new();
+        for child in node.children() {
+            match child.kind() {
+                SyntaxPart::Context(Context::Imports) => sections.push(Doc::join(
+                    child.children().map(|import| self.item(&import)),
+                    Doc::HardLine,
+                )),
+                SyntaxPart::Context(Context::Declarations) => sections.push(Doc::join(
+                    child.children().map(|decl| self.item(&decl)),
+                    Doc::HardLine.append(Doc::HardLine),
+                )),
+                _ => sections.push(self.node(&child)),
+            }
+        }
+        if !self.comments.end.is_empty() {
+            sections.push(Doc::join(
+                self.comments.end.iter().map(Doc::text),
+                Doc::HardLine,
+            ));
+        }
+        let doc = Doc::join(sections, Doc::HardLine.append(Doc::HardLine));
+        if doc == Doc::Concat(Vec::new()) {
+            doc
+        } else {
+            doc.append(Doc::HardLine)
+        }
+    }
+
+    fn node(&self, node: &SyntaxNode) -> Doc {
+        let children = significant_children(node);
+        let context = match node.kind() {
+            SyntaxPart::Context(context) => context,
+            _ => return self.spaced(&children),
+        };
+        match context {
+            Context::Module => self.module(node),
+            Context::Imports | Context::Declarations => {
+                Doc::join(children.iter().map(|e| self.element(e)), Doc::HardLine)
+            }
+            Context::Import | Context::Guard => self.spaced(&children),
+            Context::Declaration => self.declaration(&children),
+            Context::Array => self.sequence(&children, Doc::SoftLine),
+            Context::Record | Context::ExposingBlock => self.sequence(&children, Doc::Line),
+            Context::Update => match children.as_slice() {
+                [open, target, bar, fields @ .., close] => self
+                    .element(open)
+                    .append(" ")
+                    .append(self.element(target))
+                    .append(" ")
+                    .append(self.element(bar))
+                    .append(Doc::Line.append(self.sequence_items(fields)).nest(INDENT))
+                    .append(Doc::Line)
+                    .append(self.element(close))
+                    .group(),
+                _ => self.spaced(&children),
+            },
+            Context::Field => match children.as_slice() {
+                [name, colon, value] => self
+                    .element(name)
+                    .append(self.element(colon))
+                    .append(" ")
+                    .append(self.element(value)),
+                _ => self.concat(&children),
+            },
+            Context::Parenthesised => match children.as_slice() {
+                [open, inner, close] => self
+                    .element(open)
+                    .append(Doc::SoftLine.append(self.element(inner)).nest(INDENT))
+                    .append(Doc::SoftLine)
+                    .append(self.element(close))
+                    .group(),
+                _ => self.concat(&children),
+            },
+            Context::Annotated => match children.as_slice() {
+                [open, inner, colon, typ, close] => self
+                    .element(open)
+                    .append(
+                        Doc::SoftLine
+                            .append(self.element(inner))
+                            .append(" ")
+                            .append(self.element(colon))
+                            .append(Doc::Line.append(self.element(typ)).nest(INDENT))
+                            .nest(INDENT),
+                    )
+                    .append(Doc::SoftLine)
+                    .append(self.element(close))
+                    .group(),
+                _ => self.spaced(&children),
+            },
+            Context::Constructor => {
+                let (args, name) = match children.last().and_then(context_of) {
+                    Some(Context::Args) => (
+                        children
+                            .last()
+                            .and_then(|e| e.as_node())
+                            .map(significant_children),
+                        &children[..children.len() - 1],
+                    ),
+                    _ => (None, &children[..]),
+                };
+                self.concat(name)
+                    .append(
+                        Doc::concat(
+                            args.into_iter()
+                                .flatten()
+                                .map(|arg| Doc::Line.append(self.element(&arg))),
+                        )
+                        .nest(INDENT),
+                    )
+                    .group()
+            }
+            Context::Assert => match children.as_slice() {
+                [assert, condition, semicolon, body] => self
+                    .element(assert)
+                    .append(" ")
+                    .append(self.element(condition))
+                    .append(self.element(semicolon))
+                    .append(Doc::Line)
+                    .append(self.element(body))
+                    .group(),
+                _ => self.spaced(&children),
+            },
+            Context::Conditional => self.conditional(&children),
+            Context::Switch => self.switch(&children),
+            Context::Branch => self.branch(&children),
+            Context::Lambda => match children.as_slice() {
+                [fun, params, body] => self
+                    .element(fun)
+                    .append(" ")
+                    .append(self.element(params))
+                    .append(Doc::Line.append(self.element(body)).nest(INDENT))
+                    .group(),
+                _ => self.spaced(&children),
+            },
+            Context::Params => self.spaced(&children),
+            Context::Application => {
+                let mut args = Vec::new();
+                let mut head = NodeOrToken::Node(node.clone());
+                while let Some(NodeOrToken::Node(app)) =
+                    Some(&head).filter(|e| context_of(e) == Some(Context::Application))
+                {
+                    let parts = significant_children(app);
+                    match parts.as_slice() {
+                        [func, arg] => {
+                            args.push(arg.clone());
+                            head = func.clone();
+                        }
+                        _ => break,
+                    }
+                }
+                self.element(&head)
+                    .append(
+                        Doc::concat(
+                            args.iter()
+                                .rev()
+                                .map(|arg| Doc::Line.append(self.element(arg))),
+                        )
+                        .nest(INDENT),
+                    )
+                    .group()
+            }
+            Context::BinOp => self.binop(node).group(),
+            Context::TypeMatch => match children.as_slice() {
+                [at, name, arg] => self
+                    .element(at)
+                    .append(self.element(name))
+                    .append(" ")
+                    .append(self.element(arg)),
+                _ => self.concat(&children),
+            },
+            Context::Forall => match children.as_slice() {
+                [head @ .., dot, typ] => self
+                    .spaced(head)
+                    .append(self.element(dot))
+                    .append(Doc::Line.append(self.element(typ)).nest(INDENT))
+                    .group(),
+                _ => self.spaced(&children),
+            },
+            Context::FunType | Context::SumType => match children.as_slice() {
+                [lhs, op, rhs] => self
+                    .element(lhs)
+                    .append(" ")
+                    .append(self.element(op))
+                    .append(Doc::Line)
+                    .append(self.element(rhs))
+                    .group(),
+                _ => self.spaced(&children),
+            },
+            // Interpolated expressions are kept on one line, as newlines would change the string
+            Context::String => Doc::concat(children.iter().map(|e| match e {
+                NodeOrToken::Node(expr) => Doc::text(self.node(expr).render(usize::MAX)),
+                NodeOrToken::Token(token) => self.token(token),
+            })),
+            // Tokens which are printed next to each other, e.g. `A.b` or `-1`
+            Context::IdUpper
+            | Context::Scoped
+            | Context::Access
+            | Context::PrefixOp
+            | Context::Spread
+            | Context::Variant => self.concat(&children),
+            // Nodes which wrap a single child
+            Context::Expr
+            | Context::Item
+            | Context::Condition
+            | Context::Then
+            | Context::Else
+            | Context::Args
+            | Context::Pattern
+            | Context::Type => self.spaced(&children),
+        }
+    }
+
+    /// `let`, `ext`, `type` and `expect` declarations, including `let` expressions
+    fn declaration(&self, children: &[SyntaxElement]) -> Doc {
+        if let [expect, value] = children {
+            if expect.kind() == SyntaxPart::Token(Token::KWExpect) {
+                return self.element(expect).append(self.binding(value));
+            }
+        }
+        let equals = children
+            .iter()
+            .position(|e| e.kind() == SyntaxPart::Token(Token::SymEquals));
+        let Some(equals) = equals else {
+            return self.spaced(children);
+        };
+        let doc = self
+            .spaced(&children[..equals])
+            .append(" ")
+            .append(self.element(&children[equals]));
+        match &children[equals + 1..] {
+            [value] => doc.append(self.binding(value)),
+            [value, semicolon, body] => doc
+                .append(self.binding(value))
+                .append(self.element(semicolon))
+                .append(Doc::Line)
+                .append(self.element(body))
+                .group(),
+            rest => doc.append(" ").append(self.spaced(rest)),
+        }
+    }
+
+    /// The value bound by a declaration. Functions start on the same line, so that only
+    /// their body is indented.
+    fn binding(&self, value: &SyntaxElement) -> Doc {
+        if context_of(&unwrap_expr(value)) == Some(Context::Lambda) {
+            Doc::text(" ").append(self.element(value))
+        } else {
+            Doc::Line.append(self.element(value)).group().nest(INDENT)
+        }
+    }
+
+    /// The items of an array or fields of a record, with the separators kept so that any
+    /// comments next to them are too
+    fn sequence(&self, children: &[SyntaxElement], padding: Doc) -> Doc {
+        match children {
+            [open, items @ .., close] if !items.is_empty() => self
+                .element(open)
+                .append(
+                    padding
+                        .clone()
+                        .append(self.sequence_items(items))
+                        .nest(INDENT),
+                )
+                .append(padding)
+                .append(self.element(close))
+                .group(),
+            _ => self.concat(children),
+        }
+    }
+
+    /// Comma separated items, with a line break after each comma
+    fn sequence_items(&self, items: &[SyntaxElement]) -> Doc {
+        let mut inner = Doc::Nil;
+        for item in items {
+            inner = inner.append(self.element(item));
+            if item.kind() == SyntaxPart::Token(Token::SymComma) {
+                inner = inner.append(Doc::Line);
+            }
+        }
+        inner
+    }
+
+    fn conditional(&self, children: &[SyntaxElement]) -> Doc {
+        let [if_, cond, then_, then_branch, else_, else_branch] = children else {
+            return self.spaced(children);
+        };
+        let else_doc = match else_branch.as_node().map(significant_children).as_deref() {
+            // Keep `else if` chains at the same indentation
+            Some([inner]) if context_of(&unwrap_expr(inner)) == Some(Context::Conditional) => {
+                Doc::text(" ").append(self.element(else_branch))
+            }
+            _ => Doc::Line.append(self.element(else_branch)).nest(INDENT),
+        };
+        self.element(if_)
+            .append(" ")
+            .append(self.element(cond))
+            .append(" ")
+            .append(self.element(then_))
+            .append(Doc::Line.append(self.element(then_branch)).nest(INDENT))
+            .append(Doc::Line)
+            .append(self.element(else_))
+            .append(else_doc)
+            .group()
+    }
+
+    fn switch(&self, children: &[SyntaxElement]) -> Doc {
+        let [switch, scrutinee, on, branches @ ..] = children else {
+            return self.spaced(children);
+        };
+        self.element(switch)
+            .append(" ")
+            .append(self.element(scrutinee))
+            .append(" ")
+            .append(self.element(on))
+            .append(
+                Doc::concat(
+                    branches
+                        .iter()
+                        .map(|branch| Doc::Line.append(self.element(branch))),
+                )
+                .nest(INDENT),
+            )
+            .group()
+    }
+
+    fn branch(&self, children: &[SyntaxElement]) -> Doc {
+        match children {
+            [head @ .., arrow, body] => self
+                .spaced(head)
+                .append(" ")
+                .append(self.element(arrow))
+                .append(Doc::Line.append(self.element(body)).nest(INDENT).group()),
+            _ => self.spaced(children),
+        }
+    }
+
+    /// The operands of a chain of operators with the same binding power, without a group, so
+    /// that `a |> f |> g` breaks before every operator
+    fn binop(&self, node: &SyntaxNode) -> Doc {
+        let children = significant_children(node);
+        let [lhs, op, rhs] = children.as_slice() else {
+            return self.spaced(&children);
+        };
+        let lhs = match lhs {
+            NodeOrToken::Node(lhs)
+                if lhs.kind() == SyntaxPart::Context(Context::BinOp)
+                    && binding_power(lhs).is_some()
+                    && binding_power(lhs) == binding_power(node) =>
+            {
+                self.binop(lhs)
+            }
+            _ => self.element(lhs),
+        };
+        lhs.append(
+            Doc::Line
+                .append(self.element(op))
+                .append(" ")
+                .append(self.element(rhs))
+                .nest(INDENT),
+        )
+    }
+}
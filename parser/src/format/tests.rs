@@ -0,0 +1,163 @@
+use expect_test::{expect, Expect};
+use higher_ast::pretty::{Pretty, DEFAULT_WIDTH};
+use line_col::LineColLookup;
+
+use super::format_module;
+use crate::{lower_ast::ToHIR, parse_module_ast};
+
+/// The module printed from its higher AST, which ignores layout and comments
+fn printed(input: &str) -> String {
+    parse_module_ast(input)
+        .unwrap_or_else(|e| panic!("failed to parse {:?}: {:?}", input, e))
+        .to_higher_ast(&LineColLookup::new(input))
+        .pretty(DEFAULT_WIDTH)
+}
+
+/// Check the formatted output, and that formatting keeps the meaning of the module, keeps
+/// every comment and leaves formatted code unchanged
+fn check(input: &str, width: usize, expected: Expect) {
+    let formatted = format_module(input, width).unwrap();
+    expected.assert_eq(&formatted);
+    assert_eq!(printed(input), printed(&formatted));
+    for comment in input
+        .lines()
+        .filter_map(|line| line.find("//").map(|i| &line[i..]))
+    {
+        assert!(formatted.contains(comment.trim_end()), "lost {:?}", comment);
+    }
+    assert_eq!(format_module(&formatted, width).unwrap(), formatted);
+}
+
+#[test]
+fn normalises_layout() {
+    check(
+        "import \"./a\"   as A\nimport pkg \"ren/array\" as Array exposing {map,filter}\n\n\n\nlet x   =\n  1\nlet y = [ 1,2 ,3 ]\n",
+        DEFAULT_WIDTH,
+        expect![[r#"
+            import "./a" as A
+            import pkg "ren/array" as Array exposing { map, filter }
+
+            let x = 1
+
+            let y = [1, 2, 3]
+        "#]],
+    );
+    check(
+        "let r = { name: \"ren\", version: 1, tags: [\"functional\", \"language\"] }\n",
+        40,
+        expect![[r#"
+            let r =
+                {
+                    name: "ren",
+                    version: 1,
+                    tags: ["functional", "language"]
+                }
+        "#]],
+    );
+    check(
+        "let n = ( f x:Number )\nlet s = {r|version:2,tags}\nlet t = { (latest r) | name: \"ren-lang\", version: r.version }\n",
+        40,
+        expect![[r#"
+            let n = (f x : Number)
+
+            let s = { r | version: 2, tags }
+
+            let t =
+                { (latest r) |
+                    name: "ren-lang",
+                    version: r.version
+                }
+        "#]],
+    );
+}
+
+#[test]
+fn indents_branches() {
+    check(
+        "let f = fun a b -> switch a on case #just y if y > 0 -> y case _ -> if b then 1 else if a then 2 else 3\n",
+        40,
+        expect![[r#"
+            let f = fun a b ->
+                switch a on
+                    case #just y if y > 0 -> y
+                    case _ ->
+                        if b then
+                            1
+                        else if a then 2 else 3
+        "#]],
+    );
+    check(
+        "let p = numbers |> List.map double |> List.filter isEven |> List.sum\n",
+        40,
+        expect![[r#"
+            let p =
+                numbers
+                    |> List.map double
+                    |> List.filter isEven
+                    |> List.sum
+        "#]],
+    );
+}
+
+#[test]
+fn lays_out_tests() {
+    check(
+        "let half = fun n -> assert (n % 2) == 0;   n / 2\nexpect   (half 4) == 2\nexpect assert (half 2) == 1; (half 8) == 4\n",
+        36,
+        expect![[r#"
+            let half = fun n ->
+                assert (n % 2) == 0; n / 2
+
+            expect (half 4) == 2
+
+            expect
+                assert (half 2) == 1;
+                (half 8) == 4
+        "#]],
+    );
+}
+
+#[test]
+fn keeps_comments() {
+    check(
+        r#"// The utilities
+import "./utils" as Utils // trailing
+
+// Doc for x
+pub let x = 1 // one
+
+let r = { a: 1, // first
+  b: (2) }
+
+let f = fun a ->
+  // before the body
+  a
+// end of file
+"#,
+        DEFAULT_WIDTH,
+        expect![[r#"
+            // The utilities
+            import "./utils" as Utils // trailing
+
+            // Doc for x
+            pub let x = 1 // one
+
+            let r =
+                {
+                    a: 1, // first
+                    b: (2)
+                }
+
+            let f = fun a ->
+                // before the body
+                a
+
+            // end of file
+        "#]],
+    );
+}
+
+#[test]
+fn rejects_syntax_errors() {
+    assert!(format_module("let = 1\n", DEFAULT_WIDTH).is_err());
+}
@@ -1,16 +1,19 @@
 use higher_ast::Operator;
 
 use crate::{
-    syntax::{Context, Token, TokenType},
+    syntax::{Context, SyntaxPart, Token, TokenType},
     Parser,
 };
 
 use super::{parse_literal, pattern::parse_pattern};
 
-const NESTED_EXPR: super::NestedParser = super::NestedParser {
+pub(super) const NESTED_EXPR: super::NestedParser = super::NestedParser {
     func: expr,
     record_value_required: false,
     record_allow_empty: true,
+    allow_spread: false,
+    record_allow_update: true,
+    allow_annotation: true,
 };
 
 pub(super) fn expr(p: &mut Parser) {
@@ -84,7 +87,8 @@ fn parse_term(p: &mut Parser) {
                     }
                     m.complete(p, Context::Constructor);
                 } else {
-                    todo!("ERROR")
+                    p.error("a constructor name");
+                    m.complete(p, SyntaxPart::Error);
                 }
             }
             Token::KWIf => {
@@ -93,32 +97,31 @@ fn parse_term(p: &mut Parser) {
                 let condition_m = p.start("condition");
                 expr(p);
                 condition_m.complete(p, Context::Condition);
-                assert!(p.peek().is(Token::KWThen));
-                p.bump();
+                p.expect(Token::KWThen, "`then`");
                 let then_m = p.start("then");
                 expr(p);
                 then_m.complete(p, Context::Then);
-                assert!(p.peek().is(Token::KWElse));
-                p.bump();
+                p.expect(Token::KWElse, "`else`");
                 let else_m = p.start("else");
                 expr(p);
                 else_m.complete(p, Context::Else);
                 conditional_m.complete(p, Context::Conditional);
             }
             Token::KWLet => parse_let(p),
+            Token::KWAssert => parse_assert(p),
             Token::KWSwitch => parse_switch(p),
             Token::KWFun => parse_lambda(p),
-            _ => {}
+            _ => p.error("an expression"),
         },
         TokenType::String(_) => unreachable!(),
-        TokenType::None => todo!("ERROR: EOF"),
+        TokenType::None => p.error("an expression"),
     }
 }
 
 fn parse_scoped(p: &mut Parser) {
     let m = p.start("scoped");
     loop {
-        if p.bump_matching(Token::IdUpper) && p.bump_matching(Token::SymDot) {
+        if p.bump_matching(Token::IdUpper) && p.expect(Token::SymDot, "`.`") {
             match p.peek() {
                 TokenType::Token(Token::IdLower) => {
                     p.bump();
@@ -126,11 +129,11 @@ fn parse_scoped(p: &mut Parser) {
                     break;
                 }
                 TokenType::Token(Token::IdUpper) => continue,
-                _ => todo!("ERROR"),
+                _ => p.error("a variable name"),
             }
-        } else {
-            todo!("ERROR");
         }
+        m.complete(p, SyntaxPart::Error);
+        break;
     }
 }
 
@@ -138,33 +141,18 @@ fn parse_subexpression(p: &mut Parser, minimum_binding_power: u8) -> bool {
     let mut start = p.start("subexpr");
     if parse_single_term(p) {
         if loop {
-            let op = match p.peek() {
-                TokenType::None => break true,
-                TokenType::Token(Token::OpAdd) => Operator::Add,
-                TokenType::Token(Token::OpAnd) => Operator::And,
-                TokenType::Token(Token::OpConcat) => Operator::Concat,
-                // TokenType::Token(Token::OpCons) => Operator::Cons,
-                TokenType::Token(Token::OpDiv) => Operator::Div,
-                TokenType::Token(Token::OpEq) => Operator::Eq,
-                TokenType::Token(Token::OpGte) => Operator::Gte,
-                TokenType::Token(Token::OpGt) => Operator::Gt,
-                TokenType::Token(Token::OpLte) => Operator::Lte,
-                TokenType::Token(Token::OpLt) => Operator::Lt,
-                TokenType::Token(Token::OpMod) => Operator::Mod,
-                TokenType::Token(Token::OpMul) => Operator::Mul,
-                TokenType::Token(Token::OpNeq) => Operator::Neq,
-                TokenType::Token(Token::OpOr) => Operator::Or,
-                TokenType::Token(Token::OpPipe) => Operator::Pipe,
-                TokenType::Token(Token::OpSub) => Operator::Sub,
-                _ => break true, // we’ll handle errors later.
+            let Some((left_binding_power, right_binding_power)) = p.peek_infix_binding_power()
+            else {
+                break true; // we’ll handle errors later.
             };
-            let (left_binding_power, right_binding_power) = infix_binding_power(op);
             if left_binding_power < minimum_binding_power {
                 break false;
             }
             // Eat the operator’s token.
             p.bump();
-            parse_subexpression(p, right_binding_power);
+            if !parse_subexpression(p, right_binding_power) {
+                p.error("an expression");
+            }
             start.commit(p, Context::BinOp);
         } {
             loop {
@@ -175,10 +163,10 @@ fn parse_subexpression(p: &mut Parser, minimum_binding_power: u8) -> bool {
                         // todo!("ERROR: Application args must be single terms. peek = {:?}", p.peek());
                     }
                 } else if p.bump_matching(Token::SymDot) {
-                    if p.bump_matching(Token::IdLower) {
+                    if p.expect(Token::IdLower, "a field name") {
                         start.commit(p, Context::Access);
                     } else {
-                        todo!("ERROR: Access key is not IdLower");
+                        start.commit(p, SyntaxPart::Error);
                     }
                 } else {
                     break;
@@ -197,26 +185,38 @@ pub(crate) fn parse_prefix_op(p: &mut Parser, operator: Operator) {
     let right_binding_power = prefix_binding_power(operator).unwrap();
     let m = p.start("prefix");
     p.bump();
-    parse_subexpression(p, right_binding_power);
+    if !parse_subexpression(p, right_binding_power) {
+        p.error("an expression");
+    }
     m.complete(p, Context::PrefixOp);
 }
 
 fn parse_let(p: &mut Parser) {
+    assert!(p.peek().is(Token::KWLet));
     let declaration = p.start("let_expr");
-    if p.bump_matching(Token::KWLet) && parse_pattern(p) && p.bump_matching(Token::SymEquals) {
-        let expr_m = p.start("let_body");
-        expr(p);
-        if p.peek().is(Token::OpSeq) {
-            expr_m.complete(p, Context::Expr);
-            p.bump();
-            expr(p);
-            declaration.complete(p, Context::Declaration);
-        } else {
-            todo!("ERROR: Missing semi")
-        }
-    } else {
-        todo!("ERROR");
+    p.bump();
+    if !parse_pattern(p) {
+        p.error("a pattern");
     }
+    p.expect(Token::SymEquals, "`=`");
+    let expr_m = p.start("let_body");
+    expr(p);
+    expr_m.complete(p, Context::Expr);
+    p.expect(Token::OpSeq, "`;`");
+    expr(p);
+    declaration.complete(p, Context::Declaration);
+}
+
+fn parse_assert(p: &mut Parser) {
+    assert!(p.peek().is(Token::KWAssert));
+    let assertion = p.start("assert");
+    p.bump();
+    let condition_m = p.start("assert_condition");
+    expr(p);
+    condition_m.complete(p, Context::Condition);
+    p.expect(Token::OpSeq, "`;`");
+    expr(p);
+    assertion.complete(p, Context::Assert);
 }
 
 fn parse_lambda(p: &mut Parser) {
@@ -225,7 +225,12 @@ fn parse_lambda(p: &mut Parser) {
     p.bump();
     let params = p.start("lambda_params");
     loop {
-        super::parse_pattern(p);
+        if !super::parse_pattern(p) {
+            p.error("a pattern or `->`");
+            params.complete(p, Context::Params);
+            lambda.complete(p, SyntaxPart::Error);
+            break;
+        }
         if p.bump_matching(Token::SymArrow) {
             params.complete(p, Context::Params);
             expr(p);
@@ -242,22 +247,20 @@ fn parse_switch(p: &mut Parser) {
     let mut expr_m = p.start("switch_expr");
     expr(p);
     expr_m.complete(p, Context::Expr);
-    if !p.bump_matching(Token::KWOn) {
-        todo!("ERROR expected KWOn");
-    }
+    p.expect(Token::KWOn, "`on`");
     loop {
         let branch_m = p.start("case");
         if p.bump_matching(Token::KWCase) {
-            super::parse_pattern(p);
+            if !super::parse_pattern(p) {
+                p.error("a pattern");
+            }
             if p.peek().is(Token::KWIf) {
                 let guard_m = p.start("case_guard");
                 p.bump();
                 expr(p);
                 guard_m.complete(p, Context::Guard);
             }
-            if !p.bump_matching(Token::SymArrow) {
-                todo!("ERROR");
-            }
+            p.expect(Token::SymArrow, "`->`");
             expr_m = p.start("branch_expr");
             expr(p);
             expr_m.complete(p, Context::Expr);
@@ -270,35 +273,10 @@ fn parse_switch(p: &mut Parser) {
     where_m.complete(p, Context::Switch);
 }
 
-fn infix_binding_power(operator: Operator) -> (u8, u8) {
-    match operator {
-        //Left associativity
-        Operator::Pipe => (2, 3),
-        Operator::Eq => (4, 5),
-        Operator::Gt => (4, 5),
-        Operator::Gte => (4, 5),
-        Operator::Lt => (4, 5),
-        Operator::Lte => (4, 5),
-        Operator::Neq => (4, 5),
-        Operator::Add => (6, 7),
-        Operator::Sub => (6, 7),
-        Operator::Div => (8, 9),
-        Operator::Mul => (8, 9),
-
-        //Right associativity
-        Operator::Or => (2, 1),
-        Operator::And => (3, 2),
-        Operator::Concat => (5, 4),
-        Operator::Cons => (5, 4),
-
-        //Unknown
-        Operator::Mod => (8, 9),
-    }
-}
-
 fn prefix_binding_power(operator: Operator) -> Option<u8> {
     if let Operator::Sub = operator {
-        Some(10)
+        // Tighter than `*` but not `^`, so `-a ^ 2` is `-(a ^ 2)`
+        Some(Operator::Pow.infix_binding_power().1)
     } else {
         None
     }
@@ -1,43 +1,66 @@
 use crate::{
-    syntax::{Context, StringToken, Token, TokenType},
-    Parsed, Parser,
+    parser::Marker,
+    syntax::{Context, StringToken, SyntaxPart, Token, TokenType},
+    Fixities, Parsed, Parser,
 };
 
 mod expression;
 mod module;
 mod pattern;
 mod ren_type;
+mod reparse;
 use higher_ast::Operator;
 use pattern::parse_pattern;
 use ren_type::parse_type;
+pub use reparse::{reparse_module, reparse_module_with, TextEdit};
 
-pub fn parse_module(input: &str) -> Parsed {
-    let mut p = Parser::new(input);
+pub fn parse_module(input: &str) -> Parsed<'_> {
+    parse_module_with(input, &Fixities::new())
+}
+
+/// Parse a module which imports user-defined operators with the given fixities. Fixities
+/// declared in the module itself take precedence.
+pub fn parse_module_with<'source>(input: &'source str, imported: &Fixities) -> Parsed<'source> {
+    let mut fixities = imported.clone();
+    fixities.extend(&crate::fixity::declared_in(input));
+    let mut p = Parser::new(input).with_fixities(fixities);
     let root = p.start("module");
     module::module(&mut p);
     root.complete(&mut p, Context::Module);
     p.parse()
 }
 
-pub fn parse_expression(input: &str) -> Parsed {
+pub fn parse_expression(input: &str) -> Parsed<'_> {
     let mut p = Parser::new(input);
     let root = p.start("expr_root");
     expression::expr(&mut p);
+    finish_input(&mut p);
     root.complete(&mut p, Context::Expr);
     p.parse()
 }
 
 pub fn parse_repl_stmt(
     input: &str,
-) -> Result<super::REPLStmt<Parsed, Parsed, Parsed>, &'static str> {
+) -> Result<super::REPLStmt<Parsed<'_>, Parsed<'_>, Parsed<'_>>, &'static str> {
     let mut p = Parser::new(input);
     let stmt = match p.peek() {
         TokenType::Token(Token::KWImport) => {
+            let m = p.start("repl_stmt_root");
             module::parse_import(&mut p);
+            finish_stmt(&mut p, m);
             super::REPLStmt::Import
         }
-        TokenType::Token(Token::KWExt | Token::KWLet | Token::KWType) => {
+        TokenType::Token(
+            Token::KWExt
+            | Token::KWLet
+            | Token::KWType
+            | Token::KWExpect
+            | Token::KWInfixl
+            | Token::KWInfixr,
+        ) => {
+            let m = p.start("repl_stmt_root");
             module::parse_declaration(&mut p);
+            finish_stmt(&mut p, m);
             super::REPLStmt::Decl
         }
         TokenType::Token(Token::KWPub) => {
@@ -52,6 +75,7 @@ pub fn parse_repl_stmt(
         _ => {
             let root = p.start("repl_stmt_root");
             expression::expr(&mut p);
+            finish_input(&mut p);
             root.complete(&mut p, Context::Expr);
             super::REPLStmt::Expr
         }
@@ -59,20 +83,52 @@ pub fn parse_repl_stmt(
     Ok(stmt(p.parse()))
 }
 
-pub(crate) struct NestedParser<E> {
+/// Wrap any remaining input in an `Error` node, so that all of the input is consumed
+fn finish_input(p: &mut Parser) {
+    if !p.peek().is(TokenType::None) {
+        p.recover("end of input", &[]);
+    }
+}
+
+/// Statements without a wrapping root node can only be followed by the end of the input.
+/// Any remaining input is wrapped (along with the statement) in an `Error` node.
+fn finish_stmt(p: &mut Parser, m: Marker) {
+    if p.peek().is(TokenType::None) {
+        m.discard();
+    } else {
+        p.recover("end of input", &[]);
+        m.complete(p, SyntaxPart::Error);
+    }
+}
+
+pub(crate) struct NestedParser<E = crate::ParseError> {
     func: fn(&mut Parser<E>),
     /// Whether the value part of a record literal is required
     record_value_required: bool,
     /// Whether {} is a valid record
     record_allow_empty: bool,
+    /// Whether an array or record may contain a single `..name`, matching the remaining items
+    /// or fields
+    allow_spread: bool,
+    /// Whether a record may start with `rec |`, replacing some of the fields of `rec`
+    record_allow_update: bool,
+    /// Whether a parenthesised term may end with a type annotation, e.g. `(x : Number)`
+    allow_annotation: bool,
+}
+// Derived impls would require `E: Copy`
+impl<E> Clone for NestedParser<E> {
+    fn clone(&self) -> Self {
+        *self
+    }
 }
+impl<E> Copy for NestedParser<E> {}
 impl<E> NestedParser<E> {
     pub fn call(&self, p: &mut Parser<E>) {
         (self.func)(p)
     }
 }
 
-pub(crate) fn parse_literal<E>(p: &mut Parser<E>, nested: NestedParser<E>) {
+pub(crate) fn parse_literal(p: &mut Parser, nested: NestedParser) {
     match p.peek() {
         TokenType::Token(tok) => match tok {
             Token::Number | /*Token::Bool |*/ Token::SymUnderscore | Token::IdLower => p.bump(),
@@ -100,8 +156,9 @@ fn parse_string(p: &mut Parser, nested: NestedParser) {
             let nested_m = p.start("string_nested");
             nested.call(p);
             nested_m.complete(p, Context::Expr);
-            if !p.bump_matching(Token::SymRBrace) {
-                todo!("ERROR");
+            if !p.expect(Token::SymRBrace, "`}`") {
+                str_m.complete(p, Context::String);
+                break;
             }
         }
         if p.bump_matching(StringToken::Delimiter) {
@@ -109,7 +166,9 @@ fn parse_string(p: &mut Parser, nested: NestedParser) {
             break;
         }
         if let TokenType::Token(_) | TokenType::None = p.peek() {
-            todo!("ERROR");
+            p.error("`\"`");
+            str_m.complete(p, Context::String);
+            break;
         }
     }
 }
@@ -118,10 +177,14 @@ fn parse_parenthesised(p: &mut Parser, nested: NestedParser) {
     let m = p.start("paren");
     p.bump();
     nested.call(p);
-    if p.peek() == TokenType::Token(Token::SymRParen) {
-        p.bump();
+    if nested.allow_annotation && p.bump_matching(Token::SymColon) {
+        parse_type(p);
+        p.expect(Token::SymRParen, "`)`");
+        m.complete(p, Context::Annotated);
+    } else {
+        p.expect(Token::SymRParen, "`)`");
         m.complete(p, Context::Parenthesised);
-    } //else error
+    }
 }
 
 fn parse_record(p: &mut Parser, nested: NestedParser) {
@@ -132,27 +195,56 @@ fn parse_record(p: &mut Parser, nested: NestedParser) {
         p.bump();
         rec_m.complete(p, Context::Record);
     } else {
+        let mut spread = false;
+        let mut update = false;
+        if nested.record_allow_update && p.peek().is(Token::SymLParen) {
+            // Anything other than a variable must be parenthesised to be updated
+            parse_parenthesised(p, nested);
+            p.expect(Token::OpOr, "`|`");
+            update = true;
+        }
+        let mut first = !update;
         loop {
             let field = p.start("field");
-            p.bump_matching(Token::IdLower);
-            if p.bump_matching(Token::SymColon) {
-                nested.call(p)
-            } else if nested.record_value_required {
-                todo!("Error! required `: value` part of record")
+            if nested.allow_spread && !spread && p.peek().is(Token::SymDoubleDot) {
+                spread = true;
+                parse_spread(p);
+            } else {
+                p.expect(Token::IdLower, "a field name");
+                if first && nested.record_allow_update && p.peek().is(Token::OpOr) {
+                    // `{ rec | ... }`: the name was the record being updated, not a field
+                    field.discard();
+                    p.bump();
+                    update = true;
+                    first = false;
+                    continue;
+                }
+                if p.bump_matching(Token::SymColon) {
+                    nested.call(p)
+                } else if nested.record_value_required {
+                    p.error("`:`");
+                }
+            }
+            if !p.peek().is(Token::SymComma) && !p.peek().is(Token::SymRBrace) {
+                p.recover("`,` or `}`", &[Token::SymComma, Token::SymRBrace]);
             }
+            first = false;
             if p.peek().is(Token::SymComma) {
                 field.complete(p, Context::Field);
                 p.bump();
                 continue; // No dangling comma
             }
-            if p.peek().is(Token::SymRBrace) {
-                field.complete(p, Context::Field);
-                p.bump();
-                rec_m.complete(p, Context::Record);
-                break;
-            } else {
-                todo!("ERROR: {:?}", p.peek());
-            }
+            field.complete(p, Context::Field);
+            p.bump_matching(Token::SymRBrace);
+            rec_m.complete(
+                p,
+                if update {
+                    Context::Update
+                } else {
+                    Context::Record
+                },
+            );
+            break;
         }
     }
 }
@@ -160,21 +252,41 @@ fn parse_record(p: &mut Parser, nested: NestedParser) {
 fn parse_array(p: &mut Parser, nested: NestedParser) {
     let m = p.start("array");
     p.bump();
+    let mut spread = false;
     loop {
         if p.bump_matching(Token::SymRBracket) {
             m.complete(p, Context::Array);
             break;
         }
+        if p.peek().is(TokenType::None) {
+            p.error("`]`");
+            m.complete(p, Context::Array);
+            break;
+        }
         let item_m = p.start("array_item");
-        nested.call(p);
+        if nested.allow_spread && !spread && p.peek().is(Token::SymDoubleDot) {
+            spread = true;
+            parse_spread(p);
+        } else {
+            nested.call(p);
+        }
         item_m.complete(p, Context::Item);
         if p.bump_matching(Token::SymComma) {
             continue; // No dangling comma
         } else if !p.peek().is(Token::SymRBracket) {
-            todo!("ERROR: non-comma following item in array");
+            p.recover("`,` or `]`", &[Token::SymComma, Token::SymRBracket]);
+            p.bump_matching(Token::SymComma);
         }
     }
 }
 
+/// `..name`, matching the rest of an array or record
+fn parse_spread(p: &mut Parser) {
+    let m = p.start("spread");
+    p.bump();
+    p.expect(Token::IdLower, "a variable name");
+    m.complete(p, Context::Spread);
+}
+
 #[cfg(test)]
 mod tests;
@@ -1,8 +1,20 @@
 use crate::{
+    fixity::parse_precedence,
     parser::Parser,
-    syntax::{Context, StringToken, Token, TokenType},
+    syntax::{Context, StringToken, SyntaxPart, Token, TokenType},
 };
 
+/// The tokens which can start a top-level declaration
+pub(super) const DECLARATION_START: &[Token] = &[
+    Token::KWPub,
+    Token::KWLet,
+    Token::KWExt,
+    Token::KWType,
+    Token::KWExpect,
+    Token::KWInfixl,
+    Token::KWInfixr,
+];
+
 pub(super) fn module(p: &mut Parser) {
     if p.peek().is(Token::KWImport) {
         let imports = p.start("imports");
@@ -11,10 +23,14 @@ pub(super) fn module(p: &mut Parser) {
         }
         imports.complete(p, Context::Imports);
     }
-    if let TokenType::Token(Token::KWPub | Token::KWLet | Token::KWExt) = p.peek() {
+    if !p.peek().is(TokenType::None) {
         let declarations = p.start("declarations");
-        while let TokenType::Token(Token::KWPub | Token::KWLet | Token::KWExt) = p.peek() {
-            parse_declaration(p);
+        loop {
+            match p.peek() {
+                TokenType::None => break,
+                TokenType::Token(tok) if DECLARATION_START.contains(&tok) => parse_declaration(p),
+                _ => p.recover("a declaration", DECLARATION_START),
+            }
         }
         declarations.complete(p, Context::Declarations);
     }
@@ -40,7 +56,10 @@ pub(super) fn parse_import(p: &mut Parser) {
                     p.bump();
                     break;
                 }
-                _ => todo!("ERROR"),
+                _ => {
+                    p.error("`\"`");
+                    break;
+                }
             }
         }
         str_m.complete(p, Context::String);
@@ -55,76 +74,112 @@ pub(super) fn parse_import(p: &mut Parser) {
                             break;
                         }
                     } else {
-                        todo!("ERROR");
+                        p.error("a module name");
+                        alias.complete(p, SyntaxPart::Error);
+                        break;
                     }
                 }
             } else {
-                todo!("ERROR");
+                p.error("a module name");
             }
         }
 
-        // if p.bump_matching(Token::KWExposing) && p.bump_matching(Token::SymLBrace) {
-        //     let exp_block = p.start("exposing");
-        //     loop {
-        //         if p.bump_matching(Token::IdLower) {
-        //             if p.bump_matching(Token::SymRBrace) {
-        //                 exp_block.complete(p, Context::ExposingBlock);
-        //                 break;
-        //             }
-        //             if !p.bump_matching(Token::Comma) {
-        //                 todo!("ERROR");
-        //             }
-        //         } else {
-        //             todo!("ERROR");
-        //         }
-        //     }
-        // }
-        import.complete(p, Context::Import);
+        if p.bump_matching(Token::KWExposing) {
+            parse_exposing(p);
+        }
+    } else {
+        p.error("an import path");
+    }
+    import.complete(p, Context::Import);
+}
+
+/// The names in an exposing block, e.g. `{ map, <+> }`
+pub(super) fn parse_exposing(p: &mut Parser) {
+    if !p.peek().is(Token::SymLBrace) {
+        p.error("`{`");
+        return;
+    }
+    let block = p.start("exposing");
+    p.bump();
+    loop {
+        if !p.bump_matching(Token::IdLower) && !p.bump_matching(Token::OpCustom) {
+            p.error("a variable name or an operator");
+            break;
+        }
+        if !p.bump_matching(Token::SymComma) {
+            break;
+        }
     }
+    p.expect(Token::SymRBrace, "`}`");
+    block.complete(p, Context::ExposingBlock);
 }
 
 pub(super) fn parse_declaration(p: &mut Parser) {
     let dec_m = p.start("declaration");
     p.bump_matching(Token::KWPub);
-    if p.bump_matching(Token::KWLet) && p.bump_matching(Token::IdLower) {
+    if p.bump_matching(Token::KWLet) {
+        p.expect(Token::IdLower, "a variable name");
         if p.bump_matching(Token::SymColon) {
             super::parse_type(p);
         }
-        if p.bump_matching(Token::SymEquals) {
+        if p.expect(Token::SymEquals, "`=`") {
             let expr_m = p.start("declaration_body");
             super::expression::expr(p);
             expr_m.complete(p, Context::Expr);
-        } else {
-            todo!("ERROR: Missing expression");
         }
-    } else if p.bump_matching(Token::KWExt) && p.bump_matching(Token::IdLower) {
+    } else if p.bump_matching(Token::KWExt) {
+        p.expect(Token::IdLower, "a variable name");
         if p.bump_matching(Token::SymColon) {
             super::parse_type(p);
         }
-        if p.bump_matching(Token::SymEquals) && p.peek().is(Token::SymDoubleQuote) {
-            let str_m = p.start("ext_name");
-            p.bump();
-            loop {
-                match p.peek() {
-                    TokenType::String(StringToken::Text | StringToken::Escape) => p.bump(),
-                    TokenType::String(StringToken::Delimiter) => {
-                        p.bump();
-                        break;
+        if p.expect(Token::SymEquals, "`=`") {
+            if p.peek().is(Token::SymDoubleQuote) {
+                let str_m = p.start("ext_name");
+                p.bump();
+                loop {
+                    match p.peek() {
+                        TokenType::String(StringToken::Text | StringToken::Escape) => p.bump(),
+                        TokenType::String(StringToken::Delimiter) => {
+                            p.bump();
+                            break;
+                        }
+                        _ => {
+                            p.error("`\"`");
+                            break;
+                        }
                     }
-                    _ => todo!("ERROR"),
                 }
+                str_m.complete(p, Context::String);
+            } else {
+                p.error("an external name");
             }
-            str_m.complete(p, Context::String);
+        }
+    } else if p.bump_matching(Token::KWType) {
+        p.expect(Token::IdUpper, "a type name");
+        while p.bump_matching(Token::IdLower) {}
+        if p.expect(Token::SymEquals, "`=`") {
+            super::parse_type(p);
+        }
+    } else if p.bump_matching(Token::KWExpect) {
+        let expr_m = p.start("expect_body");
+        super::expression::expr(p);
+        expr_m.complete(p, Context::Expr);
+    } else if p.bump_matching(Token::KWInfixl) || p.bump_matching(Token::KWInfixr) {
+        if p.peek().is(Token::Number) && p.peek_text().and_then(parse_precedence).is_some() {
+            p.bump();
         } else {
-            todo!("ERROR: Missing external name");
+            // An out of range precedence is still part of the declaration
+            p.error("a precedence from 0 to 9");
+            p.bump_matching(Token::Number);
+        }
+        p.expect(Token::OpCustom, "an operator");
+        if p.expect(Token::SymEquals, "`=`") {
+            let expr_m = p.start("infix_body");
+            super::expression::expr(p);
+            expr_m.complete(p, Context::Expr);
         }
-    } else if p.bump_matching(Token::KWType)
-        && p.bump_matching(Token::IdUpper)
-        && p.bump_matching(Token::SymEquals)
-    {
-        super::parse_type(p);
     } else {
-        todo!("ERROR: recieved: {:?}", p.peek());
+        p.error("`let`, `ext`, `type`, `expect`, `infixl` or `infixr`");
     }
     dec_m.complete(p, Context::Declaration);
 }
@@ -1,16 +1,21 @@
 use crate::{
-    syntax::{Context, Token, TokenType},
+    syntax::{Context, SyntaxPart, Token, TokenType},
     Parser,
 };
 
 use super::parse_literal;
 
-const NESTED_PATTERN: super::NestedParser = super::NestedParser {
+pub(super) const NESTED_PATTERN: super::NestedParser = super::NestedParser {
     func: |p| {
-        pattern(p);
+        if !pattern(p) {
+            p.error("a pattern");
+        }
     },
     record_value_required: false,
     record_allow_empty: false,
+    allow_spread: true,
+    record_allow_update: false,
+    allow_annotation: false,
 };
 
 pub(super) fn parse_pattern(p: &mut Parser) -> bool {
@@ -50,7 +55,8 @@ fn pattern(p: &mut Parser) -> bool {
                     }
                     typ_m.complete(p, Context::TypeMatch);
                 } else {
-                    todo!("ERROR: invalid type name token")
+                    p.error("a type name");
+                    typ_m.complete(p, SyntaxPart::Error);
                 }
             }
             Token::SymHash => {
@@ -60,8 +66,11 @@ fn pattern(p: &mut Parser) -> bool {
                     if p.bump_whitespace() {
                         let args = p.start("args");
                         loop {
-                            pattern(p); //TODO: better parsing for constructor
-                            if !p.bump_whitespace() || p.peek().is(Token::SymArrow) {
+                            //TODO: better parsing for constructor
+                            if !pattern(p)
+                                || !p.bump_whitespace()
+                                || p.peek().is(Token::SymArrow)
+                            {
                                 break;
                             }
                         }
@@ -69,10 +78,13 @@ fn pattern(p: &mut Parser) -> bool {
                     }
                     con_m.complete(p, Context::Constructor);
                 } else {
-                    todo!("ERROR")
+                    p.error("a constructor name");
+                    con_m.complete(p, SyntaxPart::Error);
                 }
             }
-            _ => todo!("ERROR: invalid Pattern start token: {:?}", tok),
+            _ => {
+                return false;
+            }
         },
         TokenType::None => {
             return false;
@@ -1,40 +1,52 @@
 use crate::{
     parser::Parser,
-    syntax::{Context, Token, TokenType},
+    syntax::{Context, SyntaxPart, Token, TokenType},
 };
 
-const NESTED_TYPE: super::NestedParser = super::NestedParser {
+pub(super) const NESTED_TYPE: super::NestedParser = super::NestedParser {
     func: typ,
     record_value_required: true,
     record_allow_empty: true,
+    allow_spread: false,
+    record_allow_update: false,
+    allow_annotation: false,
 };
 
 pub(super) fn parse_type(p: &mut Parser) {
     let m = p.start("type");
-    typ(p);
+    if p.peek().is(Token::KWForall) {
+        // Quantifiers are only allowed at the top of a type, e.g. `forall a b. a -> b`
+        let forall_m = p.start("forall");
+        p.bump();
+        p.expect(Token::IdLower, "a type variable");
+        while p.bump_matching(Token::IdLower) {}
+        p.expect(Token::SymDot, "`.`");
+        typ(p);
+        forall_m.complete(p, Context::Forall);
+    } else {
+        typ(p);
+    }
     m.complete(p, Context::Type);
 }
 
 fn typ(p: &mut Parser) {
-    match p.peek() {
-        TokenType::Token(Token::SymLBrace) => super::parse_record(p, NESTED_TYPE), // Rec
-        TokenType::Token(Token::OpMul) => p.bump(),                                // Any
-        _ => {
-            parse_subtype(p, 0);
-        }
+    if !parse_subtype(p, 0) {
+        p.error("a type");
     }
 }
 
 fn parse_single_term(p: &mut Parser) -> bool {
     match p.peek() {
+        TokenType::Token(Token::SymLBrace) => super::parse_record(p, NESTED_TYPE), // Rec
+        TokenType::Token(Token::OpMul) => p.bump(),                                // Any
         TokenType::Token(Token::SymHash) => {
             // Variant
             let var_m = p.start("variant");
             p.bump();
-            if p.bump_matching(Token::IdLower) {
+            if p.expect(Token::IdLower, "a variant name") {
                 var_m.complete(p, Context::Variant);
             } else {
-                todo!("ERROR! variant name must be IdLower");
+                var_m.complete(p, SyntaxPart::Error);
             }
         }
         TokenType::Token(Token::IdUpper) => p.bump(), // Con
@@ -48,38 +60,30 @@ fn parse_single_term(p: &mut Parser) -> bool {
 
 fn parse_subtype(p: &mut Parser, minimum_binding_power: u8) -> bool {
     let mut start = p.start("subtyp");
-    if parse_single_term(p) {
-        if loop {
-            let (left_binding_power, right_binding_power, ctx) = match p.peek() {
-                TokenType::Token(Token::OpOr) => (2, 3, Context::SumType),
-                TokenType::Token(Token::SymArrow) => (2, 1, Context::FunType),
-                TokenType::None => break true,
-                _ => break true, // we’ll handle errors later.
-            };
-            if left_binding_power < minimum_binding_power {
-                break false;
-            }
-            // Eat the operator’s token.
-            p.bump();
-            parse_subtype(p, right_binding_power);
-            start.commit(p, ctx);
-        } {
-            loop {
-                if p.bump_whitespace() {
-                    if parse_single_term(p) {
-                        start.commit(p, Context::Application);
-                    } else {
-                        // todo!("ERROR: Application args must be single terms. peek = {:?}", p.peek());
-                    }
-                } else {
-                    break;
-                }
-            }
-        }
+    if !parse_single_term(p) {
         start.discard();
-        true
-    } else {
-        start.discard();
-        false
+        return false;
+    }
+    // Application binds tighter than any operator, and its arguments must be single terms
+    while p.bump_whitespace() && parse_single_term(p) {
+        start.commit(p, Context::Application);
     }
+    loop {
+        let (left_binding_power, right_binding_power, ctx) = match p.peek() {
+            TokenType::Token(Token::OpOr) => (2, 3, Context::SumType),
+            TokenType::Token(Token::SymArrow) => (2, 1, Context::FunType),
+            _ => break, // we’ll handle errors later.
+        };
+        if left_binding_power < minimum_binding_power {
+            break;
+        }
+        // Eat the operator’s token.
+        p.bump();
+        if !parse_subtype(p, right_binding_power) {
+            p.error("a type");
+        }
+        start.commit(p, ctx);
+    }
+    start.discard();
+    true
 }
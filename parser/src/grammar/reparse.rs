@@ -0,0 +1,265 @@
+//! Incremental reparsing of a module after an edit.
+//!
+//! Only the innermost import, declaration or brace block containing the edit is relexed and
+//! reparsed, and every other green subtree is reused. A block is only reused if it parses
+//! exactly as it would as part of the whole module, otherwise the whole module is reparsed.
+
+use std::ops::Range;
+
+use rowan::{GreenNode, NodeOrToken, TextRange, TextSize};
+
+use super::{expression, module, parse_record, pattern, ren_type, NestedParser};
+use crate::{
+    syntax::{lexer::Lexer, Context, SyntaxNode, SyntaxPart, Token, TokenType},
+    Fixities, Parsed, Parser,
+};
+
+/// A change to the source of a module: the `range` of the old source is replaced by `text`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub range: Range<usize>,
+    pub text: String,
+}
+impl TextEdit {
+    pub fn new<S: Into<String>>(range: Range<usize>, text: S) -> Self {
+        Self {
+            range,
+            text: text.into(),
+        }
+    }
+    /// Apply the edit to the source it was made to
+    pub fn apply(&self, input: &str) -> String {
+        let mut output = input.to_string();
+        output.replace_range(self.range.clone(), &self.text);
+        output
+    }
+    /// How many bytes the text after the edited range moves by
+    fn delta(&self) -> isize {
+        self.text.len() as isize - self.range.len() as isize
+    }
+}
+
+/// Reparse a module after an edit, given its parse before the edit and its source after it
+pub fn reparse_module<'source>(
+    parsed: &Parsed,
+    edit: &TextEdit,
+    input: &'source str,
+) -> Parsed<'source> {
+    reparse_module_with(parsed, edit, input, &Fixities::new())
+}
+
+/// Reparse a module after an edit, given its parse before the edit and its source after it.
+/// `imported` must be the fixities the module was originally parsed with.
+pub fn reparse_module_with<'source>(
+    parsed: &Parsed,
+    edit: &TextEdit,
+    input: &'source str,
+    imported: &Fixities,
+) -> Parsed<'source> {
+    reparse_block(parsed, edit, input).unwrap_or_else(|| super::parse_module_with(input, imported))
+}
+
+/// The kinds of node which can be reparsed on their own
+#[derive(Clone, Copy)]
+enum Fragment {
+    Import,
+    Declaration,
+    /// The names exposed by an import, e.g. `{ map, <+> }`
+    Exposing,
+    /// A record, as an expression, pattern or type
+    Record(NestedParser),
+}
+impl Fragment {
+    fn of(node: &SyntaxNode) -> Option<Self> {
+        let SyntaxPart::Context(context) = node.kind() else {
+            return None;
+        };
+        Some(match context {
+            Context::Import => Self::Import,
+            // `let` expressions are declarations too
+            Context::Declaration
+                if node.parent()?.kind() == SyntaxPart::Context(Context::Declarations) =>
+            {
+                Self::Declaration
+            }
+            Context::ExposingBlock => Self::Exposing,
+            Context::Record | Context::Update => {
+                // Patterns and types never contain expressions, so the nearest one decides
+                let nested = node
+                    .ancestors()
+                    .skip(1)
+                    .find_map(|ancestor| match ancestor.kind() {
+                        SyntaxPart::Context(Context::Pattern) => Some(pattern::NESTED_PATTERN),
+                        SyntaxPart::Context(Context::Type) => Some(ren_type::NESTED_TYPE),
+                        _ => None,
+                    })
+                    .unwrap_or(expression::NESTED_EXPR);
+                Self::Record(nested)
+            }
+            _ => return None,
+        })
+    }
+    /// Whether a fragment of this kind can start with `token`
+    fn starts_with(&self, token: TokenType) -> bool {
+        match (self, token) {
+            (Self::Import, TokenType::Token(Token::KWImport)) => true,
+            (Self::Declaration, TokenType::Token(token)) => {
+                module::DECLARATION_START.contains(&token)
+            }
+            (Self::Exposing | Self::Record(_), TokenType::Token(Token::SymLBrace)) => true,
+            _ => false,
+        }
+    }
+    /// Parse `input` as a single fragment, returning its tree and whether the parser is left
+    /// with whitespace pending. Fragments which don't consume all of the input, or have errors,
+    /// are rejected.
+    fn parse(
+        self,
+        input: &str,
+        fixities: &Fixities,
+        after_whitespace: bool,
+    ) -> Option<(GreenNode, bool)> {
+        let mut p = Parser::new(input).with_fixities(fixities.clone());
+        if after_whitespace {
+            p = p.after_whitespace();
+        }
+        match self {
+            Self::Import => module::parse_import(&mut p),
+            Self::Declaration => module::parse_declaration(&mut p),
+            Self::Exposing => module::parse_exposing(&mut p),
+            Self::Record(nested) => parse_record(&mut p, nested),
+        }
+        if !p.at_end() {
+            return None;
+        }
+        let pending_whitespace = p.pending_whitespace();
+        let parsed = p.parse();
+        if parsed.has_errors() {
+            return None;
+        }
+        Some((parsed.syntax().green().into_owned(), pending_whitespace))
+    }
+}
+
+pub(super) fn reparse_block<'source>(
+    parsed: &Parsed,
+    edit: &TextEdit,
+    input: &'source str,
+) -> Option<Parsed<'source>> {
+    let root = parsed.syntax();
+    let old_len = usize::from(root.text_range().end());
+    if root.kind() != SyntaxPart::Context(Context::Module)
+        || edit.range.start > edit.range.end
+        || edit.range.end > old_len
+        || input.len() as isize != old_len as isize + edit.delta()
+    {
+        return None;
+    }
+    let range = TextRange::new(
+        TextSize::try_from(edit.range.start).ok()?,
+        TextSize::try_from(edit.range.end).ok()?,
+    );
+    let covering = match root.covering_element(range) {
+        NodeOrToken::Node(node) => node,
+        NodeOrToken::Token(token) => token.parent()?,
+    };
+    covering
+        .ancestors()
+        .find_map(|node| reparse_node(parsed, &node, edit, input))
+}
+
+fn reparse_node<'source>(
+    parsed: &Parsed,
+    node: &SyntaxNode,
+    edit: &TextEdit,
+    input: &'source str,
+) -> Option<Parsed<'source>> {
+    let fragment = Fragment::of(node)?;
+    let old_range = node.text_range();
+    let (start, end) = (usize::from(old_range.start()), usize::from(old_range.end()));
+    if edit.range.start < start || edit.range.end > end {
+        return None;
+    }
+    // Errors at either end of the block may belong to its neighbours
+    if parsed
+        .errors()
+        .iter()
+        .any(|error| (start..=end).contains(&error.range().start))
+    {
+        return None;
+    }
+    // Declaring an operator changes how the rest of the module parses
+    if declares_fixity(node) {
+        return None;
+    }
+    // The text before the block is unchanged, but its first token must not join onto the
+    // last token before it
+    if start > 0 && edit.range.start == start && !input[..start].ends_with(char::is_whitespace) {
+        return None;
+    }
+    let new_end = end.checked_add_signed(edit.delta())?;
+    let text = input.get(start..new_end)?;
+    if !fragment.starts_with(relex(&input[start..], text.len())?) {
+        return None;
+    }
+
+    let fixities = parsed.fixities();
+    let old_text = node.text().to_string();
+    let (green, pending) = fragment.parse(text, fixities, false)?;
+    let (green_after_whitespace, pending_after_whitespace) =
+        fragment.parse(text, fixities, true)?;
+    let (_, old_pending) = fragment.parse(&old_text, fixities, false)?;
+    let (_, old_pending_after_whitespace) = fragment.parse(&old_text, fixities, true)?;
+    // The block must parse the same whether or not there was whitespace before it, and leave
+    // the parser as the old block did for whatever follows it
+    if green != green_after_whitespace
+        || pending != old_pending
+        || pending_after_whitespace != old_pending_after_whitespace
+        || green.kind() != node.green().kind()
+        || declares_fixity(&SyntaxNode::new_root(green.clone()))
+    {
+        return None;
+    }
+
+    let line_lookup = line_col::LineColLookup::new(input);
+    let errors = parsed
+        .errors()
+        .iter()
+        .map(|error| {
+            if error.range().start < start {
+                error.clone()
+            } else {
+                error.shifted(edit.delta(), &line_lookup)
+            }
+        })
+        .collect();
+    Some(Parsed::new(
+        node.replace_with(green),
+        input,
+        errors,
+        fixities.clone(),
+    ))
+}
+
+/// Lex the start of `input`, returning the first token if the tokens of the first `len` bytes
+/// end exactly at `len`, so that they can be lexed without the text which follows them
+fn relex(input: &str, len: usize) -> Option<TokenType> {
+    let mut lexer = Lexer::new(input);
+    let (first, _) = lexer.next()?;
+    loop {
+        let end = lexer.span().end;
+        if end >= len {
+            return (end == len).then_some(first);
+        }
+        lexer.next()?;
+    }
+}
+
+fn declares_fixity(node: &SyntaxNode) -> bool {
+    node.descendants_with_tokens().any(|element| {
+        matches!(
+            element.kind(),
+            SyntaxPart::Token(Token::KWInfixl | Token::KWInfixr)
+        )
+    })
+}
@@ -0,0 +1,330 @@
+use crate::{parse_expression, parse_module};
+use expect_test::{expect, Expect};
+
+fn check_expr(input: &str, expected_tree: Expect, expected_errors: Expect) {
+    let parse = parse_expression(input);
+    expected_tree.assert_eq(&parse.debug_tree());
+    expected_errors.assert_debug_eq(&errors(&parse));
+}
+
+fn check_module(input: &str, expected_tree: Expect, expected_errors: Expect) {
+    let parse = parse_module(input);
+    expected_tree.assert_eq(&parse.debug_tree());
+    expected_errors.assert_debug_eq(&errors(&parse));
+}
+
+fn errors(parse: &crate::Parsed) -> Vec<String> {
+    parse.errors().iter().map(ToString::to_string).collect()
+}
+
+#[test]
+fn valid_input_has_no_errors() {
+    let parse = parse_module("let a = 1");
+    assert!(!parse.has_errors());
+}
+
+#[test]
+fn missing_then() {
+    check_expr(
+        "if a 1 else 2",
+        expect![[r#"
+            Context(Expr)@0..13
+              Context(Conditional)@0..13
+                Token(KWIf)@0..2 "if"
+                Context(Condition)@2..7
+                  Context(Application)@2..6
+                    Token(Whitespace)@2..3 " "
+                    Token(IdLower)@3..4 "a"
+                    Token(Whitespace)@4..5 " "
+                    Token(Number)@5..6 "1"
+                  Token(Whitespace)@6..7 " "
+                Context(Then)@7..7
+                Token(KWElse)@7..11 "else"
+                Context(Else)@11..13
+                  Token(Whitespace)@11..12 " "
+                  Token(Number)@12..13 "2""#]],
+        expect![[r#"
+            [
+                "1:8: expected `then`, found \"else\"",
+            ]
+        "#]],
+    )
+}
+
+#[test]
+fn unclosed_array() {
+    check_expr(
+        "[1, 2",
+        expect![[r#"
+            Context(Expr)@0..5
+              Context(Array)@0..5
+                Token(SymLBracket)@0..1 "["
+                Context(Item)@1..2
+                  Token(Number)@1..2 "1"
+                Token(SymComma)@2..3 ","
+                Token(Whitespace)@3..4 " "
+                Context(Item)@4..5
+                  Token(Number)@4..5 "2""#]],
+        expect![[r#"
+            [
+                "1:6: expected `,` or `]`, found end of input",
+            ]
+        "#]],
+    )
+}
+
+#[test]
+fn junk_in_array() {
+    check_expr(
+        "[1, let, 3]",
+        expect![[r#"
+            Context(Expr)@0..11
+              Context(Array)@0..11
+                Token(SymLBracket)@0..1 "["
+                Context(Item)@1..2
+                  Token(Number)@1..2 "1"
+                Token(SymComma)@2..3 ","
+                Token(Whitespace)@3..4 " "
+                Context(Item)@4..7
+                  Context(Declaration)@4..7
+                    Token(KWLet)@4..7 "let"
+                    Context(Expr)@7..7
+                Token(SymComma)@7..8 ","
+                Token(Whitespace)@8..9 " "
+                Context(Item)@9..10
+                  Token(Number)@9..10 "3"
+                Token(SymRBracket)@10..11 "]""#]],
+        expect![[r#"
+            [
+                "1:8: expected a pattern, found \",\"",
+            ]
+        "#]],
+    )
+}
+
+#[test]
+fn multiple_spreads() {
+    check_module(
+        "let f = fun [..a, ..b] -> a",
+        expect![[r#"
+            Context(Module)@0..27
+              Context(Declarations)@0..27
+                Context(Declaration)@0..27
+                  Token(KWLet)@0..3 "let"
+                  Token(Whitespace)@3..4 " "
+                  Token(IdLower)@4..5 "f"
+                  Token(Whitespace)@5..6 " "
+                  Token(SymEquals)@6..7 "="
+                  Context(Expr)@7..27
+                    Token(Whitespace)@7..8 " "
+                    Context(Lambda)@8..27
+                      Token(KWFun)@8..11 "fun"
+                      Context(Params)@11..25
+                        Context(Pattern)@11..22
+                          Token(Whitespace)@11..12 " "
+                          Context(Array)@12..22
+                            Token(SymLBracket)@12..13 "["
+                            Context(Item)@13..16
+                              Context(Spread)@13..16
+                                Token(SymDoubleDot)@13..15 ".."
+                                Token(IdLower)@15..16 "a"
+                            Token(SymComma)@16..17 ","
+                            Token(Whitespace)@17..18 " "
+                            Context(Item)@18..18
+                            Error@18..21
+                              Token(SymDoubleDot)@18..20 ".."
+                              Token(IdLower)@20..21 "b"
+                            Token(SymRBracket)@21..22 "]"
+                        Token(Whitespace)@22..23 " "
+                        Token(SymArrow)@23..25 "->"
+                      Token(Whitespace)@25..26 " "
+                      Token(IdLower)@26..27 "a""#]],
+        expect![[r#"
+            [
+                "1:19: expected a pattern, found \"..\"",
+            ]
+        "#]],
+    )
+}
+
+#[test]
+fn unterminated_string() {
+    check_expr(
+        r#""abc"#,
+        expect![[r#"
+            Context(Expr)@0..4
+              Context(String)@0..4
+                Token(SymDoubleQuote)@0..1 "\""
+                StringToken(Text)@1..4 "abc""#]],
+        expect![[r#"
+            [
+                "1:5: expected `\"`, found end of input",
+            ]
+        "#]],
+    )
+}
+
+#[test]
+fn missing_binop_rhs() {
+    check_expr(
+        "1 +",
+        expect![[r#"
+            Context(Expr)@0..3
+              Context(BinOp)@0..3
+                Token(Number)@0..1 "1"
+                Token(Whitespace)@1..2 " "
+                Token(OpAdd)@2..3 "+""#]],
+        expect![[r#"
+            [
+                "1:4: expected an expression, found end of input",
+            ]
+        "#]],
+    )
+}
+
+#[test]
+fn junk_between_declarations() {
+    check_module(
+        "let a = 1\n) ) \nlet b = 2",
+        expect![[r#"
+            Context(Module)@0..24
+              Context(Declarations)@0..24
+                Context(Declaration)@0..10
+                  Token(KWLet)@0..3 "let"
+                  Token(Whitespace)@3..4 " "
+                  Token(IdLower)@4..5 "a"
+                  Token(Whitespace)@5..6 " "
+                  Token(SymEquals)@6..7 "="
+                  Context(Expr)@7..10
+                    Token(Whitespace)@7..8 " "
+                    Token(Number)@8..9 "1"
+                    Token(Whitespace)@9..10 "\n"
+                Error@10..15
+                  Token(SymRParen)@10..11 ")"
+                  Token(Whitespace)@11..12 " "
+                  Token(SymRParen)@12..13 ")"
+                  Token(Whitespace)@13..15 " \n"
+                Context(Declaration)@15..24
+                  Token(KWLet)@15..18 "let"
+                  Token(Whitespace)@18..19 " "
+                  Token(IdLower)@19..20 "b"
+                  Token(Whitespace)@20..21 " "
+                  Token(SymEquals)@21..22 "="
+                  Context(Expr)@22..24
+                    Token(Whitespace)@22..23 " "
+                    Token(Number)@23..24 "2""#]],
+        expect![[r#"
+            [
+                "2:1: expected a declaration, found \")\"",
+            ]
+        "#]],
+    )
+}
+
+#[test]
+fn missing_declaration_body() {
+    check_module(
+        "let a\nlet b = 2",
+        expect![[r#"
+            Context(Module)@0..15
+              Context(Declarations)@0..15
+                Context(Declaration)@0..6
+                  Token(KWLet)@0..3 "let"
+                  Token(Whitespace)@3..4 " "
+                  Token(IdLower)@4..5 "a"
+                  Token(Whitespace)@5..6 "\n"
+                Context(Declaration)@6..15
+                  Token(KWLet)@6..9 "let"
+                  Token(Whitespace)@9..10 " "
+                  Token(IdLower)@10..11 "b"
+                  Token(Whitespace)@11..12 " "
+                  Token(SymEquals)@12..13 "="
+                  Context(Expr)@13..15
+                    Token(Whitespace)@13..14 " "
+                    Token(Number)@14..15 "2""#]],
+        expect![[r#"
+            [
+                "2:1: expected `=`, found \"let\"",
+            ]
+        "#]],
+    )
+}
+
+#[test]
+fn precedence_out_of_range() {
+    check_module(
+        "infixl 10 <+> = add",
+        expect![[r#"
+            Context(Module)@0..19
+              Context(Declarations)@0..19
+                Context(Declaration)@0..19
+                  Token(KWInfixl)@0..6 "infixl"
+                  Token(Whitespace)@6..7 " "
+                  Token(Number)@7..9 "10"
+                  Token(Whitespace)@9..10 " "
+                  Token(OpCustom)@10..13 "<+>"
+                  Token(Whitespace)@13..14 " "
+                  Token(SymEquals)@14..15 "="
+                  Context(Expr)@15..19
+                    Token(Whitespace)@15..16 " "
+                    Token(IdLower)@16..19 "add""#]],
+        expect![[r#"
+            [
+                "1:8: expected a precedence from 0 to 9, found \"10\"",
+            ]
+        "#]],
+    )
+}
+
+#[test]
+fn unclosed_exposing() {
+    check_module(
+        "import \"./list\" exposing { map, filter\nlet x = 1",
+        expect![[r#"
+            Context(Module)@0..48
+              Context(Imports)@0..39
+                Context(Import)@0..39
+                  Token(KWImport)@0..6 "import"
+                  Token(Whitespace)@6..7 " "
+                  Context(String)@7..15
+                    Token(SymDoubleQuote)@7..8 "\""
+                    StringToken(Text)@8..14 "./list"
+                    StringToken(Delimiter)@14..15 "\""
+                  Token(Whitespace)@15..16 " "
+                  Token(KWExposing)@16..24 "exposing"
+                  Token(Whitespace)@24..25 " "
+                  Context(ExposingBlock)@25..39
+                    Token(SymLBrace)@25..26 "{"
+                    Token(Whitespace)@26..27 " "
+                    Token(IdLower)@27..30 "map"
+                    Token(SymComma)@30..31 ","
+                    Token(Whitespace)@31..32 " "
+                    Token(IdLower)@32..38 "filter"
+                    Token(Whitespace)@38..39 "\n"
+              Context(Declarations)@39..48
+                Context(Declaration)@39..48
+                  Token(KWLet)@39..42 "let"
+                  Token(Whitespace)@42..43 " "
+                  Token(IdLower)@43..44 "x"
+                  Token(Whitespace)@44..45 " "
+                  Token(SymEquals)@45..46 "="
+                  Context(Expr)@46..48
+                    Token(Whitespace)@46..47 " "
+                    Token(Number)@47..48 "1""#]],
+        expect![[r#"
+            [
+                "2:1: expected `}`, found \"let\"",
+            ]
+        "#]],
+    )
+}
+
+#[test]
+fn error_span() {
+    let parse = parse_module("let a = 1\nlet = 2");
+    let error = &parse.errors()[0];
+    assert_eq!(error.expected(), "a variable name");
+    assert_eq!(error.found(), Some("="));
+    assert_eq!(error.range(), 14..15);
+    assert_eq!(error.span(), ((2, 5), (2, 6)).into());
+}
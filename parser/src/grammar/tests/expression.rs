@@ -119,6 +119,57 @@ mod literal {
                     Token(SymRBrace)@14..15 "}""#]],
         )
     }
+    #[test]
+    fn parse_update() {
+        check(
+            "{ rec | foo, bar: 1 }",
+            expect![[r#"
+                Context(Expr)@0..21
+                  Context(Update)@0..21
+                    Token(SymLBrace)@0..1 "{"
+                    Token(Whitespace)@1..2 " "
+                    Token(IdLower)@2..5 "rec"
+                    Token(Whitespace)@5..6 " "
+                    Token(OpOr)@6..7 "|"
+                    Context(Field)@7..11
+                      Token(Whitespace)@7..8 " "
+                      Token(IdLower)@8..11 "foo"
+                    Token(SymComma)@11..12 ","
+                    Context(Field)@12..20
+                      Token(Whitespace)@12..13 " "
+                      Token(IdLower)@13..16 "bar"
+                      Token(SymColon)@16..17 ":"
+                      Token(Whitespace)@17..18 " "
+                      Token(Number)@18..19 "1"
+                      Token(Whitespace)@19..20 " "
+                    Token(SymRBrace)@20..21 "}""#]],
+        );
+        check(
+            "{ (f x) | foo: 1 }",
+            expect![[r#"
+                Context(Expr)@0..18
+                  Context(Update)@0..18
+                    Token(SymLBrace)@0..1 "{"
+                    Token(Whitespace)@1..2 " "
+                    Context(Parenthesised)@2..7
+                      Token(SymLParen)@2..3 "("
+                      Context(Application)@3..6
+                        Token(IdLower)@3..4 "f"
+                        Token(Whitespace)@4..5 " "
+                        Token(IdLower)@5..6 "x"
+                      Token(SymRParen)@6..7 ")"
+                    Token(Whitespace)@7..8 " "
+                    Token(OpOr)@8..9 "|"
+                    Context(Field)@9..17
+                      Token(Whitespace)@9..10 " "
+                      Token(IdLower)@10..13 "foo"
+                      Token(SymColon)@13..14 ":"
+                      Token(Whitespace)@14..15 " "
+                      Token(Number)@15..16 "1"
+                      Token(Whitespace)@16..17 " "
+                    Token(SymRBrace)@17..18 "}""#]],
+        )
+    }
 }
 
 mod operator {
@@ -333,6 +384,49 @@ fn parse_let() {
     )
 }
 
+#[test]
+fn parse_assert() {
+    check(
+        "assert x > 0; x",
+        expect![[r#"
+            Context(Expr)@0..15
+              Context(Assert)@0..15
+                Token(KWAssert)@0..6 "assert"
+                Context(Condition)@6..12
+                  Context(BinOp)@6..12
+                    Token(Whitespace)@6..7 " "
+                    Token(IdLower)@7..8 "x"
+                    Token(Whitespace)@8..9 " "
+                    Token(OpGt)@9..10 ">"
+                    Token(Whitespace)@10..11 " "
+                    Token(Number)@11..12 "0"
+                Token(OpSeq)@12..13 ";"
+                Token(Whitespace)@13..14 " "
+                Token(IdLower)@14..15 "x""#]],
+    )
+}
+
+#[test]
+fn parse_annotation() {
+    check(
+        "(f x : Number)",
+        expect![[r#"
+            Context(Expr)@0..14
+              Context(Annotated)@0..14
+                Token(SymLParen)@0..1 "("
+                Context(Application)@1..4
+                  Token(IdLower)@1..2 "f"
+                  Token(Whitespace)@2..3 " "
+                  Token(IdLower)@3..4 "x"
+                Token(Whitespace)@4..5 " "
+                Token(SymColon)@5..6 ":"
+                Context(Type)@6..13
+                  Token(Whitespace)@6..7 " "
+                  Token(IdUpper)@7..13 "Number"
+                Token(SymRParen)@13..14 ")""#]],
+    )
+}
+
 #[test]
 fn parse_access() {
     check(
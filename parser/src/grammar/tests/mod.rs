@@ -1,7 +1,9 @@
+mod errors;
 mod expression;
 mod module;
 mod pattern;
 mod ren_type;
+mod reparse;
 
 #[test]
 fn parse_nothing() {
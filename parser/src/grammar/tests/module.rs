@@ -72,56 +72,66 @@ mod imports {
         )
     }
 
-    // #[test]
-    // fn parse_exposing() {
-    //     check(
-    //         r#"import "path" exposing {foo}"#,
-    //         expect![[r#"
-    //             Context(Module)@0..28
-    //               Context(Imports)@0..28
-    //                 Context(Import)@0..28
-    //                   Token(KWImport)@0..6 "import"
-    //                   Token(Whitespace)@6..7 " "
-    //                   Context(String)@7..13
-    //                     Token(SymDoubleQuote)@7..8 "\""
-    //                     StringToken(Text)@8..12 "path"
-    //                     StringToken(Delimiter)@12..13 "\""
-    //                   Token(Whitespace)@13..14 " "
-    //                   Token(KWExposing)@14..22 "exposing"
-    //                   Token(Whitespace)@22..23 " "
-    //                   Token(SymLBrace)@23..24 "{"
-    //                   Context(ExposingBlock)@24..28
-    //                     Token(IdLower)@24..27 "foo"
-    //                     Token(SymRBrace)@27..28 "}""#]],
-    //     )
-    // }
+    #[test]
+    fn parse_exposing() {
+        check(
+            r#"import "path" exposing {foo}"#,
+            expect![[r#"
+                Context(Module)@0..28
+                  Context(Imports)@0..28
+                    Context(Import)@0..28
+                      Token(KWImport)@0..6 "import"
+                      Token(Whitespace)@6..7 " "
+                      Context(String)@7..13
+                        Token(SymDoubleQuote)@7..8 "\""
+                        StringToken(Text)@8..12 "path"
+                        StringToken(Delimiter)@12..13 "\""
+                      Token(Whitespace)@13..14 " "
+                      Token(KWExposing)@14..22 "exposing"
+                      Token(Whitespace)@22..23 " "
+                      Context(ExposingBlock)@23..28
+                        Token(SymLBrace)@23..24 "{"
+                        Token(IdLower)@24..27 "foo"
+                        Token(SymRBrace)@27..28 "}""#]],
+        )
+    }
 
-    // #[test]
-    // fn parse_exposing_multi() {
-    //     check(
-    //         r#"import "path" exposing {foo, bar}"#,
-    //         expect![[r#"
-    //             Context(Module)@0..33
-    //               Context(Imports)@0..33
-    //                 Context(Import)@0..33
-    //                   Token(KWImport)@0..6 "import"
-    //                   Token(Whitespace)@6..7 " "
-    //                   Context(String)@7..13
-    //                     Token(SymDoubleQuote)@7..8 "\""
-    //                     StringToken(Text)@8..12 "path"
-    //                     StringToken(Delimiter)@12..13 "\""
-    //                   Token(Whitespace)@13..14 " "
-    //                   Token(KWExposing)@14..22 "exposing"
-    //                   Token(Whitespace)@22..23 " "
-    //                   Token(SymLBrace)@23..24 "{"
-    //                   Context(ExposingBlock)@24..33
-    //                     Token(IdLower)@24..27 "foo"
-    //                     Token(Comma)@27..28 ","
-    //                     Token(Whitespace)@28..29 " "
-    //                     Token(IdLower)@29..32 "bar"
-    //                     Token(SymRBrace)@32..33 "}""#]],
-    //     )
-    // }
+    #[test]
+    fn parse_exposing_multi() {
+        check(
+            r#"import "path" as Name exposing { foo, bar, <+> }"#,
+            expect![[r#"
+                Context(Module)@0..48
+                  Context(Imports)@0..48
+                    Context(Import)@0..48
+                      Token(KWImport)@0..6 "import"
+                      Token(Whitespace)@6..7 " "
+                      Context(String)@7..13
+                        Token(SymDoubleQuote)@7..8 "\""
+                        StringToken(Text)@8..12 "path"
+                        StringToken(Delimiter)@12..13 "\""
+                      Token(Whitespace)@13..14 " "
+                      Token(KWAs)@14..16 "as"
+                      Token(Whitespace)@16..17 " "
+                      Context(IdUpper)@17..22
+                        Token(IdUpper)@17..21 "Name"
+                        Token(Whitespace)@21..22 " "
+                      Token(KWExposing)@22..30 "exposing"
+                      Token(Whitespace)@30..31 " "
+                      Context(ExposingBlock)@31..48
+                        Token(SymLBrace)@31..32 "{"
+                        Token(Whitespace)@32..33 " "
+                        Token(IdLower)@33..36 "foo"
+                        Token(SymComma)@36..37 ","
+                        Token(Whitespace)@37..38 " "
+                        Token(IdLower)@38..41 "bar"
+                        Token(SymComma)@41..42 ","
+                        Token(Whitespace)@42..43 " "
+                        Token(OpCustom)@43..46 "<+>"
+                        Token(Whitespace)@46..47 " "
+                        Token(SymRBrace)@47..48 "}""#]],
+        )
+    }
 
     #[test]
     fn parse_full() {
@@ -267,4 +277,136 @@ mod declaration {
                         Token(IdLower)@14..17 "foo""#]],
         );
     }
+
+    #[test]
+    fn typ() {
+        check(
+            "type Id a = a -> a",
+            expect![[r#"
+                Context(Module)@0..18
+                  Context(Declarations)@0..18
+                    Context(Declaration)@0..18
+                      Token(KWType)@0..4 "type"
+                      Token(Whitespace)@4..5 " "
+                      Token(IdUpper)@5..7 "Id"
+                      Token(Whitespace)@7..8 " "
+                      Token(IdLower)@8..9 "a"
+                      Token(Whitespace)@9..10 " "
+                      Token(SymEquals)@10..11 "="
+                      Context(Type)@11..18
+                        Token(Whitespace)@11..12 " "
+                        Context(FunType)@12..18
+                          Token(IdLower)@12..13 "a"
+                          Token(Whitespace)@13..14 " "
+                          Token(SymArrow)@14..16 "->"
+                          Token(Whitespace)@16..17 " "
+                          Token(IdLower)@17..18 "a""#]],
+        );
+    }
+
+    #[test]
+    fn mixed() {
+        check(
+            "pub type Point = { x: Number }\nlet origin = 0\ntype Name = String\next log = \"log\"\n",
+            expect![[r#"
+                Context(Module)@0..81
+                  Context(Declarations)@0..81
+                    Context(Declaration)@0..31
+                      Token(KWPub)@0..3 "pub"
+                      Token(Whitespace)@3..4 " "
+                      Token(KWType)@4..8 "type"
+                      Token(Whitespace)@8..9 " "
+                      Token(IdUpper)@9..14 "Point"
+                      Token(Whitespace)@14..15 " "
+                      Token(SymEquals)@15..16 "="
+                      Context(Type)@16..31
+                        Token(Whitespace)@16..17 " "
+                        Context(Record)@17..30
+                          Token(SymLBrace)@17..18 "{"
+                          Token(Whitespace)@18..19 " "
+                          Context(Field)@19..29
+                            Token(IdLower)@19..20 "x"
+                            Token(SymColon)@20..21 ":"
+                            Token(Whitespace)@21..22 " "
+                            Token(IdUpper)@22..28 "Number"
+                            Token(Whitespace)@28..29 " "
+                          Token(SymRBrace)@29..30 "}"
+                        Token(Whitespace)@30..31 "\n"
+                    Context(Declaration)@31..46
+                      Token(KWLet)@31..34 "let"
+                      Token(Whitespace)@34..35 " "
+                      Token(IdLower)@35..41 "origin"
+                      Token(Whitespace)@41..42 " "
+                      Token(SymEquals)@42..43 "="
+                      Context(Expr)@43..46
+                        Token(Whitespace)@43..44 " "
+                        Token(Number)@44..45 "0"
+                        Token(Whitespace)@45..46 "\n"
+                    Context(Declaration)@46..65
+                      Token(KWType)@46..50 "type"
+                      Token(Whitespace)@50..51 " "
+                      Token(IdUpper)@51..55 "Name"
+                      Token(Whitespace)@55..56 " "
+                      Token(SymEquals)@56..57 "="
+                      Context(Type)@57..65
+                        Token(Whitespace)@57..58 " "
+                        Token(IdUpper)@58..64 "String"
+                        Token(Whitespace)@64..65 "\n"
+                    Context(Declaration)@65..80
+                      Token(KWExt)@65..68 "ext"
+                      Token(Whitespace)@68..69 " "
+                      Token(IdLower)@69..72 "log"
+                      Token(Whitespace)@72..73 " "
+                      Token(SymEquals)@73..74 "="
+                      Token(Whitespace)@74..75 " "
+                      Context(String)@75..80
+                        Token(SymDoubleQuote)@75..76 "\""
+                        StringToken(Text)@76..79 "log"
+                        StringToken(Delimiter)@79..80 "\""
+                    Token(Whitespace)@80..81 "\n""#]],
+        );
+    }
+
+    #[test]
+    fn expectation() {
+        check(
+            "expect 1 == 1",
+            expect![[r#"
+                Context(Module)@0..13
+                  Context(Declarations)@0..13
+                    Context(Declaration)@0..13
+                      Token(KWExpect)@0..6 "expect"
+                      Context(Expr)@6..13
+                        Context(BinOp)@6..13
+                          Token(Whitespace)@6..7 " "
+                          Token(Number)@7..8 "1"
+                          Token(Whitespace)@8..9 " "
+                          Token(OpEq)@9..11 "=="
+                          Token(Whitespace)@11..12 " "
+                          Token(Number)@12..13 "1""#]],
+        );
+    }
+
+    #[test]
+    fn infix() {
+        check(
+            "pub infixr 5 <+> = add",
+            expect![[r#"
+                Context(Module)@0..22
+                  Context(Declarations)@0..22
+                    Context(Declaration)@0..22
+                      Token(KWPub)@0..3 "pub"
+                      Token(Whitespace)@3..4 " "
+                      Token(KWInfixr)@4..10 "infixr"
+                      Token(Whitespace)@10..11 " "
+                      Token(Number)@11..12 "5"
+                      Token(Whitespace)@12..13 " "
+                      Token(OpCustom)@13..16 "<+>"
+                      Token(Whitespace)@16..17 " "
+                      Token(SymEquals)@17..18 "="
+                      Context(Expr)@18..22
+                        Token(Whitespace)@18..19 " "
+                        Token(IdLower)@19..22 "add""#]],
+        );
+    }
 }
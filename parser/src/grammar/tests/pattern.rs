@@ -162,4 +162,40 @@ mod literal {
                     Token(SymRBrace)@14..15 "}""#]],
         )
     }
+
+    #[test]
+    fn parse_spread() {
+        check(
+            "[x, ..rest]",
+            expect![[r#"
+                Context(Pattern)@0..11
+                  Context(Array)@0..11
+                    Token(SymLBracket)@0..1 "["
+                    Context(Item)@1..2
+                      Token(IdLower)@1..2 "x"
+                    Token(SymComma)@2..3 ","
+                    Token(Whitespace)@3..4 " "
+                    Context(Item)@4..10
+                      Context(Spread)@4..10
+                        Token(SymDoubleDot)@4..6 ".."
+                        Token(IdLower)@6..10 "rest"
+                    Token(SymRBracket)@10..11 "]""#]],
+        );
+        check(
+            "{name, ..others}",
+            expect![[r#"
+                Context(Pattern)@0..16
+                  Context(Record)@0..16
+                    Token(SymLBrace)@0..1 "{"
+                    Context(Field)@1..5
+                      Token(IdLower)@1..5 "name"
+                    Token(SymComma)@5..6 ","
+                    Context(Field)@6..15
+                      Token(Whitespace)@6..7 " "
+                      Context(Spread)@7..15
+                        Token(SymDoubleDot)@7..9 ".."
+                        Token(IdLower)@9..15 "others"
+                    Token(SymRBrace)@15..16 "}""#]],
+        );
+    }
 }
@@ -16,3 +16,47 @@ fn parse_any() {
               Token(OpMul)@0..1 "*""#]],
     );
 }
+
+#[test]
+fn parse_forall() {
+    check(
+        "forall a b. a -> b",
+        expect![[r#"
+        Context(Type)@0..18
+          Context(Forall)@0..18
+            Token(KWForall)@0..6 "forall"
+            Token(Whitespace)@6..7 " "
+            Token(IdLower)@7..8 "a"
+            Token(Whitespace)@8..9 " "
+            Token(IdLower)@9..10 "b"
+            Token(SymDot)@10..11 "."
+            Context(FunType)@11..18
+              Token(Whitespace)@11..12 " "
+              Token(IdLower)@12..13 "a"
+              Token(Whitespace)@13..14 " "
+              Token(SymArrow)@14..16 "->"
+              Token(Whitespace)@16..17 " "
+              Token(IdLower)@17..18 "b""#]],
+    );
+}
+
+#[test]
+fn parse_application_in_function() {
+    check(
+        "Array a -> Maybe a",
+        expect![[r#"
+        Context(Type)@0..18
+          Context(FunType)@0..18
+            Context(Application)@0..7
+              Token(IdUpper)@0..5 "Array"
+              Token(Whitespace)@5..6 " "
+              Token(IdLower)@6..7 "a"
+            Token(Whitespace)@7..8 " "
+            Token(SymArrow)@8..10 "->"
+            Context(Application)@10..18
+              Token(Whitespace)@10..11 " "
+              Token(IdUpper)@11..16 "Maybe"
+              Token(Whitespace)@16..17 " "
+              Token(IdLower)@17..18 "a""#]],
+    );
+}
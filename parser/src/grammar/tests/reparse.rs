@@ -0,0 +1,154 @@
+use crate::{
+    grammar::reparse::reparse_block, parse_module, parse_module_with, reparse_module_with,
+    Fixities, TextEdit,
+};
+
+const SAMPLE: &str = r#"import "./list" as List exposing { map, <+> }
+import pkg "ren/array" as Array
+
+let a = 1
+
+pub let f = fun { x, y } -> { x: x + 1, y }
+
+let g : { a: Number } -> Number = fun r -> r.a
+
+let h = fun r -> { r | a: List.map (fun x -> x) [1, 2] }
+
+ext log : String -> Number = "log"
+
+expect (f { x: 1, y: 2 }) == { x: 2, y: 2 }
+"#;
+
+/// An edit replacing the first occurrence of `old` in `input` with `new`
+fn replace(input: &str, old: &str, new: &str) -> TextEdit {
+    let start = input
+        .find(old)
+        .expect("text to replace should be in the input");
+    TextEdit::new(start..start + old.len(), new)
+}
+
+/// Reparse `input` after an edit, checking the result is identical to parsing the edited
+/// input from scratch. Returns whether the edit could be reparsed incrementally, as otherwise
+/// the edited input is parsed from scratch anyway.
+fn check(input: &str, edit: &TextEdit) -> bool {
+    let parsed = parse_module(input);
+    let edited = edit.apply(input);
+    let Some(reparsed) = reparse_block(&parsed, edit, &edited) else {
+        return false;
+    };
+    let expected = parse_module(&edited);
+    assert_eq!(
+        reparsed.debug_tree(),
+        expected.debug_tree(),
+        "{:?} in {:?}",
+        edit,
+        input
+    );
+    assert_eq!(
+        reparsed.errors(),
+        expected.errors(),
+        "{:?} in {:?}",
+        edit,
+        input
+    );
+    true
+}
+
+#[test]
+fn reparses_blocks() {
+    assert!(!parse_module(SAMPLE).has_errors());
+    for (old, new) in [
+        // Declarations and imports
+        ("let a = 1", "let a = 42"),
+        ("\"ren/array\" as Array", "\"ren/list\" as List.Data"),
+        (
+            "ext log : String -> Number = \"log\"",
+            "ext log : Number = \"now\"",
+        ),
+        // Records as expressions, patterns and types
+        ("{ x: x + 1, y }", "{ x: x * 2, y: y }"),
+        ("{ x, y }", "{ x, ..rest }"),
+        ("{ a: Number }", "{ a: Number, b: String }"),
+        ("{ r | a: List.map (fun x -> x) [1, 2] }", "{ r | a: [] }"),
+        // Exposed names
+        ("{ map, <+> }", "{ map, filter, <+> }"),
+    ] {
+        assert!(check(SAMPLE, &replace(SAMPLE, old, new)), "{}", new);
+    }
+}
+
+#[test]
+fn reparses_the_whole_module_when_blocks_change() {
+    for (old, new) in [
+        // Splitting a declaration in two
+        ("let a = 1", "let a = 1\nlet b = 2"),
+        // Joining a declaration onto the next one
+        ("1\n\npub let", "1\n\nlet"),
+        // Joining the last token of a declaration onto the first of the next one
+        ("r.a\n\n", "r.a"),
+        // Declaring the fixity of an operator
+        ("let a = 1", "infixl 5 <+> = fun a b -> a"),
+        // Introducing a syntax error
+        ("{ x: x + 1, y }", "{ x: x + 1, }"),
+    ] {
+        assert!(!check(SAMPLE, &replace(SAMPLE, old, new)), "{}", new);
+    }
+}
+
+#[test]
+fn keeps_errors_elsewhere() {
+    let input = "let a = 1\n\nlet b = (2\n\nlet c = }\n";
+    let parsed = parse_module(input);
+    assert!(parsed.has_errors());
+    // The errors after the edit move with the text they are in
+    assert!(check(input, &replace(input, "1", "100")));
+    assert!(!check(input, &replace(input, "(2", "2")));
+}
+
+#[test]
+fn depends_on_whitespace_before_blocks() {
+    // Whether `(b)` is an argument depends on whitespace before the declaration it is in
+    let input = "ext a : Number = \"a\"\nexpect(b)(c)\n";
+    assert!(!check(input, &replace(input, "(b)", "(bb)")));
+    // The `ext` declaration leaves that whitespace for the next declaration, but `let` doesn't
+    assert!(!check(
+        input,
+        &replace(input, "ext a : Number = \"a\"", "let a = 1")
+    ));
+    assert!(check(input, &replace(input, "\"a\"", "\"abc\"")));
+}
+
+#[test]
+fn uses_imported_fixities() {
+    let mut imported = Fixities::new();
+    imported.insert(
+        "<+>",
+        higher_ast::Fixity::new(higher_ast::Associativity::Right, 5),
+    );
+    let input = "let a = 1 <+> 2 <+> 3\n\nlet b = 1\n";
+    let parsed = parse_module_with(input, &imported);
+    let edit = replace(input, "3", "4");
+    let edited = edit.apply(input);
+    assert!(reparse_block(&parsed, &edit, &edited).is_some());
+    assert_eq!(
+        reparse_module_with(&parsed, &edit, &edited, &imported).debug_tree(),
+        parse_module_with(&edited, &imported).debug_tree()
+    );
+    // Edits which can't be reparsed incrementally fall back to parsing the whole module
+    let edit = replace(input, "\n\nlet b", "let b");
+    let edited = edit.apply(input);
+    assert_eq!(
+        reparse_module_with(&parsed, &edit, &edited, &imported).debug_tree(),
+        parse_module_with(&edited, &imported).debug_tree()
+    );
+}
+
+#[test]
+fn matches_full_parse_after_any_small_edit() {
+    for (offset, _) in SAMPLE.char_indices() {
+        check(SAMPLE, &TextEdit::new(offset..offset + 1, ""));
+        for text in [" ", "x", "{", "}", "\"", "let "] {
+            check(SAMPLE, &TextEdit::new(offset..offset, text));
+        }
+    }
+}
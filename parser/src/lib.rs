@@ -1,20 +1,40 @@
+pub mod fixity;
 mod grammar;
 mod parser;
 mod syntax;
 
-pub use self::parser::Parsed;
 pub(crate) use self::parser::Parser;
-pub use grammar::{parse_expression, parse_module, parse_repl_stmt};
+pub use self::parser::{ParseError, Parsed};
+pub use grammar::{
+    parse_expression, parse_module, parse_module_with, parse_repl_stmt, reparse_module,
+    reparse_module_with, TextEdit,
+};
+pub use higher_ast::Fixities;
+pub mod format;
 pub mod lower_ast;
 
-pub fn parse_expr_ast(input: &str) -> Result<lower_ast::Expr, ()> {
+pub fn parse_expr_ast(input: &str) -> Result<lower_ast::Expr, Vec<ParseError>> {
     let parsed = parse_expression(input);
-    lower_ast::expr_ast(parsed.syntax()).ok_or(())
+    if parsed.has_errors() {
+        return Err(parsed.errors().to_vec());
+    }
+    Ok(lower_ast::expr_ast(parsed.syntax()).expect("error-free parse should lower to Expr"))
+}
+
+pub fn parse_module_ast(input: &str) -> Result<lower_ast::Module, Vec<ParseError>> {
+    parse_module_ast_with(input, &Fixities::new())
 }
 
-pub fn parse_module_ast(input: &str) -> Result<lower_ast::Module, ()> {
-    let parsed = parse_module(input);
-    lower_ast::module_ast(parsed.syntax()).ok_or(())
+/// Parse a module which imports user-defined operators with the given fixities
+pub fn parse_module_ast_with(
+    input: &str,
+    imported: &Fixities,
+) -> Result<lower_ast::Module, Vec<ParseError>> {
+    let parsed = parse_module_with(input, imported);
+    if parsed.has_errors() {
+        return Err(parsed.errors().to_vec());
+    }
+    Ok(lower_ast::module_ast(parsed.syntax()).expect("error-free parse should lower to Module"))
 }
 
 #[derive(Debug)]
@@ -25,20 +45,6 @@ pub enum REPLStmt<D, E, I> {
     Comment(String),
     Empty,
 }
-impl<T> REPLStmt<T, T, T> {
-    fn map_all<F, U>(self, f: F) -> REPLStmt<U, U, U>
-    where
-        F: Fn(T) -> U,
-    {
-        match self {
-            REPLStmt::Decl(d) => REPLStmt::Decl(f(d)),
-            REPLStmt::Expr(e) => REPLStmt::Expr(f(e)),
-            REPLStmt::Import(i) => REPLStmt::Import(f(i)),
-            REPLStmt::Comment(c) => REPLStmt::Comment(c),
-            REPLStmt::Empty => REPLStmt::Empty,
-        }
-    }
-}
 impl<D, E, I> REPLStmt<D, E, I> {
     pub fn map_each<DF, DR, EF, ER, IF, IR>(self, d_f: DF, e_f: EF, i_f: IF) -> REPLStmt<DR, ER, IR>
     where
@@ -75,6 +81,36 @@ impl<D, E, I> REPLStmt<D, E, I> {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StmtError {
+    /// The statement contained syntax errors
+    Syntax(Vec<ParseError>),
+    Other(&'static str),
+}
+impl From<&'static str> for StmtError {
+    fn from(e: &'static str) -> Self {
+        Self::Other(e)
+    }
+}
+impl core::fmt::Display for StmtError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StmtError::Syntax(errors) => {
+                let mut iter = errors.iter();
+                if let Some(e) = iter.next() {
+                    write!(f, "{}", e)?;
+                }
+                for e in iter {
+                    write!(f, "\n{}", e)?;
+                }
+                Ok(())
+            }
+            StmtError::Other(s) => s.fmt(f),
+        }
+    }
+}
+impl std::error::Error for StmtError {}
+
 /// Parse a single REPL statement (import, declaration or expression)
 pub fn parse_stmt_ast<'source>(
     input: &'source str,
@@ -83,15 +119,24 @@ pub fn parse_stmt_ast<'source>(
         REPLStmt<lower_ast::Decl, lower_ast::Expr, lower_ast::Import>,
         ::line_col::LineColLookup<'source>,
     ),
-    &'static str,
+    StmtError,
 > {
     let line_lookup = ::line_col::LineColLookup::new(input);
     parse_repl_stmt(input)?
-        .map_all(|parsed| parsed.syntax())
+        .map_ok(check_errors, check_errors, check_errors)?
         .map_ok(
             |syntax| lower_ast::decl_ast(syntax).ok_or("error convertirng parsed to Decl"),
             |syntax| lower_ast::expr_ast(syntax).ok_or("error convertirng parsed to Expr"),
             |syntax| lower_ast::import_ast(syntax).ok_or("error convertirng parsed to Import"),
         )
         .map(|stmt| (stmt, line_lookup))
+        .map_err(StmtError::from)
+}
+
+fn check_errors(parsed: Parsed) -> Result<syntax::SyntaxNode, StmtError> {
+    if parsed.has_errors() {
+        Err(StmtError::Syntax(parsed.errors().to_vec()))
+    } else {
+        Ok(parsed.syntax())
+    }
 }
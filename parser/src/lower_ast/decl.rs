@@ -1,3 +1,4 @@
+use higher_ast::{Associativity, Fixity};
 use smol_str::SmolStr;
 
 use super::{
@@ -36,6 +37,21 @@ impl Decl {
     pub fn is_type(&self) -> bool {
         self.0.find_token(Token::KWType).is_some()
     }
+    pub fn is_expect(&self) -> bool {
+        self.0.find_token(Token::KWExpect).is_some()
+    }
+    /// The fixity given by an `infixl` or `infixr` declaration
+    fn fixity(&self) -> Option<Fixity> {
+        let associativity = if self.0.find_token(Token::KWInfixl).is_some() {
+            Associativity::Left
+        } else if self.0.find_token(Token::KWInfixr).is_some() {
+            Associativity::Right
+        } else {
+            return None;
+        };
+        let precedence = self.0.find_token(Token::Number)?.text().parse().ok()?;
+        Some(Fixity::new(associativity, precedence))
+    }
     fn name(&self) -> Option<SmolStr> {
         self.0
             .find_token(Token::IdLower)
@@ -55,6 +71,14 @@ impl Decl {
                 .and_then(super::simple_str)
         }
     }
+    /// The type variables declared by a type declaration, e.g. `a` in `type Maybe a = ...`
+    fn type_vars(&self) -> impl Iterator<Item = SmolStr> + '_ {
+        self.0
+            .children_with_tokens()
+            .filter_map(|e| e.into_token())
+            .filter(|tok| tok.kind() == Token::IdLower.into())
+            .map(|tok| SmolStr::new(tok.text()))
+    }
     fn type_annotation(&self) -> Option<Type> {
         self.0
             .find_node(Context::Type)
@@ -66,7 +90,7 @@ impl ToHIR for Decl {
     type ValidationError = ();
     fn to_higher_ast(&self, line_lookup: &line_col::LineColLookup) -> Self::HIRType {
         if self.is_type() {
-            higher_ast::Decl::typ(
+            higher_ast::Decl::generic_typ(
                 self.type_annotation()
                     .map(|t| t.to_higher_ast(line_lookup))
                     .unwrap_or_default(),
@@ -76,6 +100,20 @@ impl ToHIR for Decl {
                     .find_token(Token::IdUpper)
                     .map(|tok| SmolStr::new(tok.text()))
                     .unwrap(),
+                self.type_vars(),
+            )
+        } else if self.is_expect() {
+            higher_ast::Decl::expect(
+                RangeLookup(line_lookup, self.0.text_range()),
+                self.expr().unwrap().to_higher_ast(line_lookup),
+            )
+        } else if let Some(fixity) = self.fixity() {
+            higher_ast::Decl::infix(
+                RangeLookup(line_lookup, self.0.text_range()),
+                self.is_public(),
+                fixity,
+                self.0.find_token(Token::OpCustom).unwrap().text(),
+                self.expr().unwrap().to_higher_ast(line_lookup),
             )
         } else if self.is_local() {
             higher_ast::Decl::local(
@@ -8,17 +8,22 @@ use super::{
     literal,
     macro_impl::create_ast_enum,
     pattern::Pattern,
+    ren_type::Type,
     FromSyntaxElement, RangeLookup, SyntaxNode, SyntaxToken, ToHIR,
 };
 
 type HigherExpr = higher_ast::Expr;
 
+/// Give a lowered expression the span of its syntax, then turn any placeholders directly
+/// inside it into the parameters of a lambda. Children are lowered first, so placeholders
+/// belong to the innermost expression containing them.
 fn make_spanned(
     expr: HigherExpr,
     text_range: ::rowan::TextRange,
     line_lookup: &::line_col::LineColLookup,
 ) -> HigherExpr {
     expr.with_span(RangeLookup(line_lookup, text_range))
+        .replace_placeholders()
 }
 
 create_ast_enum! {
@@ -34,6 +39,8 @@ create_ast_enum! {
 
         // Other contexts
         Context::Access => EAccess(struct AccessExpr),
+        Context::Annotated => EAnnotated(struct AnnotatedExpr),
+        Context::Assert => EAssert(struct AssertExpr),
         Context::Declaration => EBinding(struct BindingExpr),
         Context::BinOp => EBinOp(struct BinOpExpr),
         Context::Application => ECall(struct CallExpr),
@@ -41,6 +48,7 @@ create_ast_enum! {
         Context::Lambda => ELambda(struct LambdaExpr),
         Context::PrefixOp => EPrefixOp(struct PrefixOpExpr),
         Context::Switch => EWhere(struct WhereExpr),
+        Context::Update => EUpdate(struct UpdateExpr),
 
         // Literal tokens
         Token::Number => LNum(literal::LNumber<Self>),
@@ -151,6 +159,38 @@ impl ToHIR for AccessExpr {
     }
 }
 
+impl AnnotatedExpr {
+    pub fn expr(&self) -> Option<Expr> {
+        self.0
+            .children_with_tokens()
+            .skip_trivia()
+            .next()
+            .and_then(Expr::from_element)
+    }
+    pub fn annotation(&self) -> Option<Type> {
+        self.0
+            .find_node(Context::Type)
+            .and_then(Type::from_root_node)
+    }
+}
+impl ToHIR for AnnotatedExpr {
+    type HIRType = HigherExpr;
+    type ValidationError = ();
+
+    fn to_higher_ast(&self, line_lookup: &line_col::LineColLookup) -> Self::HIRType {
+        HigherExpr::annotated(
+            self.expr().to_higher_ast(line_lookup).unwrap(),
+            self.annotation()
+                .map(|t| t.to_higher_ast(line_lookup))
+                .unwrap_or_default(),
+        )
+    }
+
+    fn validate(&self) -> Option<Self::ValidationError> {
+        todo!()
+    }
+}
+
 impl BindingExpr {
     pub fn pattern(&self) -> Option<Pattern> {
         self.0
@@ -165,6 +205,7 @@ impl BindingExpr {
     pub fn result_expr(&self) -> Option<Expr> {
         self.0
             .children_with_tokens()
+            .skip_trivia()
             .last()
             .and_then(Expr::from_element)
     }
@@ -187,12 +228,17 @@ impl ToHIR for BindingExpr {
 }
 
 impl BinOpExpr {
-    pub fn op(&self) -> Option<Operator> {
+    /// The operator token, which may be a user-defined operator
+    fn op_token(&self) -> Option<SyntaxToken> {
         self.0
             .children_with_tokens()
             .skip_trivia()
             .nth(1)
-            .and_then(|e| Operator::from_symbol(e.into_token().unwrap().text()))
+            .and_then(|e| e.into_token())
+    }
+    pub fn op(&self) -> Option<Operator> {
+        self.op_token()
+            .and_then(|tok| Operator::from_symbol(tok.text()))
     }
     pub fn lhs(&self) -> Option<Expr> {
         self.0
@@ -214,11 +260,18 @@ impl ToHIR for BinOpExpr {
     type ValidationError = ();
 
     fn to_higher_ast(&self, line_lookup: &line_col::LineColLookup) -> Self::HIRType {
-        HigherExpr::binop(
-            self.lhs().to_higher_ast(line_lookup).unwrap(),
-            self.op().unwrap(),
-            self.rhs().to_higher_ast(line_lookup).unwrap(),
-        )
+        let lhs = self.lhs().to_higher_ast(line_lookup).unwrap();
+        let rhs = self.rhs().to_higher_ast(line_lookup).unwrap();
+        match self.op() {
+            Some(op) => HigherExpr::binop(lhs, op, rhs),
+            // User-defined operators are calls to the value they stand for
+            None => {
+                let op = self.op_token().unwrap();
+                let func =
+                    HigherExpr::var(op.text()).with_span(RangeLookup(line_lookup, op.text_range()));
+                HigherExpr::apply_many(func, [lhs, rhs])
+            }
+        }
     }
 
     fn validate(&self) -> Option<Self::ValidationError> {
@@ -301,6 +354,85 @@ impl ToHIR for ConditionalExpr {
     }
 }
 
+impl AssertExpr {
+    pub fn condition(&self) -> Option<Expr> {
+        self.0
+            .find_node(Context::Condition)
+            .and_then(Expr::from_root_node)
+    }
+    pub fn body(&self) -> Option<Expr> {
+        self.0
+            .children_with_tokens()
+            .skip_trivia()
+            .last()
+            .and_then(Expr::from_element)
+    }
+}
+impl ToHIR for AssertExpr {
+    type HIRType = HigherExpr;
+    type ValidationError = ();
+
+    fn to_higher_ast(&self, line_lookup: &line_col::LineColLookup) -> Self::HIRType {
+        HigherExpr::assert(
+            self.condition().to_higher_ast(line_lookup).unwrap(),
+            self.body().to_higher_ast(line_lookup).unwrap(),
+        )
+    }
+
+    fn validate(&self) -> Option<Self::ValidationError> {
+        todo!()
+    }
+}
+
+impl UpdateExpr {
+    /// The record being updated, which follows the opening `{`
+    pub fn target(&self) -> Option<Expr> {
+        self.0
+            .children_with_tokens()
+            .skip_trivia()
+            .nth(1)
+            .and_then(Expr::from_element)
+    }
+    /// The range, name and new value of each replaced field
+    pub fn fields(&self) -> Vec<(::rowan::TextRange, SmolStr, Option<Expr>)> {
+        self.0
+            .children()
+            .filter(|node| node.kind() == Context::Field.into())
+            .filter_map(|field_node| {
+                let mut iter = field_node.children_with_tokens().skip_trivia();
+                let name = iter.find(|n| n.kind() == Token::IdLower.into())?;
+                let value = iter.last();
+                let end = value.as_ref().unwrap_or(&name).text_range().end();
+                Some((
+                    ::rowan::TextRange::new(name.text_range().start(), end),
+                    SmolStr::new(name.into_token()?.text()),
+                    value.and_then(Expr::from_element),
+                ))
+            })
+            .collect()
+    }
+}
+impl ToHIR for UpdateExpr {
+    type HIRType = HigherExpr;
+    type ValidationError = ();
+
+    fn to_higher_ast(&self, line_lookup: &line_col::LineColLookup) -> Self::HIRType {
+        HigherExpr::update(
+            self.target().to_higher_ast(line_lookup).unwrap(),
+            self.fields().into_iter().map(|(range, name, value)| {
+                let value = value
+                    .map(|v| v.to_higher_ast(line_lookup))
+                    .unwrap_or_else(|| HigherExpr::var(name.to_string()));
+                (RangeLookup(line_lookup, range), name, value)
+            }),
+        )
+    }
+
+    fn validate(&self) -> Option<Self::ValidationError> {
+        todo!()
+    }
+}
+
 impl LambdaExpr {
     pub fn params(&self) -> Vec<Pattern> {
         self.0
@@ -412,10 +544,9 @@ impl WhereExpr {
                                 }
                                 _ => panic!("Unexpected node in branch"),
                             });
-                    if pat.is_some() && expr.is_some() {
-                        Some((pat.unwrap(), guard, expr.unwrap()))
-                    } else {
-                        None
+                    match (pat, expr) {
+                        (Some(pat), Some(expr)) => Some((pat, guard, expr)),
+                        _ => None,
                     }
                 } else {
                     None
@@ -4,10 +4,12 @@ use crate::syntax::RenLang;
 
 use super::{FromSyntaxElement, SyntaxElement, SyntaxNode, SyntaxToken};
 
+#[allow(clippy::type_complexity)]
 pub(super) trait SyntaxNodeExtension {
     fn child_tokens(
         &self,
     ) -> FilterMap<rowan::SyntaxElementChildren<RenLang>, fn(SyntaxElement) -> Option<SyntaxToken>>;
+    #[allow(dead_code)]
     fn map_children<T>(
         &self,
     ) -> FilterMap<rowan::SyntaxElementChildren<RenLang>, fn(SyntaxElement) -> Option<T>>
@@ -50,13 +52,12 @@ impl SyntaxNodeExtension for SyntaxNode {
 pub(super) trait TokenTypeWrapper {
     fn is_trivia(&self) -> bool {
         use crate::syntax::{StringToken, SyntaxPart, Token};
-        match self.token_type() {
+        matches!(
+            self.token_type(),
             SyntaxPart::Token(
                 Token::Whitespace | Token::Comment | Token::SymDoubleQuote | Token::SymLParen,
-            )
-            | SyntaxPart::StringToken(StringToken::ExprStart | StringToken::Delimiter) => true,
-            _ => false,
-        }
+            ) | SyntaxPart::StringToken(StringToken::ExprStart | StringToken::Delimiter)
+        )
     }
     fn is_not_trivia(&self) -> bool {
         !self.is_trivia()
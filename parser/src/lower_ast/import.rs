@@ -29,7 +29,7 @@ impl FromSyntaxElement for Import {
 }
 impl Import {
     fn source(&self) -> Option<Source> {
-        match self.0.child_tokens().skip_trivia().nth(1).unwrap().kind() {
+        match self.0.children_with_tokens().skip_trivia().nth(1)?.kind() {
             crate::syntax::SyntaxPart::Token(Token::KWPkg) => Some(Source::Package),
             crate::syntax::SyntaxPart::Token(Token::KWExt) => Some(Source::External),
             crate::syntax::SyntaxPart::Context(Context::String) => Some(Source::Project),
@@ -54,19 +54,21 @@ impl Import {
             })
             .unwrap_or_default()
     }
-    // fn exposing(&self) -> Vec<SmolStr> {
-    //     self.0
-    //         .find_node(Context::ExposingBlock)
-    //         .map(|node| {
-    //             node.child_tokens()
-    //                 .filter_map(|tok| match tok.kind() {
-    //                     SyntaxPart::Token(Token::IdLower) => Some(SmolStr::new(tok.text())),
-    //                     _ => None,
-    //                 })
-    //                 .collect()
-    //         })
-    //         .unwrap_or_default()
-    // }
+    fn exposing(&self) -> Vec<SmolStr> {
+        self.0
+            .find_node(Context::ExposingBlock)
+            .map(|node| {
+                node.child_tokens()
+                    .filter_map(|tok| match tok.kind() {
+                        SyntaxPart::Token(Token::IdLower | Token::OpCustom) => {
+                            Some(SmolStr::new(tok.text()))
+                        }
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
 }
 
 impl ToHIR for Import {
@@ -77,7 +79,7 @@ impl ToHIR for Import {
             path: self.path().map(|s| s.to_string()).unwrap(),
             source: self.source().unwrap(),
             alias: self.name().into_iter().map(|s| s.to_string()).collect(),
-            // unqualified: self.exposing().into_iter().map(|s| s.to_string()).collect(),
+            exposing: self.exposing().into_iter().map(|s| s.to_string()).collect(),
         }
     }
     fn validate(&self) -> Option<Self::ValidationError> {
@@ -12,6 +12,7 @@ use super::{
 
 type HigherLiteral<T> = higher_ast::Literal<T>;
 
+#[allow(dead_code, clippy::enum_variant_names)]
 pub(crate) enum Literal<T> {
     //where T: ToHIR {
     LNum(LNumber<T>),
@@ -116,6 +117,7 @@ where
                         StringPart::Left(SmolStr::new(match t.text().chars().last().unwrap() {
                             '$' => "$",
                             '\\' => "\\",
+                            '"' => "\"",
                             'n' => "\n",
                             'r' => "\r",
                             't' => "\t",
@@ -135,7 +137,7 @@ where
                         text.push(txt);
                     }
                     expr_segment => {
-                        if text.len() > 0 {
+                        if !text.is_empty() {
                             acc.push(StringPart::Left(SmolStr::new(text.join(""))));
                             text.clear();
                         }
@@ -144,7 +146,7 @@ where
                 }
                 (acc, text)
             });
-        if text.len() > 0 {
+        if !text.is_empty() {
             parts.push(StringPart::Left(SmolStr::new(text.join(""))));
         }
         parts
@@ -178,10 +180,15 @@ impl<T> LRecord<T>
 where
     T: FromSyntaxElement,
 {
+    /// The name and value of each field. A `..name` spread is keyed by its own name.
     pub fn fields(&self) -> Vec<(String, Option<T>)> {
         self.0
             .children()
             .filter_map(|field_node| {
+                if let Some(spread) = field_node.find_node(Context::Spread) {
+                    let name = spread.find_token(Token::IdLower)?.text().to_string();
+                    return Some((name, T::from_node(Context::Spread, spread)));
+                }
                 let mut iter = field_node.children_with_tokens().skip_trivia();
                 iter.find(|n| n.kind() == Token::IdLower.into()).map(|n| {
                     (
@@ -5,6 +5,7 @@ macro_rules! create_enum {
         $(Token::$tok:ident => $tok_variant:ident($(struct $tok_struct_name:ident)?$($tok_typ_name:ty)?),)*
     }) => {
         #[derive(Debug, Clone, PartialEq, Eq)]
+        #[allow(clippy::enum_variant_names)]
         pub enum $enum_name {
             $($ctx_variant($($ctx_struct_name)?$($ctx_typ_name)?),)*
             $($tok_variant($($tok_struct_name)?$($tok_typ_name)?),)*
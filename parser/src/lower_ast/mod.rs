@@ -119,7 +119,7 @@ fn simple_str(node: SyntaxNode) -> Option<::smol_str::SmolStr> {
             })
             .collect::<Vec<_>>()
             .join("");
-        if s.len() > 0 {
+        if !s.is_empty() {
             Some(SmolStr::new(s))
         } else {
             None
@@ -18,6 +18,7 @@ create_ast_enum! {
         Context::Array => PArr(literal::LArray<Self>),
         Context::Constructor => PCon(literal::LConstructor<Self>),
         Context::TypeMatch => PTyp(struct PType),
+        Context::Spread => PSpread(struct PSpread),
         Token::Number => PNum(literal::LNumber<Self>),
         Token::IdLower => PVar(struct PVar),
         Token::SymUnderscore => PAny(struct PAny),
@@ -90,3 +91,23 @@ impl ToHIR for PVar {
         todo!()
     }
 }
+
+impl PSpread {
+    pub fn name(&self) -> Option<SmolStr> {
+        self.0
+            .find_token(Token::IdLower)
+            .map(|t| SmolStr::new(t.text()))
+    }
+}
+impl ToHIR for PSpread {
+    type HIRType = HigherPattern;
+    type ValidationError = ();
+
+    fn to_higher_ast(&self, _line_lookup: &line_col::LineColLookup) -> Self::HIRType {
+        HigherPattern::Spread(self.name().unwrap().to_string())
+    }
+
+    fn validate(&self) -> Option<Self::ValidationError> {
+        todo!()
+    }
+}
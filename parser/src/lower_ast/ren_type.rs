@@ -13,6 +13,7 @@ type HigherType = higher_ast::Type;
 create_ast_enum! {
     Type = Context::Type => <HigherType, ()> {
         Context::Application => TApp(struct TApp),
+        Context::Forall => TForall(struct TForall),
         Context::FunType => TFun(struct TFun),
         Context::Record => TRec(struct TRec),
         Context::SumType => TSum(struct TSum),
@@ -102,6 +103,38 @@ impl ToHIR for TVar {
     }
 }
 
+impl TForall {
+    pub fn vars(&self) -> impl Iterator<Item = SmolStr> + '_ {
+        self.0
+            .children_with_tokens()
+            .filter_map(|e| e.into_token())
+            .filter(|t| t.kind_matches(Token::IdLower))
+            .map(|t| SmolStr::new(t.text()))
+    }
+    pub fn typ(&self) -> Option<Type> {
+        self.0
+            .children_with_tokens()
+            .skip_trivia()
+            .last()
+            .and_then(Type::from_element)
+    }
+}
+impl ToHIR for TForall {
+    type HIRType = HigherType;
+    type ValidationError = ();
+
+    fn to_higher_ast(&self, line_lookup: &line_col::LineColLookup) -> Self::HIRType {
+        HigherType::forall(
+            self.vars(),
+            self.typ().map(|t| t.to_higher_ast(line_lookup)).unwrap(),
+        )
+    }
+
+    fn validate(&self) -> Option<Self::ValidationError> {
+        todo!()
+    }
+}
+
 impl TFun {
     pub fn arg(&self) -> Option<Type> {
         self.0
@@ -165,8 +198,7 @@ impl ToHIR for TEnum {
     type ValidationError = ();
 
     fn to_higher_ast(&self, _line_lookup: &line_col::LineColLookup) -> Self::HIRType {
-        // HigherType::sum([(self.name().unwrap(), Vec::new())])
-        unimplemented!("Should never be calling to_higher_ast on Variant context")
+        HigherType::sum([(self.name().unwrap(), Vec::<HigherType>::new())])
     }
 
     fn validate(&self) -> Option<Self::ValidationError> {
@@ -189,22 +221,26 @@ impl TApp {
             .last()
             .and_then(Type::from_element)
     }
-}
-impl ToHIR for TApp {
-    type HIRType = HigherType;
-    type ValidationError = ();
-
-    fn to_higher_ast(&self, line_lookup: &line_col::LineColLookup) -> Self::HIRType {
+    /// The type being applied, and all of the arguments it is applied to
+    fn parts(&self) -> (Type, Vec<Type>) {
         let mut typ = self.typ().unwrap();
         let mut r_args = vec![self.arg()];
         while let Type::TApp(app) = typ {
             r_args.push(app.arg());
             typ = app.typ().unwrap();
         }
-        r_args.reverse();
-        let args = r_args
+        (typ, r_args.into_iter().rev().flatten().collect())
+    }
+}
+impl ToHIR for TApp {
+    type HIRType = HigherType;
+    type ValidationError = ();
+
+    fn to_higher_ast(&self, line_lookup: &line_col::LineColLookup) -> Self::HIRType {
+        let (typ, args) = self.parts();
+        let args = args
             .into_iter()
-            .filter_map(|arg| arg.to_higher_ast(line_lookup))
+            .map(|arg| arg.to_higher_ast(line_lookup))
             .collect();
         if let Type::TEnum(var) = typ {
             HigherType::sum([(var.name().unwrap(), args)])
@@ -219,14 +255,22 @@ impl ToHIR for TApp {
 }
 
 impl TSum {
-    pub fn parts(&self) -> impl Iterator<Item = (String, Vec<Type>)> {
-        //TODO: iterate (variant_name, [args...])
-        // self.0.children().filter_map(|node| if node.kind() == Context::Variant.into() {
-        //     node.children_with_tokens().skip_trivia()
-        // } else {
-        //     None
-        // })
-        None.into_iter() //XXX
+    /// The variants of the sum type, with the types of their arguments
+    pub fn parts(&self) -> Vec<(String, Vec<Type>)> {
+        self.0
+            .children_with_tokens()
+            .skip_trivia()
+            .filter_map(Type::from_element)
+            .flat_map(|typ| match typ {
+                Type::TSum(sum) => sum.parts(),
+                Type::TEnum(var) => vec![(var.name().unwrap().to_string(), Vec::new())],
+                Type::TApp(app) => match app.parts() {
+                    (Type::TEnum(var), args) => vec![(var.name().unwrap().to_string(), args)],
+                    _ => Vec::new(),
+                },
+                _ => Vec::new(),
+            })
+            .collect()
     }
 }
 impl ToHIR for TSum {
@@ -236,6 +280,7 @@ impl ToHIR for TSum {
     fn to_higher_ast(&self, line_lookup: &line_col::LineColLookup) -> Self::HIRType {
         HigherType::sum(
             self.parts()
+                .into_iter()
                 .map(|(s, v)| (s, v.into_iter().map(|t| t.to_higher_ast(line_lookup)))),
         )
     }
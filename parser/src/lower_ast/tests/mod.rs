@@ -2,6 +2,8 @@ use crate::parse_expression;
 
 use super::expr::Expr;
 
+mod round_trip;
+
 // #[test]
 // #[ignore = "module unimplemented"]
 // fn parse_sample_file() {
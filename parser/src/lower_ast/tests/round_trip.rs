@@ -0,0 +1,391 @@
+//! Check that printing the higher AST as Ren source and parsing it again gives the same AST.
+
+use higher_ast::pretty::Pretty;
+use line_col::LineColLookup;
+
+use crate::{lower_ast::ToHIR, parse_expr_ast, parse_module_ast, parse_module_ast_with, Fixities};
+
+/// Remove the spans from the debug representation of an AST, as printing changes the layout
+fn strip_spans(debug: &str) -> String {
+    const SPAN: &str = "Span {";
+    let mut out = String::new();
+    let mut rest = debug;
+    while let Some(start) = rest.find(SPAN) {
+        out.push_str(&rest[..start]);
+        rest = &rest[start + SPAN.len()..];
+        let mut depth = 1;
+        let end = rest
+            .char_indices()
+            .find(|(_, c)| {
+                match c {
+                    '{' => depth += 1,
+                    '}' => depth -= 1,
+                    _ => {}
+                }
+                depth == 0
+            })
+            .map(|(i, _)| i)
+            .unwrap();
+        rest = rest[end + 1..].trim_start_matches(", ");
+    }
+    out.push_str(rest);
+    out
+}
+
+fn lower_expr(input: &str) -> higher_ast::Expr {
+    parse_expr_ast(input)
+        .unwrap_or_else(|e| panic!("failed to parse {:?}: {:?}", input, e))
+        .to_higher_ast(&LineColLookup::new(input))
+}
+
+fn lower_module(input: &str) -> higher_ast::Module {
+    parse_module_ast(input)
+        .unwrap_or_else(|e| panic!("failed to parse {:?}: {:?}", input, e))
+        .to_higher_ast(&LineColLookup::new(input))
+}
+
+fn check_expr(input: &str) {
+    let expected = lower_expr(input);
+    for width in [80, 20] {
+        let printed = expected.pretty(width);
+        assert_eq!(
+            strip_spans(&format!("{:?}", lower_expr(&printed))),
+            strip_spans(&format!("{:?}", expected)),
+            "{:?} was printed at width {} as:\n{}",
+            input,
+            width,
+            printed
+        );
+    }
+}
+
+fn check_module(input: &str) {
+    let expected = lower_module(input);
+    for width in [80, 20] {
+        let printed = expected.pretty(width);
+        assert_eq!(
+            strip_spans(&format!("{:?}", lower_module(&printed))),
+            strip_spans(&format!("{:?}", expected)),
+            "module was printed at width {} as:\n{}",
+            width,
+            printed
+        );
+    }
+}
+
+#[test]
+fn literals() {
+    for input in [
+        "1",
+        "1.5",
+        "-2",
+        "[]",
+        "[1, [2, 3], {}]",
+        "{ a: 1, b, c: { d: [e] } }",
+        r#""text""#,
+        r#""escapes \\ \" \$ \n \r \t""#,
+        r#""nested ${"string ${x}"} and ${a + b}""#,
+        "#none",
+        "#just 1",
+        "#pair (#just -1) [#none]",
+    ] {
+        check_expr(input);
+    }
+}
+
+#[test]
+fn operators() {
+    for input in [
+        "a + b * c",
+        "(a + b) * c",
+        "a - (b - c)",
+        "a - b - c",
+        "a / b % c",
+        "a <> b <> c",
+        "(a <> b) <> c",
+        "a == b <> c",
+        "(a == b <> c) == d",
+        "x |> f |> g y",
+        "a |> (b |> c)",
+        "1 + f x * 2",
+        "(1 + f x) * 2",
+        "1 + (f x) - 2",
+        "((x.a) + 1) * y.b",
+        "a + b.c - d",
+        "-a + -1",
+        "(if a then b else c) + 1",
+        "1 + (fun x -> x)",
+        "a |> (fun x -> x * 2)",
+    ] {
+        check_expr(input);
+    }
+}
+
+#[test]
+fn mod_binds_like_mul() {
+    // `%` has the same precedence as `*` and `/`, and groups to the left with them
+    for (input, grouped) in [
+        ("a * b % c", "(a * b) % c"),
+        ("a % b * c", "(a % b) * c"),
+        ("a / b % c", "(a / b) % c"),
+        ("a + b % c", "a + (b % c)"),
+    ] {
+        assert_eq!(
+            strip_spans(&format!("{:?}", lower_expr(input))),
+            strip_spans(&format!("{:?}", lower_expr(grouped))),
+            "{:?}",
+            input
+        );
+    }
+}
+
+#[test]
+fn every_operator() {
+    for &op in higher_ast::Operator::ALL {
+        let input = format!("a {} b", op.symbol());
+        match lower_expr(&input) {
+            higher_ast::Expr::Binop(_, _, parsed, _) => assert_eq!(parsed, op, "{:?}", input),
+            expr => panic!("{:?} was parsed as {:?}", input, expr),
+        }
+        check_expr(&input);
+        check_expr(&format!("(a {0} b) {0} c", op.symbol()));
+        check_expr(&format!("a {0} (b {0} c)", op.symbol()));
+    }
+}
+
+#[test]
+fn user_defined_operators() {
+    use higher_ast::{Associativity, Decl, Fixity};
+    /// The value of the module's `let` declaration, printed
+    fn grouping(input: &str, imported: &Fixities) -> String {
+        let module = parse_module_ast_with(input, imported)
+            .unwrap()
+            .to_higher_ast(&LineColLookup::new(input));
+        module
+            .declarations()
+            .iter()
+            .find_map(|decl| match decl {
+                Decl::Let { expr, .. } => Some(expr.pretty(80)),
+                _ => None,
+            })
+            .unwrap()
+    }
+    let none = Fixities::new();
+    // Without a fixity declaration, operators bind tighter than any built-in operator
+    assert_eq!(grouping("let x = a <+> b * c", &none), "(a <+> b) * c");
+    assert_eq!(grouping("let x = a <+> b <+> c", &none), "(a <+> b) <+> c");
+    assert_eq!(
+        grouping("infixl 6 <+> = add\nlet x = a <+> b * c", &none),
+        "a <+> (b * c)"
+    );
+    // Declarations may come after the operator is used
+    assert_eq!(
+        grouping("let x = a <+> b <+> c\ninfixr 6 <+> = add", &none),
+        "a <+> (b <+> c)"
+    );
+    let mut imported = Fixities::new();
+    imported.insert("<+>", Fixity::new(Associativity::Left, 2));
+    assert_eq!(
+        grouping("let x = a <+> b == c", &imported),
+        "a <+> (b == c)"
+    );
+    assert_eq!(
+        grouping("infixl 9 <+> = add\nlet x = a <+> b == c", &imported),
+        "(a <+> b) == c"
+    );
+
+    check_module(
+        r#"infixl 6 <+> = fun a b -> a + b
+pub infixr 0 <|> = fun a b -> if a then a else b
+let xs = [1 <+> 2 <+> 3, (f x) <+> g.y, -1 <+> -(2 <+> 3)]
+let pick = fun x y z -> x <|> y <|> z
+infixr 5 <:> = fun a b -> [a, b]
+let pair = 1 <:> 2 <.> 3
+"#,
+    );
+}
+
+#[test]
+fn placeholders() {
+    use higher_ast::Expr;
+    for input in [
+        "_ + 1",
+        "(_ * 2) + 1",
+        "f (_ + 1)",
+        "f _ x _",
+        "f (_.a) (g _)",
+        "[_, 1, { k: _ }]",
+        "\"hi ${_}!\"",
+        "{ r | a: _ }",
+        "{ (_) | a: 1 }",
+        "#just _",
+        "if _ then 1 else _",
+        "switch _ on case 1 -> 2 case _ -> 3",
+        "xs |> map (_ <> \"!\")",
+    ] {
+        check_expr(input);
+    }
+    // Each placeholder belongs to the innermost expression containing it
+    let Expr::Call(_, _, args) = lower_expr("f (_ + 1) x") else {
+        panic!("the call has no placeholders of its own");
+    };
+    assert!(matches!(args.as_slice(), [Expr::Lambda(..), Expr::Var(..)]));
+    let Expr::Lambda(_, params, body) = lower_expr("f _ (_ + 1)") else {
+        panic!("the call has a placeholder of its own");
+    };
+    assert_eq!(params.len(), 1);
+    assert!(matches!(*body, Expr::Call(_, _, args) if matches!(args[1], Expr::Lambda(..))));
+}
+
+#[test]
+fn application_and_access() {
+    for input in [
+        "f x y",
+        "f (g x) (-1) (#true)",
+        "f (#just 1) [2]",
+        "f (a.b) c",
+        "(f x).y",
+        "a.b.c",
+        "A.B.c",
+        "A.B.c.d e",
+        "f _ 1",
+        "(x : Number)",
+        "f (g x : Array a) 1",
+        "(fun x -> x : Number -> Number) 1",
+        "{ (r : { a: Number }) | a: 1 }",
+    ] {
+        check_expr(input);
+    }
+}
+
+#[test]
+fn compound_expressions() {
+    for input in [
+        "fun x -> x",
+        "fun a (#just b) @Number c [d, e] { f, g: _ } -> a",
+        "fun x -> fun y -> x + y",
+        "if a then b else if c then d else e",
+        "if if a then b else c then d else e",
+        "let x = 1; x",
+        "let x = let y = 2; y; x",
+        "let f = fun x -> x * 2; f 3",
+        "switch x on case 0 -> a case #just y if y > 1 -> b case _ -> c",
+        "switch x on case { a, b: [c, d] } -> a case [1, _] -> b",
+        "switch x on case 1 -> (switch y on case 2 -> a) case _ -> b",
+        "switch x on case 1 -> (fun z -> switch z on case 2 -> a) case _ -> b",
+        "switch x on case 1 -> a case _ -> switch y on case 2 -> b",
+        "[fun x -> x, if a then b else c, #just 1]",
+        "{ f: fun x -> x, g: let y = 1; y }",
+        "{ r | a: 1, b }",
+        "{ (f x) | a: { r | b: [2] } }",
+        "fun r -> { r | count: 1 + r.count }",
+        "assert x > 0; x * 2",
+        "fun x -> assert x > 0; assert (f x) == 1; x",
+        "fun [x, ..rest] { name, ..others } -> rest",
+        "switch xs on case [] -> 0 case [a, ..mid, z] -> a case { a: [..ys], ..r } -> 1",
+        "let [x, ..xs] = ys; xs",
+        "xs |> List.map (fun x -> x * 2 + 1) |> List.filter (fun x -> x > 3) |> List.foldl (fun acc x -> acc + x) 0",
+    ] {
+        check_expr(input);
+    }
+}
+
+#[test]
+fn modules() {
+    check_module(
+        r#"import "./utils" as Utils
+import pkg "std/list" as List exposing { map, <+> }
+import ext "./ffi"
+
+let xs = [1, 2, 3]
+
+pub let main = fun args -> xs |> List.map (fun x -> x * 2 + 1) |> List.filter (fun x -> x > 3)
+
+let f : Number -> Array a -> Number = fun n r -> n
+
+let g : forall a b. (a -> b) -> Array a -> Array b = fun f xs -> xs
+
+ext log : String -> ? = "console.log"
+
+expect (f 1 []) == 1
+
+pub let classify = fun n -> switch n on case 0 -> "zero" case #just { a, b: [x, y] } if a > 1 -> "${x} and ${y}" case _ -> if n > 10 then #big n else let y = n * (2 - 1); y
+"#,
+    );
+}
+
+#[test]
+fn generic_type_declarations() {
+    use higher_ast::{Decl, Type};
+    fn type_decls(module: &higher_ast::Module) -> Vec<(Vec<String>, Type)> {
+        module
+            .declarations()
+            .iter()
+            .map(|decl| match decl {
+                Decl::Type { vars, typ, .. } => (vars.clone(), typ.clone()),
+                _ => panic!("expected a type declaration, found {:?}", decl),
+            })
+            .collect()
+    }
+    let module = lower_module(
+        "pub type Maybe a = #just a | #nothing\npub type Pair a b = { first: a, second: Array b }\n",
+    );
+    assert_eq!(
+        type_decls(&module),
+        vec![
+            (
+                vec!["a".to_string()],
+                Type::sum([("just", vec![Type::var("a")]), ("nothing", vec![])])
+            ),
+            (
+                vec!["a".to_string(), "b".to_string()],
+                Type::rec([
+                    ("first", Type::var("a")),
+                    ("second", Type::arr(Type::var("b")))
+                ])
+            ),
+        ]
+    );
+    assert_eq!(
+        type_decls(&lower_module(&module.pretty(80))),
+        type_decls(&module)
+    );
+}
+
+#[test]
+fn mixed_declarations() {
+    use higher_ast::Decl;
+    let module = lower_module(
+        r#"type Point = { x: Number }
+let origin = 0
+pub type Maybe a = #just a | #nothing
+ext log : String -> ? = "console.log"
+pub let getX : Point -> Number = fun p -> p.x
+expect (getX { x: 1 }) == 1
+pub infixl 6 <+> = getX
+"#,
+    );
+    let decls = module
+        .declarations()
+        .iter()
+        .map(|decl| match decl {
+            Decl::Let { var, exposed, .. } => format!("let {} {}", var, exposed),
+            Decl::Ext { var, exposed, .. } => format!("ext {} {}", var, exposed),
+            Decl::Type { name, exposed, .. } => format!("type {} {}", name, exposed),
+            Decl::Expect { .. } => "expect".to_string(),
+            Decl::Infix { op, exposed, .. } => format!("infix {} {}", op, exposed),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        decls,
+        [
+            "type Point false",
+            "let origin false",
+            "type Maybe true",
+            "ext log false",
+            "let getX true",
+            "expect",
+            "infix <+> true"
+        ]
+    );
+}
@@ -0,0 +1,69 @@
+use rowan::{TextRange, TextSize};
+
+/// An error encountered whilst parsing, located in the source text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// A description of what the parser expected at this position
+    expected: &'static str,
+    /// The text that was found instead, or `None` at the end of the input
+    found: Option<String>,
+    range: TextRange,
+    span: higher_ast::Span,
+}
+impl ParseError {
+    pub(crate) fn new(
+        expected: &'static str,
+        found: Option<String>,
+        range: std::ops::Range<usize>,
+        line_lookup: &line_col::LineColLookup,
+    ) -> Self {
+        Self {
+            expected,
+            found,
+            range: TextRange::new(
+                TextSize::try_from(range.start).unwrap(),
+                TextSize::try_from(range.end).unwrap(),
+            ),
+            span: (line_lookup.get(range.start), line_lookup.get(range.end)).into(),
+        }
+    }
+    /// The same error, moved `delta` bytes through the source text
+    pub(crate) fn shifted(&self, delta: isize, line_lookup: &line_col::LineColLookup) -> Self {
+        let range = self.range();
+        let shift = |offset: usize| offset.checked_add_signed(delta).unwrap();
+        Self::new(
+            self.expected,
+            self.found.clone(),
+            shift(range.start)..shift(range.end),
+            line_lookup,
+        )
+    }
+    pub fn expected(&self) -> &str {
+        self.expected
+    }
+    pub fn found(&self) -> Option<&str> {
+        self.found.as_deref()
+    }
+    /// The byte range in the source text of the unexpected input
+    pub fn range(&self) -> std::ops::Range<usize> {
+        self.range.start().into()..self.range.end().into()
+    }
+    /// The (line, column) span of the unexpected input
+    pub fn span(&self) -> higher_ast::Span {
+        self.span
+    }
+    /// A description of the error, without its location
+    pub fn message(&self) -> String {
+        match &self.found {
+            Some(text) => format!("expected {}, found {:?}", self.expected, text),
+            None => format!("expected {}, found end of input", self.expected),
+        }
+    }
+}
+impl core::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ((line, col), _): ((usize, usize), (usize, usize)) = self.span.into();
+        write!(f, "{}:{}: {}", line, col, self.message())
+    }
+}
+impl std::error::Error for ParseError {}
@@ -1,38 +1,56 @@
+use std::borrow::Cow;
+
+use higher_ast::Operator;
 use rowan::{GreenNode, GreenNodeBuilder, Language};
 
-use crate::syntax::{
-    lexer::{Lexeme, Lexer},
-    RenLang, SyntaxNode, Token, TokenType,
+use crate::{
+    syntax::{
+        lexer::{Lexeme, Lexer},
+        RenLang, SyntaxNode, SyntaxPart, Token, TokenType,
+    },
+    Fixities,
 };
 
+mod error;
 mod marker;
+pub use error::ParseError;
 pub(crate) use marker::Marker;
 
-pub(crate) struct Parser<'source, E> {
+pub(crate) struct Parser<'source, E = ParseError> {
+    input: &'source str,
     lexer: Lexer<'source>,
     builder: GreenNodeBuilder<'static>,
     line_lookup: line_col::LineColLookup<'source>,
     whitespace_token: Option<Lexeme<'source>>,
     errors: Vec<E>,
+    last_error: Option<usize>,
+    fixities: Fixities,
 }
 
 impl<'source, E> Parser<'source, E> {
     pub fn new(input: &'source str) -> Self {
         Self {
+            input,
             lexer: Lexer::new(input),
             builder: GreenNodeBuilder::new(),
             line_lookup: line_col::LineColLookup::new(input),
             whitespace_token: None,
             errors: Vec::new(),
+            last_error: None,
+            fixities: Fixities::new(),
         }
     }
+    /// Parse user-defined operators with these fixities
+    pub fn with_fixities(mut self, fixities: Fixities) -> Self {
+        self.fixities = fixities;
+        self
+    }
     pub fn start(&mut self, label: &'static str) -> Marker {
         Marker::new(self.builder.checkpoint(), label)
     }
     pub fn bump(&mut self) {
         let (kind, text) = self.lexer.next().expect("Tried to bump at end of input");
-        self.builder
-            .token(RenLang::kind_to_raw(kind.into()), text.into());
+        self.builder.token(RenLang::kind_to_raw(kind.into()), text);
     }
     pub fn bump_matching<T: Into<TokenType>>(&mut self, token: T) -> bool {
         if self.peek() == token.into() {
@@ -42,18 +60,6 @@ impl<'source, E> Parser<'source, E> {
             false
         }
     }
-    pub fn parse(self) -> Parsed<'source> {
-        let remaining = self.lexer.collect::<Vec<_>>();
-        assert!(
-            remaining.len() < 1,
-            "Parser ended before end of input. Remaining Lexemes: {:?}",
-            remaining
-        );
-        Parsed {
-            green_node: self.builder.finish(),
-            line_lookup: self.line_lookup,
-        }
-    }
     pub fn bump_whitespace(&mut self) -> bool {
         if self.whitespace_token.is_some() {
             self.whitespace_token = None;
@@ -70,7 +76,7 @@ impl<'source, E> Parser<'source, E> {
             let peek = self.peek_internal();
             match peek {
                 TokenType::Token(Token::Whitespace) => {
-                    self.whitespace_token = self.lexer.peek().into();
+                    self.whitespace_token = self.lexer.peek();
                     self.bump();
                 }
                 TokenType::Token(Token::Comment) => {
@@ -82,6 +88,41 @@ impl<'source, E> Parser<'source, E> {
             }
         }
     }
+    /// The left and right binding powers of the infix operator written by the next token, if
+    /// there is one. User-defined operators bind according to their fixity.
+    pub fn peek_infix_binding_power(&mut self) -> Option<(u8, u8)> {
+        match self.peek() {
+            TokenType::Token(Token::OpCustom) => {
+                let (_, text) = self.lexer.peek()?;
+                Some(self.fixities.get(text).binding_power())
+            }
+            TokenType::Token(_) => self
+                .lexer
+                .peek()
+                .and_then(|(_, text)| Operator::from_symbol(text))
+                .map(|op| op.infix_binding_power()),
+            _ => None,
+        }
+    }
+    /// Parse as though the input follows whitespace which [`Self::bump_whitespace`] hasn't
+    /// checked for yet, as it might when parsing part of a larger input
+    pub fn after_whitespace(mut self) -> Self {
+        self.whitespace_token = Some((Token::Whitespace.into(), ""));
+        self
+    }
+    /// Whether whitespace has been peeked which [`Self::bump_whitespace`] hasn't checked for
+    pub fn pending_whitespace(&self) -> bool {
+        self.whitespace_token.is_some()
+    }
+    /// Whether all of the input has been consumed, without consuming any whitespace
+    pub fn at_end(&mut self) -> bool {
+        self.lexer.peek().is_none()
+    }
+    /// The text of the next token
+    pub fn peek_text(&mut self) -> Option<&'source str> {
+        self.peek();
+        self.lexer.peek().map(|(_, text)| text)
+    }
     fn peek_internal(&mut self) -> TokenType {
         self.lexer
             .peek()
@@ -90,11 +131,108 @@ impl<'source, E> Parser<'source, E> {
     }
 }
 
-pub struct Parsed<'source> {
+impl<'source, E> Parser<'source, E>
+where
+    E: From<ParseError>,
+{
+    /// Record an error at the next token, without consuming any input.
+    /// Only the first error at any given position is kept, to avoid cascading errors.
+    pub fn error(&mut self, expected: &'static str) {
+        let found = match self.peek() {
+            TokenType::None => None,
+            _ => self.lexer.peek().map(|(_, text)| text.to_string()),
+        };
+        let span = self.lexer.span();
+        if self.last_error == Some(span.start) {
+            return;
+        }
+        self.last_error = Some(span.start);
+        let error = ParseError::new(expected, found, span, &self.line_lookup);
+        self.errors.push(error.into());
+    }
+    /// Bump the next token if it matches, otherwise record an error without consuming any input
+    pub fn expect<T: Into<TokenType>>(&mut self, token: T, expected: &'static str) -> bool {
+        if self.bump_matching(token) {
+            true
+        } else {
+            self.error(expected);
+            false
+        }
+    }
+    /// Record an error, then wrap tokens in an `Error` node until one of the `recovery` tokens
+    /// (or the end of input) is reached. Recovery tokens inside balanced brackets are skipped.
+    pub fn recover(&mut self, expected: &'static str, recovery: &[Token]) {
+        self.error(expected);
+        let m = self.start("error");
+        let mut consumed = false;
+        let mut depth = 0usize;
+        loop {
+            match self.peek() {
+                TokenType::None => break,
+                TokenType::Token(tok) if depth == 0 && recovery.contains(&tok) => break,
+                peek => {
+                    match peek {
+                        TokenType::Token(
+                            Token::SymLBrace | Token::SymLBracket | Token::SymLParen,
+                        ) => depth += 1,
+                        TokenType::Token(
+                            Token::SymRBrace | Token::SymRBracket | Token::SymRParen,
+                        ) => depth = depth.saturating_sub(1),
+                        _ => {}
+                    }
+                    self.bump();
+                    consumed = true;
+                }
+            }
+        }
+        if consumed {
+            m.complete(self, SyntaxPart::Error);
+        } else {
+            m.discard();
+        }
+    }
+    pub fn parse(mut self) -> Parsed<'source, E> {
+        let remaining = self.lexer.by_ref().collect::<Vec<_>>();
+        assert!(
+            remaining.is_empty(),
+            "Parser ended before end of input. Remaining Lexemes: {:?}",
+            remaining
+        );
+        for (range, text) in self.lexer.take_errors() {
+            let found = Some(text.to_string());
+            let error = ParseError::new("a valid token", found, range, &self.line_lookup);
+            self.errors.push(error.into());
+        }
+        Parsed {
+            green_node: self.builder.finish(),
+            source: Cow::Borrowed(self.input),
+            errors: self.errors,
+            fixities: self.fixities,
+        }
+    }
+}
+
+pub struct Parsed<'source, E = ParseError> {
     green_node: GreenNode,
-    line_lookup: line_col::LineColLookup<'source>,
+    source: Cow<'source, str>,
+    errors: Vec<E>,
+    /// The fixities the input was parsed with, needed to reparse part of it
+    fixities: Fixities,
 }
-impl<'source> Parsed<'source> {
+impl<'source, E> Parsed<'source, E> {
+    pub(crate) fn new(
+        green_node: GreenNode,
+        source: &'source str,
+        errors: Vec<E>,
+        fixities: Fixities,
+    ) -> Self {
+        Self {
+            green_node,
+            source: Cow::Borrowed(source),
+            errors,
+            fixities,
+        }
+    }
     pub fn debug_tree(&self) -> String {
         let syntax_node = SyntaxNode::new_root(self.green_node.clone());
         let formatted = format!("{:#?}", syntax_node);
@@ -105,10 +243,30 @@ impl<'source> Parsed<'source> {
     pub(crate) fn syntax(&self) -> SyntaxNode {
         SyntaxNode::new_root(self.green_node.clone())
     }
-    pub fn map<'p, F, T>(&self, f: F) -> T
+    pub fn map<F, T>(&self, f: F) -> T
     where
-        F: Fn(SyntaxNode, &line_col::LineColLookup<'source>) -> T,
+        F: Fn(SyntaxNode, &line_col::LineColLookup) -> T,
     {
-        f(self.syntax(), &self.line_lookup)
+        f(self.syntax(), &line_col::LineColLookup::new(&self.source))
+    }
+    /// All the errors encountered whilst parsing
+    pub fn errors(&self) -> &[E] {
+        &self.errors
+    }
+    pub fn has_errors(&self) -> bool {
+        !self.errors.is_empty()
+    }
+    pub(crate) fn fixities(&self) -> &Fixities {
+        &self.fixities
+    }
+    /// Copy the source into the parse, so it can be kept after the source is edited and then
+    /// passed to [`crate::reparse_module`]
+    pub fn into_owned(self) -> Parsed<'static, E> {
+        Parsed {
+            green_node: self.green_node,
+            source: Cow::Owned(self.source.into_owned()),
+            errors: self.errors,
+            fixities: self.fixities,
+        }
     }
 }
@@ -1,3 +1,6 @@
+// num_derive expands to impls inside an anonymous const
+#![allow(non_local_definitions)]
+
 use num_derive::{FromPrimitive, ToPrimitive};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, FromPrimitive, ToPrimitive)]
@@ -7,7 +10,7 @@ pub enum Context {
     Imports,
     Import, // After import keyword
     IdUpper,
-    // ExposingBlock, // Inside exposing block
+    ExposingBlock, // Inside exposing block
     Declarations,
     Declaration,
 
@@ -49,4 +52,10 @@ pub enum Context {
     Variant,
     SumType,
     FunType,
+    Forall,
+
+    Assert,
+    Spread,
+    Update,
+    Annotated,
 }
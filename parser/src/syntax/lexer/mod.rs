@@ -1,5 +1,7 @@
 mod token;
 
+use std::ops::Range;
+
 use logos::Logos;
 pub(crate) use token::{StringToken, Token};
 
@@ -42,6 +44,13 @@ impl<'source> LexerHolder<'source> {
             LexerHolder::None => unimplemented!("Should not call methods on LexerType::None"),
         };
     }
+    pub(super) fn span(&self) -> Range<usize> {
+        match self {
+            Self::Main(lex) => lex.span(),
+            Self::String(lex) => lex.span(),
+            LexerHolder::None => unimplemented!("Should not call methods on LexerType::None"),
+        }
+    }
     pub(super) fn morph_to_string(&mut self) {
         if let Self::Main(_) = self {
             self.morph();
@@ -59,8 +68,13 @@ impl<'source> Iterator for LexerHolder<'source> {
     fn next(&mut self) -> Option<Self::Item> {
         match self {
             LexerHolder::Main(lex) => lex.next().map(|r| {
-                r.map(|t| (TokenType::Token(t), lex.slice()))
-                    .map_err(|_| lex.slice())
+                r.map(|t| {
+                    (
+                        TokenType::Token(t.resolve_operator(lex.slice())),
+                        lex.slice(),
+                    )
+                })
+                .map_err(|_| lex.slice())
             }),
             LexerHolder::String(lex) => lex.next().map(|r| {
                 r.map(|t| (TokenType::String(t), lex.slice()))
@@ -81,7 +95,9 @@ pub(crate) struct Lexer<'source> {
     internal: LexerHolder<'source>,
     context: Vec<NestedContext>,
     peeked: Option<Lexeme<'source>>,
-    lex_errors: Vec<&'source str>,
+    /// The span of the last lexeme returned (or peeked)
+    span: Range<usize>,
+    lex_errors: Vec<(Range<usize>, &'source str)>,
 }
 impl<'source> Lexer<'source> {
     pub fn new(input: &'source str) -> Self {
@@ -89,6 +105,7 @@ impl<'source> Lexer<'source> {
             internal: LexerHolder::Main(Token::lexer(input)),
             context: Vec::new(),
             peeked: None,
+            span: 0..0,
             lex_errors: Vec::new(),
         }
     }
@@ -107,6 +124,15 @@ impl<'source> Lexer<'source> {
         }
         self.peeked
     }
+    /// The byte range of the most recently peeked (or returned) lexeme.
+    /// At the end of input this is an empty range at the end of the source.
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+    /// Take the byte ranges of any text which could not be lexed
+    pub fn take_errors(&mut self) -> Vec<(Range<usize>, &'source str)> {
+        std::mem::take(&mut self.lex_errors)
+    }
 }
 impl<'source> Iterator for Lexer<'source> {
     type Item = Lexeme<'source>;
@@ -118,9 +144,10 @@ impl<'source> Iterator for Lexer<'source> {
             loop {
                 match self.internal.next() {
                     Some(Err(s)) => {
-                        self.lex_errors.push(s);
+                        self.lex_errors.push((self.internal.span(), s));
                     }
                     Some(Ok(res @ (t, _))) => {
+                        self.span = self.internal.span();
                         match (t, self.context.last()) {
                             (TokenType::Token(Token::SymDoubleQuote), _) => {
                                 self.internal.morph_to_string();
@@ -145,7 +172,10 @@ impl<'source> Iterator for Lexer<'source> {
                         }
                         break Some(res);
                     }
-                    None => break None,
+                    None => {
+                        self.span = self.span.end..self.span.end;
+                        break None;
+                    }
                 }
             }
         }
@@ -49,3 +49,41 @@ fn lambda() {
         assert_eq!(lexed, expected);
     }
 }
+
+#[test]
+fn operators() {
+    for op in higher_ast::Operator::ALL {
+        let lexed: Vec<_> = Lexer::new(op.symbol()).collect();
+        assert!(
+            matches!(
+                lexed.as_slice(),
+                [(TokenType::Token(tok), text)] if *text == op.symbol() && *tok == Token::from(*op)
+            ),
+            "{:?} was lexed as {:?}",
+            op,
+            lexed
+        );
+    }
+}
+
+#[test]
+fn custom_operators() {
+    for op in [
+        "<+>", "<|>", "|+|", "~>", "!!", "+-", "==>", "<:>", "<.>", ":=", "...",
+    ] {
+        let lexed: Vec<_> = Lexer::new(op).collect();
+        assert_eq!(lexed, [(Token::OpCustom.into(), op)]);
+    }
+    // Symbols which aren't operators keep their own tokens
+    for (input, token) in [
+        ("->", Token::SymArrow),
+        ("=", Token::SymEquals),
+        (":", Token::SymColon),
+        (".", Token::SymDot),
+        ("..", Token::SymDoubleDot),
+        ("// comment", Token::Comment),
+    ] {
+        let lexed: Vec<_> = Lexer::new(input).collect();
+        assert_eq!(lexed, [(token.into(), input)]);
+    }
+}
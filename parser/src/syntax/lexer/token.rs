@@ -1,3 +1,7 @@
+// num_derive expands to impls inside an anonymous const
+#![allow(non_local_definitions)]
+
+use higher_ast::Operator;
 use logos::Logos;
 use num_derive::{FromPrimitive, ToPrimitive};
 
@@ -20,8 +24,8 @@ pub enum Token {
     KWElse,
     #[token("expect")]
     KWExpect,
-    // #[token("exposing")]
-    // KWExposing,
+    #[token("exposing")]
+    KWExposing,
     #[token("ext")]
     KWExt,
     #[token("forall")]
@@ -32,6 +36,10 @@ pub enum Token {
     KWIf,
     #[token("import")]
     KWImport,
+    #[token("infixl")]
+    KWInfixl,
+    #[token("infixr")]
+    KWInfixr,
     #[token("let")]
     KWLet,
     #[token("on")]
@@ -67,41 +75,33 @@ pub enum Token {
     #[regex(r"(0[xX][0-9a-fA-F]+)|(0[oO][0-7]+)|(0[bB][01]+)")] // Hex/Oct/Bin
     Number,
 
-    // Operators
-    #[token("+")]
-    OpAdd, //infixLeft  6
-    #[token("&")]
-    OpAnd, //infixRight 3
-    #[token("<>")]
-    OpConcat, //infixRight 5
-    #[token("/")]
-    OpDiv, //infixLeft  7
-    #[token("==")]
-    OpEq, //infixLeft  4
-    #[token(">")]
-    OpGt, //infixLeft  4
-    #[token(">=")]
-    OpGte, //infixLeft  4
-    #[token("<")]
-    OpLt, //infixLeft  4
-    #[token("<=")]
-    OpLte, //infixLeft  4
-    #[token("%")]
-    OpMod, //infixRight 8
-    #[token("*")]
-    OpMul, //infixLeft  7
-    #[token("!=")]
-    OpNeq, //infixLeft  4
-    #[token("|")]
-    OpOr, //infixRight 2
-    #[token("|>")]
-    OpPipe, //infixLeft 1
-    #[token("^")]
-    OpPow, //infixRight 8
+    // Built-in operators, one for each entry in `Operator`'s table. They are lexed as
+    // `OpCustom`, then given their own token by `Token::resolve_operator`.
+    OpAdd,
+    OpAnd,
+    OpConcat,
+    OpCons,
+    OpDiv,
+    OpEq,
+    OpGt,
+    OpGte,
+    OpLt,
+    OpLte,
+    OpMod,
+    OpMul,
+    OpNeq,
+    OpOr,
+    OpPipe,
+    OpPow,
+    /// Separates a binding or assertion from the expression that follows it. Unlike the
+    /// other `Op` tokens, it is not an infix operator.
     #[token(";")]
     OpSeq,
-    #[token("-")]
-    OpSub, //infixLeft  6
+    OpSub,
+    /// Any other sequence of operator characters, which must be given a meaning by a fixity
+    /// declaration, e.g. `infixl 6 <+> = add`
+    #[regex(r"[!$%&*+\-./:<=>^|~]+")]
+    OpCustom,
 
     // Symbols
     #[token("→")]
@@ -149,8 +149,6 @@ pub enum Token {
 
     // #[token(">>")]
     // OpCompose, //infixRight 9
-    // #[token("::")]
-    // OpCons, //infixRight 5
 
     /*#[regex(r"[\r\n]+", priority=2)]
     NewLine,*/
@@ -158,6 +156,32 @@ pub enum Token {
     Whitespace,
 }
 
+impl Token {
+    /// The token for a run of operator characters, which is a built-in operator's own token if
+    /// it spells one
+    pub(crate) fn resolve_operator(self, text: &str) -> Self {
+        match self {
+            Token::OpCustom => Operator::from_symbol(text).map_or(self, Token::from),
+            _ => self,
+        }
+    }
+}
+
+macro_rules! operator_tokens {
+    ($(($op:ident, $name:literal, $symbol:literal, $assoc:ident, $prec:literal)),+ $(,)?) => {
+        impl From<Operator> for Token {
+            fn from(op: Operator) -> Self {
+                paste::paste! {
+                    match op {
+                        $(Operator::$op => Token::[<Op $op>],)+
+                    }
+                }
+            }
+        }
+    };
+}
+higher_ast::operator_table!(operator_tokens);
+
 #[derive(
     Logos, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, FromPrimitive, ToPrimitive,
 )]
@@ -8,6 +8,7 @@ pub(crate) use lexer::{StringToken, Token, TokenType};
 use rowan::{Language, SyntaxKind};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, derive_more::From)]
+#[allow(clippy::upper_case_acronyms)]
 pub enum SyntaxPart {
     Error, // = 0
     EOF,   // = 1
@@ -24,14 +25,14 @@ impl Default for SyntaxPart {
     }
 }
 
-impl Into<u16> for SyntaxPart {
-    fn into(self) -> u16 {
-        match self {
-            Self::Error => 0,
-            Self::EOF => 1,
-            Self::StringToken(t) => u16::from(t.to_u8().unwrap()) + 2u16, // StringToken = 2..=7 allowed (actually only currently 4 tokens)
-            Self::Token(t) => u16::from(t.to_u8().unwrap()) + 8u16, // Token = 8..=255 allowed (actually only currently 60 tokens)
-            Self::Context(c) => u16::from(c.to_u8().unwrap()) + 256u16, // Context = 256..
+impl From<SyntaxPart> for u16 {
+    fn from(val: SyntaxPart) -> Self {
+        match val {
+            SyntaxPart::Error => 0,
+            SyntaxPart::EOF => 1,
+            SyntaxPart::StringToken(t) => u16::from(t.to_u8().unwrap()) + 2u16, // StringToken = 2..=7 allowed (actually only currently 4 tokens)
+            SyntaxPart::Token(t) => u16::from(t.to_u8().unwrap()) + 8u16, // Token = 8..=255 allowed (actually only currently 60 tokens)
+            SyntaxPart::Context(c) => u16::from(c.to_u8().unwrap()) + 256u16, // Context = 256..
         }
     }
 }
@@ -50,12 +51,12 @@ impl TryFrom<u16> for SyntaxPart {
             Self::Error
         } else if value == 1 {
             Self::EOF
-        } else if value < 7 {
+        } else if value < 8 {
             Self::StringToken(
-                StringToken::from_u16(value - 1).ok_or(SPConvertError::StringToken(value))?,
+                StringToken::from_u16(value - 2).ok_or(SPConvertError::StringToken(value))?,
             )
         } else if value <= 0xFF {
-            Self::Token(Token::from_u16(value - 7).ok_or(SPConvertError::RawToken(value))?)
+            Self::Token(Token::from_u16(value - 8).ok_or(SPConvertError::RawToken(value))?)
         } else {
             Self::Context(Context::from_u16(value - 0x100).ok_or(SPConvertError::Context(value))?)
         })
@@ -78,26 +79,6 @@ impl Language for RenLang {
 
 pub(crate) type SyntaxNode = rowan::SyntaxNode<RenLang>;
 
-#[cfg(test)]
-mod test {
-    use super::SyntaxPart;
-
-    #[test]
-    fn syntaxpart_u16_conversion() {
-        for val in 0..512 {
-            match SyntaxPart::try_from(val) {
-                Ok(sp) => assert_eq!(
-                    val,
-                    <SyntaxPart as Into<u16>>::into(sp),
-                    "Converting: {:?}",
-                    sp
-                ),
-                Err(_) => (),
-            }
-        }
-    }
-}
-
 impl From<TokenType> for SyntaxPart {
     fn from(t: TokenType) -> Self {
         match t {
@@ -118,3 +99,22 @@ impl TryFrom<SyntaxPart> for TokenType {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::SyntaxPart;
+
+    #[test]
+    fn syntaxpart_u16_conversion() {
+        for val in 0..512 {
+            if let Ok(sp) = SyntaxPart::try_from(val) {
+                assert_eq!(
+                    val,
+                    <SyntaxPart as Into<u16>>::into(sp),
+                    "Converting: {:?}",
+                    sp
+                )
+            }
+        }
+    }
+}
@@ -103,9 +103,10 @@ where
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub(crate) enum RenJsonItems<T> {
     /// [{$: tag, ...meta}]
+    #[default]
     None,
     /// [{$: tag, ...meta}, value]
     SingleValue(T),
@@ -135,7 +136,7 @@ where
             if items.len() > 1 {
                 Self::Multiple(items.into_iter().collect())
             } else {
-                Self::SingleList(items.into_iter().next().expect(&format!("Error unwrapping first value from SingleList with no items! should be unreachable: content = {}", content)))
+                Self::SingleList(items.into_iter().next().unwrap_or_else(|| panic!("Error unwrapping first value from SingleList with no items! should be unreachable: content = {}", content)))
             }
         })
     }
@@ -184,7 +185,7 @@ where
                     RenJsonItems::None
                 }
             }
-            Self::Multiple(vec) => vec.iter().cloned().filter(f).collect(),
+            Self::Multiple(vec) => vec.iter().filter(|&x| f(x)).cloned().collect(),
         }
     }
     /// Create a new iterator with the first n elements, leaving the current items unaffected
@@ -199,7 +200,7 @@ where
                 Self::None => RenJsonItems::None,
                 Self::SingleValue(v) => RenJsonItems::SingleValue(v.clone()),
                 Self::SingleList(v) => RenJsonItems::SingleList(v.clone()),
-                Self::Multiple(vec) => vec.iter().cloned().take(n).collect(),
+                Self::Multiple(vec) => vec.iter().take(n).cloned().collect(),
             }
         }
     }
@@ -212,11 +213,6 @@ where
         }
     }
 }
-impl<T> Default for RenJsonItems<T> {
-    fn default() -> Self {
-        Self::None
-    }
-}
 impl<T> Default for &RenJsonItems<T> {
     fn default() -> Self {
         &RenJsonItems::None
@@ -357,12 +353,8 @@ impl Parse for RenJsonPatField {
                                 } else {
                                     return Err(input.error("duplicate pattern field \"meta\""));
                                 }
-                            } else if name.starts_with("item") {
-                                if name.len() > 4 {
-                                    items.push((ident.clone(), (&name[4..]).parse::<usize>().ok()));
-                                } else {
-                                    items.push((ident.clone(), None));
-                                }
+                            } else if let Some(index) = name.strip_prefix("item") {
+                                items.push((ident.clone(), index.parse::<usize>().ok()));
                             }
                         } else {
                             return Err(input.error("Unsupported pattern match"));
@@ -389,12 +381,8 @@ impl Parse for RenJsonPatField {
                                 } else {
                                     return Err(input.error("duplicate pattern field \"meta\""));
                                 }
-                            } else if name.starts_with("item") {
-                                if name.len() > 4 {
-                                    items.push((ident.clone(), (&name[4..]).parse::<usize>().ok()));
-                                } else {
-                                    items.push((ident.clone(), None));
-                                }
+                            } else if let Some(index) = name.strip_prefix("item") {
+                                items.push((ident.clone(), index.parse::<usize>().ok()));
                             }
                         } else {
                             return Err(input.error("Unsupported pattern match"));
@@ -429,7 +417,7 @@ impl Parse for RenJsonPatField {
                 let (meta, items) = parse_unordered_fields!(
                     meta = kw::meta => RenJsonMetaField<syn::Ident>,
                     items = kw::items => RenJsonItemsField<syn::Ident>,
-                )(&input)?;
+                )(input)?;
                 (meta.map(|m| m.0), items.map(|i| i.0))
             }
         } else {
@@ -439,7 +427,7 @@ impl Parse for RenJsonPatField {
     }
 }
 impl RenJsonPatField {
-    pub fn with_variant_data<'a>(
+    pub fn with_variant_data(
         &self,
         variant_name: syn::Ident,
         tag: Option<&String>,
@@ -524,7 +512,7 @@ impl Parse for RenJsonAttribute {
                     .or(pat.1.as_ref().map(|m| m.clone().into())),
                 items: items
                     .map(|i| i.0)
-                    .or(pat.2.as_ref().map(|m| RenJsonItems::from(m))),
+                    .or(pat.2.as_ref().map(RenJsonItems::from)),
                 pattern: Some(pat),
             }
         } else {
@@ -37,10 +37,7 @@ impl VariantArmSer {
     //     self.meta.is_some()
     // }
     fn has_items(&self) -> bool {
-        match self.items {
-            RenJsonItems::None => false,
-            _ => true,
-        }
+        !matches!(self.items, RenJsonItems::None)
     }
     // fn has_fields(&self) -> bool {
     //     self.has_meta() || self.has_items()
@@ -412,7 +409,7 @@ impl VariantFields {
                     if path
                         .segments
                         .last()
-                        .filter(|syn::PathSegment { ident, .. }| ident.to_string() == "Meta")
+                        .filter(|syn::PathSegment { ident, .. }| *ident == "Meta")
                         .is_some()
                         && meta.is_none()
                     {
@@ -497,7 +494,7 @@ impl From<&FieldDefaults> for VariantConstructor {
                     .iter()
                     .map(|mem| match mem {
                         syn::Member::Named(name) => (
-                            (&name.to_string()[6..])
+                            name.to_string()[6..]
                                 .parse()
                                 .expect("Error converting field_{i} name into u32"),
                             name.clone(),
@@ -544,7 +541,7 @@ impl VariantConstructor {
             }
             Self::Unnamed(_, ref mut map) => {
                 map.insert(
-                    (&meta.to_string()[6..])
+                    meta.to_string()[6..]
                         .parse()
                         .expect("Error converting field_{i} name into u32"),
                     ident,
@@ -642,7 +639,10 @@ impl ToTokens for VariantConstructor {
             Self::Named(names) => syn::token::Brace::default().surround(tokens, |tokens| {
                 let field = names
                     .iter()
-                    .map(|(k, v)| quote! { #k: #v })
+                    .map(|(k, v)| match v {
+                        Some(v) => quote! { #k: #v },
+                        None => quote! { #k },
+                    })
                     .collect::<Vec<_>>();
                 tokens.extend(quote! { #(#field),* });
             }),
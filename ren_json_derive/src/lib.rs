@@ -19,9 +19,7 @@ pub fn ren_enum_serialise(tokens: proc_macro::TokenStream) -> proc_macro::TokenS
         attrs.into_iter().find(|syn::Attribute { path, .. }| {
             path.segments
                 .last()
-                .map_or(false, |syn::PathSegment { ident, .. }| {
-                    ident.to_string() == "ren_json"
-                })
+                .is_some_and(|syn::PathSegment { ident, .. }| *ident == "ren_json")
         })
     {
         match syn::parse2::<RenJsonEnumAttribute>(tokens) {
@@ -47,9 +45,7 @@ pub fn ren_enum_serialise(tokens: proc_macro::TokenStream) -> proc_macro::TokenS
                 attrs.into_iter().find(|syn::Attribute { path, .. }| {
                     path.segments
                         .last()
-                        .map_or(false, |syn::PathSegment { ident, .. }| {
-                            ident.to_string() == "ren_json"
-                        })
+                        .is_some_and(|syn::PathSegment { ident, .. }| *ident == "ren_json")
                 })
             {
                 match syn::parse2::<RenJsonAttribute>(tokens) {
@@ -76,7 +72,7 @@ pub fn ren_enum_serialise(tokens: proc_macro::TokenStream) -> proc_macro::TokenS
         },
     );
     let mut ser_generics = generics.clone();
-    if type_params.len() > 0 {
+    if !type_params.is_empty() {
         ser_generics.where_clause = Some(match ser_generics.where_clause {
             Some(mut w_c) => {
                 w_c.predicates
@@ -127,7 +123,7 @@ pub fn ren_enum_serialise(tokens: proc_macro::TokenStream) -> proc_macro::TokenS
     };
     let mut de_generics = generics.clone();
     de_generics.params.push(syn::parse_quote! { 'de });
-    if type_params.len() > 0 {
+    if !type_params.is_empty() {
         de_generics.where_clause = Some(match de_generics.where_clause {
             Some(mut w_c) => {
                 w_c.predicates
@@ -0,0 +1,88 @@
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use ast::{
+    codegen::{ffi_path, module_to_js},
+    exhaustiveness, infer,
+    resolve::resolve_module,
+};
+
+use crate::{project::load_project, CliError};
+
+/// Compile the module at `entry` and every module it imports to JavaScript.
+///
+/// Every module is checked before anything is written: variables which can't be resolved and
+/// type errors fail the build, while switches which don't match every value and unreachable
+/// cases are written to `w` as warnings. The JavaScript for each module is written to
+/// `out_dir` at the same relative path as the module has to the entry module, or beside the
+/// module if `out_dir` is missing. The FFI files of modules with `ext` declarations are copied
+/// beside them.
+pub(crate) fn build_project<W: Write>(
+    entry: &Path,
+    out_dir: Option<&Path>,
+    mut w: W,
+) -> Result<(), CliError> {
+    let root = entry.parent().unwrap_or_else(|| Path::new(""));
+    let mut modules = load_project(entry)?;
+    let mut ffi_files = Vec::new();
+    for project_module in &mut modules {
+        let module = &mut project_module.module;
+        // Type checking would report unbound variables again, so only happens once every
+        // variable has been resolved
+        let resolution = resolve_module(module);
+        let errors = if resolution.has_errors() {
+            resolution
+                .errors()
+                .iter()
+                .map(ToString::to_string)
+                .collect()
+        } else {
+            match infer::check_module(module) {
+                Ok(()) => Vec::new(),
+                Err(errors) => errors.iter().map(ToString::to_string).collect(),
+            }
+        };
+        if !errors.is_empty() {
+            return Err(CliError::File(
+                project_module.path.clone(),
+                Box::new(CliError::Check(errors)),
+            ));
+        }
+        for warning in exhaustiveness::check_module(module) {
+            writeln!(w, "warning: {}:{}", project_module.path.display(), warning)?;
+        }
+        if module.meta().uses_ffi() {
+            let path = ffi_file(&project_module.path, &ffi_path(module.meta()));
+            let source = std::fs::read(root.join(&path))
+                .map_err(|e| CliError::File(path.clone(), Box::new(e.into())))?;
+            ffi_files.push((path, source));
+        }
+    }
+
+    let out_dir = out_dir.unwrap_or(root);
+    for (path, source) in ffi_files {
+        let out_path = out_dir.join(path);
+        if let Some(parent) = out_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(out_path, source)?;
+    }
+    for project_module in &modules {
+        let out_path = out_dir.join(project_module.path.with_extension("js"));
+        if let Some(parent) = out_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(out_path, module_to_js(&project_module.module))?;
+    }
+    writeln!(w, "built {} module(s)", modules.len())?;
+    Ok(())
+}
+
+/// The path of the FFI file a module imports, relative to the project root, from its path
+/// relative to the module
+fn ffi_file(module: &Path, ffi_path: &str) -> PathBuf {
+    let dir = module.parent().unwrap_or_else(|| Path::new(""));
+    dir.join(ffi_path.trim_start_matches("./"))
+}
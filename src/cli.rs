@@ -34,12 +34,42 @@ pub(crate) enum Cmd {
         #[clap(short, long)]
         /// The file to write the output to. If missing, will write to STDOUT
         ofile: Option<PathBuf>,
-        #[clap(short, long, default_value_t)]
+        #[clap(short, long, value_enum, default_value_t)]
         /// The format of the output
-        format: crate::ReplModes, //TODO: Better output format options, seperate from repl modes
+        format: crate::OutputFormat,
+        #[clap(long)]
+        /// Parse the input as a single REPL statement, rather than as a module
+        stmt: bool,
         #[clap(hide = true, group = "src_in")]
         stdinput: Option<String>,
     },
+    /// Format Ren source files, keeping any comments
+    Fmt {
+        /// The files to format in place. If missing, will read from STDIN and write to STDOUT
+        files: Vec<PathBuf>,
+        #[clap(long)]
+        /// Only check whether the input is formatted, failing if it is not
+        check: bool,
+        #[clap(short, long, default_value_t = ast::pretty::DEFAULT_WIDTH)]
+        /// The line width to try to fit the output within
+        width: usize,
+    },
+    /// Evaluate every `expect` declaration in a module, reporting which pass or fail
+    Test {
+        /// The module to test
+        file: PathBuf,
+    },
+    /// Compile a module and every module it imports to JavaScript
+    Build {
+        /// The entry module of the project. Imports are resolved relative to its directory
+        entry: PathBuf,
+        #[clap(short, long)]
+        /// The directory to write the output to. If missing, each module's output is written
+        /// beside it
+        out_dir: Option<PathBuf>,
+    },
+    /// Run the language server, communicating over STDIN and STDOUT
+    Lsp,
 }
 
 pub(crate) fn parse() -> Cli {
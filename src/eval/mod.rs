@@ -0,0 +1,519 @@
+//! A tree-walking interpreter for Ren expressions
+
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    fmt,
+    rc::Rc,
+};
+
+use ast::{
+    expr::{literal::StringParts, StringPart},
+    Decl, Expr, Literal, Operator, Pattern,
+};
+
+use crate::value::{Closure, Value};
+
+#[cfg(test)]
+mod tests;
+
+/// The local variables in scope, as a linked list so that closures can share them
+pub(crate) type Locals = Option<Rc<Binding>>;
+
+#[derive(Debug)]
+pub(crate) struct Binding {
+    name: String,
+    value: Value,
+    next: Locals,
+}
+
+fn lookup<'l>(mut locals: &'l Locals, name: &str) -> Option<&'l Value> {
+    while let Some(binding) = locals {
+        if binding.name == name {
+            return Some(&binding.value);
+        }
+        locals = &binding.next;
+    }
+    None
+}
+fn bind(locals: Locals, name: &str, value: Value) -> Locals {
+    Some(Rc::new(Binding {
+        name: name.to_string(),
+        value,
+        next: locals,
+    }))
+}
+
+/// The variables which are in scope everywhere. These are looked up when they are used, so a
+/// function may refer to a global which is defined after it. A global which can't be evaluated
+/// returns the error that stopped it.
+pub(crate) trait Globals {
+    fn global(&self, name: &str) -> Option<Result<Value, EvalError>>;
+}
+impl Globals for HashMap<String, Value> {
+    fn global(&self, name: &str) -> Option<Result<Value, EvalError>> {
+        self.get(name).cloned().map(Ok)
+    }
+}
+
+/// The declarations of a module, which are each evaluated the first time they are used
+pub(crate) struct ModuleGlobals<'m> {
+    decls: HashMap<&'m str, &'m Expr>,
+    values: RefCell<HashMap<&'m str, Value>>,
+    /// The declarations currently being evaluated, so that a value defined in terms of itself
+    /// is reported as unbound rather than recursing forever
+    pending: RefCell<HashSet<&'m str>>,
+}
+impl<'m> ModuleGlobals<'m> {
+    pub fn new<I>(decls: I) -> Self
+    where
+        I: IntoIterator<Item = &'m Decl>,
+    {
+        Self {
+            decls: decls
+                .into_iter()
+                .filter_map(|decl| match decl {
+                    Decl::Let { var, expr, .. } | Decl::Infix { op: var, expr, .. } => {
+                        Some((var.as_str(), expr))
+                    }
+                    _ => None,
+                })
+                .collect(),
+            values: RefCell::default(),
+            pending: RefCell::default(),
+        }
+    }
+}
+impl Globals for ModuleGlobals<'_> {
+    fn global(&self, name: &str) -> Option<Result<Value, EvalError>> {
+        if let Some(value) = self.values.borrow().get(name) {
+            return Some(Ok(value.clone()));
+        }
+        let (&name, expr) = self.decls.get_key_value(name)?;
+        if !self.pending.borrow_mut().insert(name) {
+            return None;
+        }
+        let value = eval(expr, self);
+        self.pending.borrow_mut().remove(name);
+        if let Ok(value) = &value {
+            self.values.borrow_mut().insert(name, value.clone());
+        }
+        Some(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EvalError {
+    UnboundVar(String),
+    NotAFunction(Value),
+    Mismatch {
+        expected: &'static str,
+        found: Value,
+    },
+    MissingField(String, Value),
+    NoMatch(Value),
+    AssertionFailed,
+}
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnboundVar(name) => write!(f, "unbound variable `{}`", name),
+            Self::NotAFunction(value) => write!(f, "`{}` is not a function", value),
+            Self::Mismatch { expected, found } => {
+                write!(f, "expected {}, found `{}`", expected, found)
+            }
+            Self::MissingField(key, value) => write!(f, "`{}` has no field `{}`", value, key),
+            Self::NoMatch(value) => write!(f, "no pattern matches `{}`", value),
+            Self::AssertionFailed => write!(f, "assertion failed"),
+        }
+    }
+}
+impl std::error::Error for EvalError {}
+
+type EvalResult = Result<Value, EvalError>;
+
+/// Evaluate an expression in which the only free variables are globals
+pub(crate) fn eval<G: Globals + ?Sized>(expr: &Expr, globals: &G) -> EvalResult {
+    Interpreter { globals }.eval(expr, &None)
+}
+
+/// Evaluate the expression of an `expect` declaration, which passes if it is `true`
+pub(crate) fn expect<G: Globals + ?Sized>(expr: &Expr, globals: &G) -> Result<bool, EvalError> {
+    Interpreter { globals }.boolean(expr, &None)
+}
+
+struct Interpreter<'g, G: ?Sized> {
+    globals: &'g G,
+}
+
+impl<'g, G: Globals + ?Sized> Interpreter<'g, G> {
+    fn eval(&self, expr: &Expr, locals: &Locals) -> EvalResult {
+        match expr {
+            Expr::Access(_, rec, key) => {
+                let rec = self.eval(rec, locals)?;
+                match rec.field(key) {
+                    Some(value) => Ok(value.clone()),
+                    None => Err(EvalError::MissingField(key.clone(), rec)),
+                }
+            }
+            Expr::Annotated(_, expr, _) => self.eval(expr, locals),
+            Expr::Assert(_, cond, body) => {
+                if self.boolean(cond, locals)? {
+                    self.eval(body, locals)
+                } else {
+                    Err(EvalError::AssertionFailed)
+                }
+            }
+            Expr::Binop(_, lhs, op, rhs) => self.binop(lhs, *op, rhs, locals),
+            Expr::Call(_, fun, args) => {
+                let mut value = self.eval(fun, locals)?;
+                for arg in args {
+                    let arg = self.eval(arg, locals)?;
+                    value = self.apply(value, arg)?;
+                }
+                Ok(value)
+            }
+            Expr::If(_, cond, then_, else_) => {
+                if self.boolean(cond, locals)? {
+                    self.eval(then_, locals)
+                } else {
+                    self.eval(else_, locals)
+                }
+            }
+            Expr::Lambda(_, params, body) => Ok(Value::Function(Rc::new(Closure {
+                params: params.clone(),
+                body: Rc::new(body.as_ref().clone()),
+                locals: locals.clone(),
+                args: Vec::new(),
+            }))),
+            Expr::Let(_, pattern, value, body) => {
+                let value = self.eval(value, locals)?;
+                match matches(pattern, &value, locals.clone()) {
+                    Some(locals) => self.eval(body, &locals),
+                    None => Err(EvalError::NoMatch(value)),
+                }
+            }
+            Expr::Literal(_, lit) => self.literal(lit, locals),
+            // A lone placeholder has nothing to abstract over
+            Expr::Placeholder(_) => Ok(Value::undefined()),
+            Expr::Scoped(_, namespace, var) => Err(EvalError::UnboundVar(format!(
+                "{}.{}",
+                namespace.join("."),
+                var
+            ))),
+            Expr::Switch(_, subject, arms) => {
+                let subject = self.eval(subject, locals)?;
+                for (pattern, guard, body) in arms {
+                    if let Some(locals) = matches(pattern, &subject, locals.clone()) {
+                        let guarded = match guard {
+                            Some(guard) => self.boolean(guard, &locals)?,
+                            None => true,
+                        };
+                        if guarded {
+                            return self.eval(body, &locals);
+                        }
+                    }
+                }
+                Err(EvalError::NoMatch(subject))
+            }
+            Expr::Update(_, rec, fields) => {
+                let mut rec = self.eval(rec, locals)?;
+                for (_, key, value) in fields {
+                    let value = self.eval(value, locals)?;
+                    match &mut rec {
+                        Value::Record(entries) if entries.iter().any(|(k, _)| k == key) => {
+                            for (_, old) in entries.iter_mut().filter(|(k, _)| k == key) {
+                                *old = value.clone();
+                            }
+                        }
+                        _ => return Err(EvalError::MissingField(key.clone(), rec)),
+                    }
+                }
+                Ok(rec)
+            }
+            Expr::Var(_, name) => match lookup(locals, name) {
+                Some(value) => Ok(value.clone()),
+                None => self
+                    .globals
+                    .global(name)
+                    .unwrap_or_else(|| Err(EvalError::UnboundVar(name.clone()))),
+            },
+        }
+    }
+
+    /// Apply a function to a single argument. The body is evaluated once all the parameters
+    /// have been supplied.
+    fn apply(&self, fun: Value, arg: Value) -> EvalResult {
+        let closure = match fun {
+            Value::Function(closure) => closure,
+            _ => return Err(EvalError::NotAFunction(fun)),
+        };
+        let mut args = closure.args.clone();
+        args.push(arg);
+        if args.len() < closure.params.len() {
+            return Ok(Value::Function(Rc::new(Closure {
+                params: closure.params.clone(),
+                body: closure.body.clone(),
+                locals: closure.locals.clone(),
+                args,
+            })));
+        }
+        let mut locals = closure.locals.clone();
+        for (param, arg) in closure.params.iter().zip(args) {
+            locals = matches(param, &arg, locals).ok_or(EvalError::NoMatch(arg))?;
+        }
+        self.eval(&closure.body, &locals)
+    }
+
+    fn binop(&self, lhs: &Expr, op: Operator, rhs: &Expr, locals: &Locals) -> EvalResult {
+        // The logical operators only evaluate their right hand side if they need to
+        match op {
+            Operator::And => {
+                return Ok(Value::boolean(
+                    self.boolean(lhs, locals)? && self.boolean(rhs, locals)?,
+                ))
+            }
+            Operator::Or => {
+                return Ok(Value::boolean(
+                    self.boolean(lhs, locals)? || self.boolean(rhs, locals)?,
+                ))
+            }
+            _ => {}
+        }
+        let lhs = self.eval(lhs, locals)?;
+        let rhs = self.eval(rhs, locals)?;
+        Ok(match op {
+            Operator::Add => Value::Number(number(lhs)? + number(rhs)?),
+            Operator::Sub => Value::Number(number(lhs)? - number(rhs)?),
+            Operator::Mul => Value::Number(number(lhs)? * number(rhs)?),
+            Operator::Div => Value::Number(number(lhs)? / number(rhs)?),
+            Operator::Mod => Value::Number(number(lhs)? % number(rhs)?),
+            Operator::Pow => Value::Number(number(lhs)?.powf(number(rhs)?)),
+            Operator::Eq => Value::boolean(lhs == rhs),
+            Operator::Neq => Value::boolean(lhs != rhs),
+            Operator::Gt | Operator::Gte | Operator::Lt | Operator::Lte => {
+                let ordering = match (&lhs, &rhs) {
+                    (Value::Number(a), Value::Number(b)) => a.partial_cmp(b),
+                    (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+                    _ => {
+                        return Err(EvalError::Mismatch {
+                            expected: "two numbers or two strings",
+                            found: Value::Array(vec![lhs, rhs]),
+                        })
+                    }
+                };
+                Value::boolean(ordering.is_some_and(|ordering| match op {
+                    Operator::Gt => ordering.is_gt(),
+                    Operator::Gte => ordering.is_ge(),
+                    Operator::Lt => ordering.is_lt(),
+                    _ => ordering.is_le(),
+                }))
+            }
+            Operator::Concat => match (lhs, rhs) {
+                (Value::String(a), Value::String(b)) => Value::String(a + &b),
+                (Value::Array(mut a), Value::Array(b)) => {
+                    a.extend(b);
+                    Value::Array(a)
+                }
+                (lhs, rhs) => {
+                    return Err(EvalError::Mismatch {
+                        expected: "two strings or two arrays",
+                        found: Value::Array(vec![lhs, rhs]),
+                    })
+                }
+            },
+            Operator::Cons => match rhs {
+                Value::Array(mut items) => {
+                    items.insert(0, lhs);
+                    Value::Array(items)
+                }
+                _ => {
+                    return Err(EvalError::Mismatch {
+                        expected: "an array",
+                        found: rhs,
+                    })
+                }
+            },
+            Operator::Pipe => self.apply(rhs, lhs)?,
+            Operator::And | Operator::Or => unreachable!("Handled above"),
+        })
+    }
+
+    fn boolean(&self, expr: &Expr, locals: &Locals) -> Result<bool, EvalError> {
+        let value = self.eval(expr, locals)?;
+        value.as_boolean().ok_or(EvalError::Mismatch {
+            expected: "a boolean",
+            found: value,
+        })
+    }
+
+    fn literal(&self, lit: &Literal<Expr>, locals: &Locals) -> EvalResult {
+        Ok(match lit {
+            Literal::Array(items) => Value::Array(
+                items
+                    .iter()
+                    .map(|item| self.eval(item, locals))
+                    .collect::<Result<_, _>>()?,
+            ),
+            Literal::Enum(tag, args) => Value::Enum(
+                tag.clone(),
+                args.iter()
+                    .map(|arg| self.eval(arg, locals))
+                    .collect::<Result<_, _>>()?,
+            ),
+            Literal::Number(n) => Value::Number(*n),
+            Literal::Record(fields) => Value::Record(
+                fields
+                    .iter()
+                    .map(|(key, value)| Ok((key.clone(), self.eval(value, locals)?)))
+                    .collect::<Result<_, _>>()?,
+            ),
+            Literal::LStr(parts) => {
+                let mut s = String::new();
+                for part in parts {
+                    match part {
+                        StringPart::Text(text) => s.push_str(text),
+                        StringPart::Value(value) => {
+                            s.push_str(&self.eval(value, locals)?.to_text())
+                        }
+                    }
+                }
+                Value::String(s)
+            }
+        })
+    }
+}
+
+fn number(value: Value) -> Result<f64, EvalError> {
+    match value {
+        Value::Number(n) => Ok(n),
+        _ => Err(EvalError::Mismatch {
+            expected: "a number",
+            found: value,
+        }),
+    }
+}
+
+/// Match a value against a pattern, returning the locals extended with the variables the
+/// pattern binds if it matches
+fn matches(pattern: &Pattern, value: &Value, locals: Locals) -> Option<Locals> {
+    match (pattern, value) {
+        (Pattern::Any, _) => Some(locals),
+        (Pattern::Var(name) | Pattern::Spread(name), _) => Some(bind(locals, name, value.clone())),
+        (Pattern::Type(typ, pattern), _) => {
+            let is_type = match (typ.as_str(), value) {
+                ("Array", Value::Array(_))
+                | ("Function", Value::Function(_))
+                | ("Number", Value::Number(_))
+                | ("String", Value::String(_)) => true,
+                ("Boolean", _) => value.as_boolean().is_some(),
+                ("Undefined", _) => value == &Value::undefined(),
+                _ => false,
+            };
+            if is_type {
+                matches(pattern, value, locals)
+            } else {
+                None
+            }
+        }
+        (Pattern::Literal(Literal::Array(patterns)), Value::Array(items)) => {
+            match patterns
+                .iter()
+                .position(|pattern| matches!(pattern, Pattern::Spread(_)))
+            {
+                Some(spread) => {
+                    let after = patterns.len() - spread - 1;
+                    if items.len() < patterns.len() - 1 {
+                        return None;
+                    }
+                    let rest = Value::Array(items[spread..items.len() - after].to_vec());
+                    let items = items[..spread]
+                        .iter()
+                        .chain(std::iter::once(&rest))
+                        .chain(&items[items.len() - after..]);
+                    patterns
+                        .iter()
+                        .zip(items)
+                        .try_fold(locals, |locals, (pattern, item)| {
+                            matches(pattern, item, locals)
+                        })
+                }
+                None if patterns.len() == items.len() => patterns
+                    .iter()
+                    .zip(items)
+                    .try_fold(locals, |locals, (pattern, item)| {
+                        matches(pattern, item, locals)
+                    }),
+                None => None,
+            }
+        }
+        (Pattern::Literal(Literal::Enum(tag, patterns)), Value::Enum(value_tag, args))
+            if tag == value_tag && patterns.len() == args.len() =>
+        {
+            patterns
+                .iter()
+                .zip(args)
+                .try_fold(locals, |locals, (pattern, arg)| {
+                    matches(pattern, arg, locals)
+                })
+        }
+        (Pattern::Literal(Literal::Number(n)), Value::Number(m)) if n == m => Some(locals),
+        (Pattern::Literal(Literal::Record(fields)), Value::Record(entries)) => fields
+            .iter()
+            .try_fold(locals, |locals, (key, pattern)| match pattern {
+                Pattern::Spread(name) => {
+                    let rest = entries
+                        .iter()
+                        .filter(|(k, _)| {
+                            !fields.iter().any(|(key, pattern)| {
+                                key == k && !matches!(pattern, Pattern::Spread(_))
+                            })
+                        })
+                        .cloned()
+                        .collect();
+                    Some(bind(locals, name, Value::Record(rest)))
+                }
+                _ => matches(pattern, value.field(key)?, locals),
+            }),
+        (Pattern::Literal(Literal::LStr(parts)), Value::String(s)) => match parts.as_simple() {
+            Some(text) => (&text == s).then_some(locals),
+            None => {
+                let mut captures = Vec::new();
+                if !match_string(parts, s, &mut captures) {
+                    return None;
+                }
+                captures
+                    .into_iter()
+                    .try_fold(locals, |locals, (pattern, capture)| {
+                        matches(pattern, &Value::String(capture.to_string()), locals)
+                    })
+            }
+        },
+        _ => None,
+    }
+}
+
+/// Match a string against the parts of a string pattern, capturing the shortest text possible
+/// for each nested pattern
+fn match_string<'p, 's>(
+    parts: &'p [StringPart<Pattern>],
+    s: &'s str,
+    captures: &mut Vec<(&'p Pattern, &'s str)>,
+) -> bool {
+    match parts.split_first() {
+        None => s.is_empty(),
+        Some((StringPart::Text(text), rest)) => s
+            .strip_prefix(text.as_str())
+            .is_some_and(|s| match_string(rest, s, captures)),
+        Some((StringPart::Value(pattern), rest)) => {
+            for (i, _) in s.char_indices().chain(std::iter::once((s.len(), ' '))) {
+                captures.push((pattern, &s[..i]));
+                if match_string(rest, &s[i..], captures) {
+                    return true;
+                }
+                captures.pop();
+            }
+            false
+        }
+    }
+}
@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+
+use ast::Literal;
+use line_col::LineColLookup;
+use parser::lower_ast::ToHIR;
+
+use super::{eval, EvalError};
+use crate::value::Value;
+
+fn parse(input: &str) -> ast::Expr {
+    parser::parse_expr_ast(input)
+        .unwrap_or_else(|errors| panic!("Failed to parse {:?}: {:?}", input, errors))
+        .to_higher_ast(&LineColLookup::new(input))
+}
+fn eval_with(globals: &HashMap<String, Value>, input: &str) -> Result<Value, EvalError> {
+    eval(&parse(input), globals)
+}
+fn check(input: &str, expected: &str) {
+    match eval_with(&HashMap::new(), input) {
+        Ok(value) => assert_eq!(value.to_string(), expected, "evaluating {:?}", input),
+        Err(e) => panic!("Failed to evaluate {:?}: {}", input, e),
+    }
+}
+fn check_error(input: &str, expected: &str) {
+    match eval_with(&HashMap::new(), input) {
+        Ok(value) => panic!("Expected {:?} to fail, but got {}", input, value),
+        Err(e) => assert_eq!(e.to_string(), expected),
+    }
+}
+
+#[test]
+fn literals() {
+    check("[1, \"two\", #just 3]", "[1, \"two\", #just 3]");
+    check("{ a: 1, b: { c: #true } }", "{ a: 1, b: { c: #true } }");
+    check("let x = 2; \"x is ${x}\"", "\"x is 2\"");
+}
+
+#[test]
+fn operators() {
+    check("1 + 2 * 3 - 4 / 2", "5");
+    check("7 % 4", "3");
+    check("\"a\" < \"b\"", "#true");
+    check("[1, 2] == [1, 2]", "#true");
+    check("{ a: 1, b: 2 } == { b: 2, a: 1 }", "#true");
+    check("(#just 1) != (#just 1)", "#false");
+    check("\"ab\" <> \"cd\"", "\"abcd\"");
+    check("[1] <> [2, 3]", "[1, 2, 3]");
+    check("-1", "-1");
+    check("2 ^ 3 ^ 2", "512");
+    check("1 :: [2, 3]", "[1, 2, 3]");
+    check("1 < 2 & 2 < 1 | 1 == 1", "#true");
+}
+
+#[test]
+fn logical_operators_short_circuit() {
+    use ast::{Expr, Operator};
+    let unbound = || Expr::var("unbound");
+    let globals = HashMap::new();
+    let expr = Expr::binop(
+        Expr::literal(Literal::Enum("false".to_string(), vec![])),
+        Operator::And,
+        unbound(),
+    );
+    assert_eq!(eval(&expr, &globals), Ok(Value::boolean(false)));
+    let expr = Expr::binop(
+        Expr::literal(Literal::Enum("true".to_string(), vec![])),
+        Operator::Or,
+        unbound(),
+    );
+    assert_eq!(eval(&expr, &globals), Ok(Value::boolean(true)));
+    let expr = Expr::binop(
+        Expr::literal(Literal::Enum("true".to_string(), vec![])),
+        Operator::And,
+        unbound(),
+    );
+    assert_eq!(
+        eval(&expr, &globals),
+        Err(EvalError::UnboundVar("unbound".to_string()))
+    );
+}
+
+#[test]
+fn functions() {
+    check("(fun x y -> x - y) 5 2", "3");
+    check("let sub = fun x y -> x - y; let dec = sub 10; dec 3", "7");
+    check("let x = 1; let f = fun y -> x + y; let x = 10; f 1", "2");
+    check("5 |> (fun x -> x * 2)", "10");
+    check("(fun [a, b] -> a + b) [1, 2]", "3");
+}
+
+#[test]
+fn placeholders() {
+    check("let inc = _ + 1; inc 2", "3");
+    check("let get = _.a; get { a: 4 }", "4");
+    check("let half = _ / 2; 3 |> half", "1.5");
+    check("let pair = [_, _]; pair 1 2", "[1, 2]");
+    check("let wrap = { a: _, b: 2 }; wrap 1", "{ a: 1, b: 2 }");
+    check("let greet = \"hi ${_}!\"; greet \"you\"", "\"hi you!\"");
+    check("let set = { (_) | a: _ }; set { a: 1 } 2", "{ a: 2 }");
+    check("let just = #just _; just 1", "#just 1");
+    check("let f = fun x y -> x - y; let g = f _ 1; g 3", "2");
+}
+
+#[test]
+fn conditionals() {
+    check("if 1 > 2 then \"yes\" else \"no\"", "\"no\"");
+    check_error("if 1 then 2 else 3", "expected a boolean, found `1`");
+}
+
+#[test]
+fn record_updates() {
+    check("let r = { a: 1, b: 2 }; { r | b: 3 }", "{ a: 1, b: 3 }");
+    check("let a = 4; { ({ a: 1 }) | a }", "{ a: 4 }");
+    check_error("{ ({ a: 1 }) | b: 2 }", "`{ a: 1 }` has no field `b`");
+}
+
+#[test]
+fn assertions() {
+    check("let x = 2; assert x > 1; x * 2", "4");
+    check_error("assert 1 > 2; 3", "assertion failed");
+    check_error("assert 1; 3", "expected a boolean, found `1`");
+}
+
+#[test]
+fn switch() {
+    let classify = "fun x -> switch x on
+        case 0 -> \"zero\"
+        case #just n if n > 0 -> \"positive\"
+        case #just _ -> \"other\"
+        case [first, second] -> second
+        case { a } -> a
+        case @Number n -> n * 2";
+    let globals = HashMap::from([(
+        "classify".to_string(),
+        eval_with(&HashMap::new(), classify).unwrap(),
+    )]);
+    for (input, expected) in [
+        ("classify 0", "\"zero\""),
+        ("classify (#just 1)", "\"positive\""),
+        ("classify (#just -1)", "\"other\""),
+        ("classify [1, 2]", "2"),
+        ("classify { a: 5 }", "5"),
+        ("classify 4", "8"),
+    ] {
+        assert_eq!(
+            eval_with(&globals, input).unwrap().to_string(),
+            expected,
+            "evaluating {:?}",
+            input
+        );
+    }
+    assert_eq!(
+        eval_with(&globals, "classify []").unwrap_err(),
+        EvalError::NoMatch(Value::Array(vec![]))
+    );
+}
+
+#[test]
+fn spread_patterns() {
+    use ast::{Expr, Pattern};
+    let expr = Expr::switch(
+        Expr::literal(vec![Expr::literal(1), Expr::literal(2), Expr::literal(3)]),
+        vec![(
+            Literal::Array(vec![
+                Pattern::Var("first".to_string()),
+                Pattern::Spread("rest".to_string()),
+            ])
+            .into(),
+            None,
+            Expr::var("rest"),
+        )],
+    );
+    assert_eq!(eval(&expr, &HashMap::new()), Ok(Value::from(vec![2, 3])));
+    check("let [x, ..xs] = [1, 2, 3]; xs", "[2, 3]");
+    check("(fun [a, ..mid, z] -> mid) [1, 2, 3, 4]", "[2, 3]");
+    check(
+        "let { a, ..others } = { a: 1, b: 2, c: 3 }; others",
+        "{ b: 2, c: 3 }",
+    );
+    check(
+        "switch { name: \"ren\" } on case { name, ..rest } -> rest",
+        "{}",
+    );
+}
+
+#[test]
+fn string_patterns() {
+    use ast::{expr::StringPart, Expr, Pattern};
+    let greeting = Literal::LStr(vec![
+        StringPart::Text("Hello, ".to_string()),
+        StringPart::Value(Pattern::Var("name".to_string())),
+        StringPart::Text("!".to_string()),
+    ]);
+    let expr = Expr::lambda(
+        vec![Pattern::Var("s".to_string())],
+        Expr::switch(
+            Expr::var("s"),
+            vec![
+                (greeting.into(), None, Expr::var("name")),
+                (Pattern::Any, None, Expr::literal("")),
+            ],
+        ),
+    );
+    let globals = HashMap::from([("greeted".to_string(), eval(&expr, &HashMap::new()).unwrap())]);
+    assert_eq!(
+        eval_with(&globals, "greeted \"Hello, Ren!\""),
+        Ok(Value::from("Ren"))
+    );
+    assert_eq!(
+        eval_with(&globals, "greeted \"Hello, !\""),
+        Ok(Value::from(""))
+    );
+    assert_eq!(eval_with(&globals, "greeted \"Hi!\""), Ok(Value::from("")));
+}
+
+#[test]
+fn recursion_through_globals() {
+    let mut globals = HashMap::new();
+    let fact = eval_with(&globals, "fun n -> if n <= 1 then 1 else n * fact (n - 1)").unwrap();
+    globals.insert("fact".to_string(), fact);
+    assert_eq!(eval_with(&globals, "fact 5").unwrap(), Value::from(120));
+}
+
+#[test]
+fn errors() {
+    check_error("x + 1", "unbound variable `x`");
+    check_error("1 2", "`1` is not a function");
+    check_error("{ a: 1 }.b", "`{ a: 1 }` has no field `b`");
+    check_error("1 + \"a\"", "expected a number, found `\"a\"`");
+    check_error("let [a] = [1, 2]; a", "no pattern matches `[1, 2]`");
+}
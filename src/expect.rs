@@ -0,0 +1,47 @@
+use std::io::Write;
+
+use ast::{pretty::Pretty, Decl};
+use parser::lower_ast::ToHIR;
+
+use crate::{
+    eval::{expect, ModuleGlobals},
+    CliError,
+};
+
+/// Evaluate every `expect` declaration in the module, writing whether each one passed or
+/// failed. An error is returned if any of them failed.
+pub(crate) fn run_expectations<W: Write>(input: &str, mut w: W) -> Result<(), CliError> {
+    let module = parser::parse_module_ast(input)
+        .map_err(parser::StmtError::Syntax)?
+        .to_higher_ast(&line_col::LineColLookup::new(input));
+    let globals = ModuleGlobals::new(module.declarations());
+    let fixities = parser::fixity::declared_in(input);
+    let (mut passed, mut failed) = (0, 0);
+    for decl in module.declarations() {
+        let Decl::Expect { meta, expr } = decl else {
+            continue;
+        };
+        let ((line, col), _): ((usize, usize), (usize, usize)) = meta.get_span().into();
+        let source = expr.pretty_with(usize::MAX, &fixities);
+        match expect(expr, &globals) {
+            Ok(true) => {
+                passed += 1;
+                writeln!(w, "PASS {}:{} {}", line, col, source)?;
+            }
+            Ok(false) => {
+                failed += 1;
+                writeln!(w, "FAIL {}:{} {}", line, col, source)?;
+            }
+            Err(e) => {
+                failed += 1;
+                writeln!(w, "FAIL {}:{} {}\n  {}", line, col, source, e)?;
+            }
+        }
+    }
+    writeln!(w, "{} passed, {} failed", passed, failed)?;
+    if failed == 0 {
+        Ok(())
+    } else {
+        Err(CliError::Failed(failed))
+    }
+}
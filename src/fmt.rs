@@ -0,0 +1,53 @@
+use std::{
+    io::{Read, Write},
+    path::PathBuf,
+};
+
+use crate::CliError;
+
+/// Format each of the files in place. If `check` is set, the files are left unchanged and
+/// any which are not already formatted are reported as an error instead.
+pub(crate) fn format_files(files: &[PathBuf], check: bool, width: usize) -> Result<(), CliError> {
+    let mut unformatted = Vec::new();
+    for path in files {
+        let input = std::fs::read_to_string(path)?;
+        let output =
+            format_source(&input, width).map_err(|e| CliError::File(path.clone(), Box::new(e)))?;
+        if output != input {
+            if check {
+                unformatted.push(path.clone());
+            } else {
+                std::fs::write(path, output)?;
+            }
+        }
+    }
+    if unformatted.is_empty() {
+        Ok(())
+    } else {
+        Err(CliError::Unformatted(unformatted))
+    }
+}
+
+/// Format the source read from `r`, writing it to `w`. If `check` is set, nothing is written
+/// and an error is returned if the source is not already formatted.
+pub(crate) fn format_stream<R: Read, W: Write>(
+    mut r: R,
+    mut w: W,
+    check: bool,
+    width: usize,
+) -> Result<(), CliError> {
+    let mut input = String::new();
+    r.read_to_string(&mut input)?;
+    let output = format_source(&input, width)?;
+    if !check {
+        w.write_all(output.as_bytes())?;
+    } else if output != input {
+        return Err(CliError::Unformatted(vec![PathBuf::from("<stdin>")]));
+    }
+    Ok(())
+}
+
+fn format_source(input: &str, width: usize) -> Result<String, CliError> {
+    parser::format::format_module(input, width)
+        .map_err(|errors| parser::StmtError::Syntax(errors).into())
+}
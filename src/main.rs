@@ -1,12 +1,18 @@
-#[allow(dead_code)]
+mod eval;
 mod value;
 
 mod repl;
 
+mod build;
 mod cli;
+mod expect;
+mod fmt;
+mod output;
+mod project;
 #[cfg(test)]
 mod test;
 
+use output::OutputFormat;
 use repl::Modes as ReplModes;
 
 #[cfg(feature = "reqwest")]
@@ -21,7 +27,17 @@ enum CliError {
     Repl(::rustyline::error::ReadlineError),
     Parse(::clap::Error),
     Io(std::io::Error),
-    Stmt(&'static str),
+    Stmt(parser::StmtError),
+    /// An error caused by the contents of a file
+    File(std::path::PathBuf, Box<CliError>),
+    /// Files which `fmt --check` found were not formatted
+    Unformatted(Vec<std::path::PathBuf>),
+    /// The number of `expect` declarations which failed
+    Failed(usize),
+    /// Modules which import each other, each imported by the one before it
+    Cycle(Vec<std::path::PathBuf>),
+    /// The errors found by checking a module, such as unbound variables
+    Check(Vec<String>),
 }
 impl From<::rustyline::error::ReadlineError> for CliError {
     fn from(e: ::rustyline::error::ReadlineError) -> Self {
@@ -40,6 +56,11 @@ impl From<std::io::Error> for CliError {
 }
 impl From<&'static str> for CliError {
     fn from(e: &'static str) -> Self {
+        Self::Stmt(e.into())
+    }
+}
+impl From<parser::StmtError> for CliError {
+    fn from(e: parser::StmtError) -> Self {
         Self::Stmt(e)
     }
 }
@@ -50,12 +71,29 @@ impl core::fmt::Display for CliError {
             CliError::Parse(e) => e.fmt(f),
             CliError::Io(e) => e.fmt(f),
             CliError::Stmt(s) => s.fmt(f),
+            CliError::File(path, e) => write!(f, "{}: {}", path.display(), e),
+            CliError::Unformatted(paths) => {
+                write!(f, "the following files are not formatted:")?;
+                for path in paths {
+                    write!(f, "\n  {}", path.display())?;
+                }
+                Ok(())
+            }
+            CliError::Failed(count) => write!(f, "{} expectation(s) failed", count),
+            CliError::Cycle(paths) => {
+                let paths = paths
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>();
+                write!(f, "import cycle: {}", paths.join(" -> "))
+            }
+            CliError::Check(errors) => write!(f, "{}", errors.join("\n")),
         }
     }
 }
 impl core::fmt::Debug for CliError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        <Self as core::fmt::Display>::fmt(&self, f)
+        <Self as core::fmt::Display>::fmt(self, f)
         // match self {
         //     CliError::Repl(e) => e.fmt(f),
         //     CliError::Io(e) => e.fmt(f),
@@ -68,6 +106,8 @@ impl std::error::Error for CliError {
         match self {
             CliError::Repl(e) => Some(e),
             CliError::Io(e) => Some(e),
+            CliError::Stmt(e) => Some(e),
+            CliError::File(_, e) => Some(e.as_ref()),
             _ => None,
         }
     }
@@ -81,6 +121,7 @@ fn main() -> Result<(), CliError> {
             infile,
             ofile,
             format,
+            stmt,
             stdinput,
         } => {
             let input = if let Some(ipath) = infile {
@@ -89,12 +130,37 @@ fn main() -> Result<(), CliError> {
                 stdinput.unwrap()
             };
             let mut output = Vec::new();
-            parser::parse_stmt_ast(&input)
-                .and_then(|(s, ll)| format.handle_stmt(&mut output, s, &ll))?;
+            if stmt {
+                format.write_stmt(&mut output, &input)?;
+            } else {
+                format.write_module(&mut output, &input)?;
+            }
             if let Some(opath) = ofile {
                 std::fs::write(opath, output)?
+            } else {
+                std::io::Write::write_all(&mut std::io::stdout(), &output)?
             }
         }
+        cli::Cmd::Fmt {
+            files,
+            check,
+            width,
+        } => {
+            if files.is_empty() {
+                fmt::format_stream(std::io::stdin(), std::io::stdout(), check, width)?
+            } else {
+                fmt::format_files(&files, check, width)?
+            }
+        }
+        cli::Cmd::Test { file } => {
+            let input = std::fs::read_to_string(&file)?;
+            expect::run_expectations(&input, std::io::stdout())
+                .map_err(|e| CliError::File(file, Box::new(e)))?
+        }
+        cli::Cmd::Build { entry, out_dir } => {
+            build::build_project(&entry, out_dir.as_deref(), std::io::stdout())?
+        }
+        cli::Cmd::Lsp => ren_lsp::serve_stdio()?,
     };
     Ok(())
     //println!("{:?}", parser::parse(SAMPLE));
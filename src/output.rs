@@ -0,0 +1,104 @@
+use std::io::Write;
+
+use ast::pretty::{Pretty, DEFAULT_WIDTH};
+use clap::ValueEnum;
+use parser::{lower_ast::ToHIR, REPLStmt};
+
+use crate::CliError;
+
+/// The formats which the `parse` subcommand can write its output in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub(crate) enum OutputFormat {
+    /// The rowan syntax tree
+    #[default]
+    Rowan,
+    /// The debug representation of the higher AST
+    Higher,
+    /// The higher AST as compact JSON
+    Json,
+    /// The higher AST as indented JSON
+    JsonPretty,
+    /// Pretty-printed Ren source
+    Ren,
+}
+
+impl OutputFormat {
+    /// Parse and lower a whole module, then write it in this format. The rowan syntax tree is
+    /// written even if the module has syntax errors.
+    pub(crate) fn write_module<W: Write>(self, mut w: W, input: &str) -> Result<(), CliError> {
+        if self == Self::Rowan {
+            writeln!(w, "{}", parser::parse_module(input).debug_tree())?;
+            return Ok(());
+        }
+        let module = parser::parse_module_ast(input)
+            .map_err(parser::StmtError::Syntax)?
+            .to_higher_ast(&line_col::LineColLookup::new(input));
+        match self {
+            Self::Rowan => unreachable!("the syntax tree is written without lowering it"),
+            Self::Higher => writeln!(w, "{:?}", module)?,
+            Self::Json | Self::JsonPretty => {
+                module
+                    .to_json_writer(&mut w, self == Self::JsonPretty)
+                    .map_err(std::io::Error::from)?;
+                writeln!(w)?
+            }
+            Self::Ren => write!(w, "{}", module.pretty(DEFAULT_WIDTH))?,
+        }
+        Ok(())
+    }
+
+    /// Parse and lower a single REPL statement, then write it in this format. The rowan syntax
+    /// tree is written even if the statement has syntax errors.
+    pub(crate) fn write_stmt<W: Write>(self, mut w: W, input: &str) -> Result<(), CliError> {
+        if self == Self::Rowan {
+            match parser::parse_repl_stmt(input)? {
+                REPLStmt::Decl(parsed) | REPLStmt::Expr(parsed) | REPLStmt::Import(parsed) => {
+                    writeln!(w, "{}", parsed.debug_tree())?
+                }
+                REPLStmt::Comment(_) | REPLStmt::Empty => {}
+            }
+            return Ok(());
+        }
+        let (stmt, line_lookup) = parser::parse_stmt_ast(input)?;
+        match (self, stmt) {
+            (_, REPLStmt::Comment(_) | REPLStmt::Empty) => return Ok(()),
+            (Self::Rowan, _) => unreachable!("the syntax tree is written without lowering it"),
+            (Self::Higher, REPLStmt::Decl(decl)) => {
+                writeln!(w, "{:?}", decl.to_higher_ast(&line_lookup))?
+            }
+            (Self::Higher, REPLStmt::Expr(expr)) => {
+                writeln!(w, "{:?}", expr.to_higher_ast(&line_lookup))?
+            }
+            (Self::Higher, REPLStmt::Import(imp)) => {
+                writeln!(w, "{:?}", imp.to_higher_ast(&line_lookup))?
+            }
+            (Self::Json | Self::JsonPretty, stmt) => {
+                let pretty = self == Self::JsonPretty;
+                match stmt {
+                    REPLStmt::Decl(decl) => decl
+                        .to_higher_ast(&line_lookup)
+                        .to_json_writer(&mut w, pretty),
+                    REPLStmt::Expr(expr) => expr
+                        .to_higher_ast(&line_lookup)
+                        .to_json_writer(&mut w, pretty),
+                    REPLStmt::Import(imp) => imp
+                        .to_higher_ast(&line_lookup)
+                        .to_json_writer(&mut w, pretty),
+                    REPLStmt::Comment(_) | REPLStmt::Empty => Ok(()),
+                }
+                .map_err(std::io::Error::from)?;
+                writeln!(w)?
+            }
+            (Self::Ren, REPLStmt::Decl(decl)) => {
+                writeln!(w, "{}", decl.to_higher_ast(&line_lookup))?
+            }
+            (Self::Ren, REPLStmt::Expr(expr)) => {
+                writeln!(w, "{}", expr.to_higher_ast(&line_lookup))?
+            }
+            (Self::Ren, REPLStmt::Import(imp)) => {
+                writeln!(w, "{}", imp.to_higher_ast(&line_lookup))?
+            }
+        }
+        Ok(())
+    }
+}
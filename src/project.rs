@@ -0,0 +1,162 @@
+use std::{
+    collections::HashMap,
+    path::{Component, Path, PathBuf},
+};
+
+use ast::{
+    module::{
+        import::{Import, Source},
+        Meta,
+    },
+    Module,
+};
+use parser::{lower_ast::ToHIR, Fixities};
+
+use crate::CliError;
+
+/// A module of a project, and the file it was loaded from
+#[derive(Debug)]
+pub(crate) struct ProjectModule {
+    /// The path of the file, relative to the directory of the entry module
+    pub(crate) path: PathBuf,
+    pub(crate) module: Module,
+}
+
+/// Load the module at `entry` and every module it imports, directly or indirectly.
+///
+/// Project imports are resolved relative to the importing file, and package imports relative
+/// to the package directory (`.pkg/` by default) in the directory of the entry module. Each
+/// module's package directory is made relative to the module itself, so the JavaScript it is
+/// compiled to imports packages from the right place. `.ren` is added to paths without an
+/// extension, external imports are skipped, and imports outside the directory of the entry
+/// module are rejected. The modules are returned in topological order, each after every
+/// module it imports, so the entry module is last.
+pub(crate) fn load_project(entry: &Path) -> Result<Vec<ProjectModule>, CliError> {
+    let root = entry.parent().unwrap_or_else(|| Path::new(""));
+    let mut loader = Loader {
+        root: root.to_path_buf(),
+        ..Default::default()
+    };
+    let file_name = entry
+        .file_name()
+        .ok_or_else(|| CliError::File(entry.to_path_buf(), Box::new("not a file".into())))?;
+    loader.load(PathBuf::from(file_name))?;
+    Ok(loader.loaded)
+}
+
+#[derive(Default)]
+struct Loader {
+    root: PathBuf,
+    /// The modules which have been loaded, in topological order
+    loaded: Vec<ProjectModule>,
+    /// The source of each loaded module, to find the fixities it exposes to its importers
+    sources: HashMap<PathBuf, String>,
+    /// The modules being loaded, each imported by the one before it
+    stack: Vec<PathBuf>,
+}
+
+impl Loader {
+    fn load(&mut self, path: PathBuf) -> Result<(), CliError> {
+        if self.sources.contains_key(&path) {
+            return Ok(());
+        }
+        if let Some(start) = self.stack.iter().position(|p| *p == path) {
+            let mut cycle = self.stack.split_off(start);
+            cycle.push(path);
+            return Err(CliError::Cycle(cycle));
+        }
+        let file_error = |e: CliError| CliError::File(path.clone(), Box::new(e));
+        let input =
+            std::fs::read_to_string(self.root.join(&path)).map_err(|e| file_error(e.into()))?;
+        let line_lookup = line_col::LineColLookup::new(&input);
+        // Imports parse the same whatever the fixities of operators are, so the module is
+        // parsed again only if the modules it imports expose any
+        let module = parser::parse_module_ast(&input)
+            .map_err(|errors| file_error(parser::StmtError::Syntax(errors).into()))?
+            .to_higher_ast(&line_lookup);
+
+        // The package directory is relative to the entry module, so is reached from this module
+        // by leaving each of the directories it is in
+        let depth = path.parent().map_or(0, |dir| dir.components().count());
+        let pkg_path = format!("{}{}", "../".repeat(depth), module.meta().pkg_path());
+
+        self.stack.push(path.clone());
+        let mut fixities = Fixities::new();
+        for import in module.all_imports() {
+            if let Some(dependency) = dependency(&path, import, &pkg_path).map_err(file_error)? {
+                self.load(dependency.clone())?;
+                fixities.extend(&parser::fixity::exposed_by(&self.sources[&dependency]));
+            }
+        }
+        self.stack.pop();
+
+        let module = if fixities == Fixities::new() {
+            module
+        } else {
+            parser::parse_module_ast_with(&input, &fixities)
+                .map_err(|errors| file_error(parser::StmtError::Syntax(errors).into()))?
+                .to_higher_ast(&line_lookup)
+        };
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy())
+            .unwrap_or_default();
+        let module = module.with_meta(Meta::new(name, path.display()).with_pkg_path(pkg_path));
+        self.sources.insert(path.clone(), input);
+        self.loaded.push(ProjectModule { path, module });
+        Ok(())
+    }
+}
+
+/// The path of the module imported by the module at `importer`, relative to the project root,
+/// or `None` if the import is not of a Ren module. `pkg_path` is the package directory,
+/// relative to the importer.
+fn dependency(
+    importer: &Path,
+    import: &Import,
+    pkg_path: &str,
+) -> Result<Option<PathBuf>, CliError> {
+    let dir = importer.parent().unwrap_or_else(|| Path::new(""));
+    let path = match import.source {
+        Source::Project => dir.join(&import.path),
+        Source::Package => dir.join(pkg_path).join(&import.path),
+        Source::External => return Ok(None),
+    };
+    let mut path = normalise(&path);
+    // Modules outside the project would be written outside the output directory
+    if !matches!(path.components().next(), Some(Component::Normal(_))) {
+        return Err(CliError::Check(vec![format!(
+            "import {:?} is outside the project",
+            import.path
+        )]));
+    }
+    match path.extension() {
+        None => {
+            path.set_extension("ren");
+        }
+        Some(extension) if extension == "ren" => {}
+        Some(_) => return Ok(None),
+    }
+    Ok(Some(path))
+}
+
+/// Remove `.` components and `..` components which follow a directory, so that every import
+/// of a module resolves to the same path
+fn normalise(path: &Path) -> PathBuf {
+    let mut normalised = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir
+                if matches!(
+                    normalised.components().next_back(),
+                    Some(Component::Normal(_))
+                ) =>
+            {
+                normalised.pop();
+            }
+            component => normalised.push(component),
+        }
+    }
+    normalised
+}
@@ -1,3 +1,4 @@
+#[allow(dead_code)] //XXX
 pub(super) trait ReplCommand {
     fn ident() -> &'static str;
     /// Returns a tuple of (args, description)
@@ -3,7 +3,12 @@ use std::{borrow::Cow, collections::HashMap};
 
 use ast::{Expr, Type};
 
-pub(super) struct Environment {
+use crate::{
+    eval::{EvalError, Globals},
+    value::Value,
+};
+
+pub(crate) struct Environment {
     scopes: Vec<Scope<'static>>,
 }
 impl Default for Environment {
@@ -24,25 +29,44 @@ impl Environment {
     pub fn pop_scope(&mut self) -> bool {
         self.scopes.len() > 1 && self.scopes.pop().is_some()
     }
+    /// Declare a variable or type. Variables may be redeclared, replacing any previous value.
     pub fn push_declaration(&mut self, decl: ast::Decl) {
         match decl {
-            ast::Decl::Let { var, typ, expr, .. } => {
-                if !self.has_variable(&var) {
-                    self.scope_mut().vars.insert(var.into(), (typ, Some(expr)));
-                }
+            ast::Decl::Let {
+                meta, var, expr, ..
             }
-            ast::Decl::Ext { var, typ, .. } => {
-                if !self.has_variable(&var) {
-                    self.scope_mut().vars.insert(var.into(), (typ, None));
-                }
+            | ast::Decl::Infix {
+                meta,
+                op: var,
+                expr,
+                ..
+            } => {
+                self.scope_mut().values.remove(var.as_str());
+                self.scope_mut()
+                    .vars
+                    .insert(var.into(), (meta.get_type().clone(), Some(expr)));
+            }
+            ast::Decl::Ext { meta, var, .. } => {
+                self.scope_mut().values.remove(var.as_str());
+                self.scope_mut()
+                    .vars
+                    .insert(var.into(), (meta.get_type().clone(), None));
             }
             ast::Decl::Type { name, typ, .. } => {
                 if !self.has_type(&name) {
                     self.scope_mut().types.insert(name.into(), typ);
                 }
             }
+            // Tests have no name to declare
+            ast::Decl::Expect { .. } => {}
         };
     }
+    /// Set the evaluated value of a variable
+    pub fn set_value(&mut self, var: &str, value: Value) {
+        self.scope_mut()
+            .values
+            .insert(var.to_string().into(), value);
+    }
     pub fn has_type(&self, type_name: &str) -> bool {
         self.scopes
             .iter()
@@ -64,7 +88,7 @@ impl Environment {
             .find(|Scope { imports, .. }| imports.contains_key(name))
             .is_some()
     }
-    pub fn type_declarations<'a>(&'a self) -> HashMap<&'a str, &'a Type> {
+    pub fn type_declarations(&self) -> HashMap<&str, &Type> {
         self.scopes
             .iter()
             .fold(HashMap::new(), |mut map, Scope { types, .. }| {
@@ -72,7 +96,7 @@ impl Environment {
                 map
             })
     }
-    pub fn variables<'a>(&'a self) -> HashMap<&'a str, (&'a Type, Option<&'a Expr>)> {
+    pub fn variables(&self) -> HashMap<&str, (&Type, Option<&Expr>)> {
         self.scopes
             .iter()
             .fold(HashMap::new(), |mut map, Scope { vars, .. }| {
@@ -80,7 +104,7 @@ impl Environment {
                 map
             })
     }
-    pub fn imports<'a>(&'a self) -> HashMap<&'a str, &'a str> {
+    pub fn imports(&self) -> HashMap<&str, &str> {
         self.scopes
             .iter()
             .fold(HashMap::new(), |mut map, Scope { imports, .. }| {
@@ -101,10 +125,21 @@ impl Environment {
     }
 }
 
+impl Globals for Environment {
+    fn global(&self, name: &str) -> Option<Result<Value, EvalError>> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|Scope { values, .. }| values.get(name).cloned())
+            .map(Ok)
+    }
+}
+
 #[derive(Debug, Default)]
 struct Scope<'a> {
     types: HashMap<Cow<'a, str>, Type>,
     vars: HashMap<Cow<'a, str>, (Type, Option<Expr>)>,
+    values: HashMap<Cow<'a, str>, Value>,
     imports: HashMap<Cow<'a, str>, Cow<'a, str>>,
 }
 impl<'a> Scope<'a> {
@@ -115,6 +150,7 @@ impl<'a> Scope<'a> {
         Self {
             types,
             vars: HashMap::new(),
+            values: HashMap::new(),
             imports: HashMap::new(),
         }
     }
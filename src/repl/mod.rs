@@ -7,7 +7,9 @@ mod command;
 // mod config;
 mod env;
 mod mode;
+pub(crate) use env::Environment;
 pub use mode::Modes;
+#[allow(clippy::derivable_impls)]
 impl Default for Modes {
     fn default() -> Self {
         Self::Rowan
@@ -21,6 +23,7 @@ where
     P: AsRef<Path> + ?Sized,
 {
     let mut mode = start_mode;
+    let mut env = Environment::default();
     // `()` can be used when no completer is required
     let mut rl = rustyline::DefaultEditor::new()?;
     if rl.load_history(history_file_path).is_err() {
@@ -35,15 +38,15 @@ where
                 if let Err(e) = rl.add_history_entry(line.as_str()) {
                     eprintln!("{}", e);
                 }
-                if line.starts_with(COMMAND_START) {
-                    if let Err(e) = mode.handle_command(line[COMMAND_START.len()..].trim()) {
+                if let Some(cmd) = line.strip_prefix(COMMAND_START) {
+                    if let Err(e) = mode.handle_command(cmd.trim()) {
                         println!("{}", e);
                     }
                 } else {
                     if let Err(e) = parse_stmt_ast(line.as_str()).and_then(|(stmt, line_lookup)| {
-                        mode.handle_stmt(&mut out, stmt, &line_lookup)
+                        Ok(mode.handle_stmt(&mut out, stmt, &line_lookup, &mut env)?)
                     }) {
-                        println!("Error parsing line: {:?}", e);
+                        println!("Error parsing line:\n{}", e);
                     }
                 }
             }
@@ -3,13 +3,30 @@ use parser::{
     REPLStmt,
 };
 
+use super::env::Environment;
+use crate::eval::{eval, expect};
+
+const WRITE_ERROR: &str = "Failed to write output";
+
+/// Write a statement as JSON, followed by a newline
+fn write_json<W, F>(mut w: W, f: F) -> Result<(), &'static str>
+where
+    W: std::io::Write,
+    F: FnOnce(&mut W) -> ::serde_json::Result<()>,
+{
+    f(&mut w).map_err(|_| WRITE_ERROR)?;
+    writeln!(w).map_err(|_| WRITE_ERROR)
+}
+
 pub(super) trait ReplMode {
+    #[allow(dead_code)] //XXX
     fn name() -> &'static str;
-    fn handle_command<'c, 'r>(cmd: &'c str, mode: &'r mut Modes) -> Result<(), &'c str>;
+    fn handle_command<'c>(cmd: &'c str, mode: &mut Modes) -> Result<(), &'c str>;
     fn handle_stmt<W: std::io::Write>(
         w: W,
         stmt: REPLStmt<Decl, Expr, Import>,
         line_lookup: &::line_col::LineColLookup,
+        env: &mut Environment,
     ) -> Result<(), &'static str>;
 }
 
@@ -20,9 +37,9 @@ macro_rules! make_modes {
             $($cmd:ty),* $(,)?
         }$(,)?
         $(handle_stmt: )? $(stmt =>)? {
-            $(REPLStmt::)?Decl($decl_id:ident $(,$d_l_lookup:ident)?) => $decl_body:expr,
-            $(REPLStmt::)?Expr($expr_id:ident $(,$e_l_lookup:ident)?) => $expr_body:expr,
-            $(REPLStmt::)?Import($imp_id:ident $(,$i_l_lookup:ident)?) => $imp_body:expr,
+            $(REPLStmt::)?Decl($decl_id:ident $(,$d_l_lookup:ident $(,$d_env:ident $(,$d_w:ident)?)?)?) => $decl_body:expr,
+            $(REPLStmt::)?Expr($expr_id:ident $(,$e_l_lookup:ident $(,$e_env:ident $(,$e_w:ident)?)?)?) => $expr_body:expr,
+            $(REPLStmt::)?Import($imp_id:ident $(,$i_l_lookup:ident $(,$i_env:ident $(,$i_w:ident)?)?)?) => $imp_body:expr,
         }
     }$(,)?)+) => {
         #[derive(Clone, Copy, Debug)]
@@ -56,14 +73,14 @@ macro_rules! make_modes {
                 }
                 return true;
             }
-            pub fn handle_command<'r, 'c>(&'r mut self, cmd: &'c str) -> Result<(), &'c str> {
+            pub fn handle_command<'c>(&mut self, cmd: &'c str) -> Result<(), &'c str> {
                 match &self {
                     $(Self::$name => $name::handle_command(cmd, self),)+
                 }
             }
-            pub fn handle_stmt<W: std::io::Write>(&self, w: W, stmt: REPLStmt<Decl, Expr, Import>, line_lookup: &::line_col::LineColLookup) -> Result<(), &'static str> {
+            pub fn handle_stmt<W: std::io::Write>(&self, w: W, stmt: REPLStmt<Decl, Expr, Import>, line_lookup: &::line_col::LineColLookup, env: &mut Environment) -> Result<(), &'static str> {
                 match &self {
-                    $(Self::$name => $name::handle_stmt(w, stmt, line_lookup),)+
+                    $(Self::$name => $name::handle_stmt(w, stmt, line_lookup, env),)+
                 }
             }
         }
@@ -74,7 +91,7 @@ macro_rules! make_modes {
                 fn name() -> &'static str {
                     $display
                 }
-                fn handle_command<'c, 'r>(cmd: &'c str, mode: &'r mut Modes) -> Result<(), &'c str> {
+                fn handle_command<'c>(cmd: &'c str, mode: &mut Modes) -> Result<(), &'c str> {
                     #[allow(unused_imports)]
                     use crate::repl::command::ReplCommand;
                     if "help".starts_with(cmd) {
@@ -96,22 +113,22 @@ r"Commands:
                         }
                     })*
 
-                    if cmd.starts_with("mode") {
-                        if cmd.len() <= 4 {
+                    if let Some(new_mode) = cmd.strip_prefix("mode") {
+                        if new_mode.is_empty() {
                             println!("Current mode: {}", $display);
                             return Ok(());
-                        } else if mode.switch_mode(&cmd[4..].trim()) {
+                        } else if mode.switch_mode(new_mode.trim()) {
                             return Ok(());
                         }
                     }
 
                     Err("Unknown command")
                 }
-                fn handle_stmt<W: std::io::Write>(#[allow(unused_variables)]w: W, stmt: REPLStmt<Decl, Expr, Import>, line_lookup: &::line_col::LineColLookup) -> Result<(), &'static str> {
-                    match (stmt, line_lookup, ()) {
-                        (REPLStmt::Decl($decl_id), $($d_l_lookup,)? ..) => $decl_body,
-                        (REPLStmt::Expr($expr_id), $($e_l_lookup,)? ..) => $expr_body,
-                        (REPLStmt::Import($imp_id), $($i_l_lookup,)? ..) => $imp_body,
+                fn handle_stmt<W: std::io::Write>(mut w: W, stmt: REPLStmt<Decl, Expr, Import>, line_lookup: &::line_col::LineColLookup, env: &mut Environment) -> Result<(), &'static str> {
+                    match (stmt, line_lookup, env, &mut w) {
+                        (REPLStmt::Decl($decl_id), $($d_l_lookup, $($d_env, $($d_w,)?)?)? ..) => $decl_body,
+                        (REPLStmt::Expr($expr_id), $($e_l_lookup, $($e_env, $($e_w,)?)?)? ..) => $expr_body,
+                        (REPLStmt::Import($imp_id), $($i_l_lookup, $($i_env, $($i_w,)?)?)? ..) => $imp_body,
                         _ => Ok(()),
                     }
                 }
@@ -125,74 +142,106 @@ make_modes! {
         "rowan AST",
         commands: {},
         {
-            Decl(decl) => Ok(println!("{:#?}", decl)),
-            Expr(expr) => Ok(println!("{:#?}", expr)),
-            Import(imp) => Ok(println!("{:#?}", imp)),
+            Decl(decl, _line_lookup, _env, w) => {
+                writeln!(w, "{:#?}", decl).map_err(|_| WRITE_ERROR)
+            },
+            Expr(expr, _line_lookup, _env, w) => {
+                writeln!(w, "{:#?}", expr).map_err(|_| WRITE_ERROR)
+            },
+            Import(imp, _line_lookup, _env, w) => {
+                writeln!(w, "{:#?}", imp).map_err(|_| WRITE_ERROR)
+            },
         }
     }
     Higher {
         "higher AST",
         commands: {},
         {
-            Decl(decl, line_lookup) => Ok(println!("{:?}", decl.to_higher_ast(line_lookup))),
-            Expr(expr, line_lookup) => Ok(println!("{:?}", expr.to_higher_ast(line_lookup))),
-            Import(imp, line_lookup) => Ok(println!("{:?}", imp.to_higher_ast(line_lookup))),
+            Decl(decl, line_lookup, _env, w) => {
+                writeln!(w, "{:?}", decl.to_higher_ast(line_lookup)).map_err(|_| WRITE_ERROR)
+            },
+            Expr(expr, line_lookup, _env, w) => {
+                writeln!(w, "{:?}", expr.to_higher_ast(line_lookup)).map_err(|_| WRITE_ERROR)
+            },
+            Import(imp, line_lookup, _env, w) => {
+                writeln!(w, "{:?}", imp.to_higher_ast(line_lookup)).map_err(|_| WRITE_ERROR)
+            },
+        }
+    }
+    Eval {
+        "eval",
+        commands: {},
+        {
+            Decl(decl, line_lookup, env, w) => {
+                let decl = decl.to_higher_ast(line_lookup);
+                match &decl {
+                    ast::Decl::Let { var, expr, .. }
+                    | ast::Decl::Infix {
+                        op: var, expr, ..
+                    } => match eval(expr, env) {
+                        Ok(value) => {
+                            writeln!(w, "{} = {}", var, value).map_err(|_| WRITE_ERROR)?;
+                            let var = var.clone();
+                            env.push_declaration(decl);
+                            env.set_value(&var, value);
+                        }
+                        Err(e) => writeln!(w, "Error: {}", e).map_err(|_| WRITE_ERROR)?,
+                    },
+                    ast::Decl::Ext { var, .. } => {
+                        writeln!(w, "Error: external `{}` cannot be evaluated", var)
+                            .map_err(|_| WRITE_ERROR)?
+                    }
+                    ast::Decl::Type { .. } => env.push_declaration(decl),
+                    ast::Decl::Expect { expr, .. } => match expect(expr, env) {
+                        Ok(true) => writeln!(w, "Passed").map_err(|_| WRITE_ERROR)?,
+                        Ok(false) => writeln!(w, "Failed").map_err(|_| WRITE_ERROR)?,
+                        Err(e) => writeln!(w, "Failed: {}", e).map_err(|_| WRITE_ERROR)?,
+                    },
+                }
+                Ok(())
+            },
+            Expr(expr, line_lookup, env, w) => {
+                match eval(&expr.to_higher_ast(line_lookup), env) {
+                    Ok(value) => writeln!(w, "{}", value),
+                    Err(e) => writeln!(w, "Error: {}", e),
+                }
+                .map_err(|_| WRITE_ERROR)
+            },
+            Import(_imp, _line_lookup, _env, w) => {
+                writeln!(w, "Error: imports cannot be evaluated").map_err(|_| WRITE_ERROR)
+            },
+        }
+    }
+    Json {
+        "json",
+        commands: {},
+        {
+            Decl(decl, line_lookup, _env, w) => {
+                write_json(w, |w| decl.to_higher_ast(line_lookup).to_json_writer(w, false))
+            },
+            Expr(expr, line_lookup, _env, w) => {
+                write_json(w, |w| expr.to_higher_ast(line_lookup).to_json_writer(w, false))
+            },
+            Import(imp, line_lookup, _env, w) => {
+                write_json(w, |w| imp.to_higher_ast(line_lookup).to_json_writer(w, false))
+            },
+        }
+    }
+    JsonPretty {
+        "json (pretty)",
+        commands: {},
+        {
+            Decl(decl, line_lookup, _env, w) => {
+                write_json(w, |w| decl.to_higher_ast(line_lookup).to_json_writer(w, true))
+            },
+            Expr(expr, line_lookup, _env, w) => {
+                write_json(w, |w| expr.to_higher_ast(line_lookup).to_json_writer(w, true))
+            },
+            Import(imp, line_lookup, _env, w) => {
+                write_json(w, |w| imp.to_higher_ast(line_lookup).to_json_writer(w, true))
+            },
         }
     }
-    // Json {
-    //     "json",
-    //     commands: {},
-    //     {
-    //         Decl(decl, line_lookup) => {
-    //             if let Err(e) = decl.to_higher_ast(line_lookup).to_json_writer(std::io::stdout(), false) {
-    //                 println!("{}", e);
-    //             }
-    //             print!("\n"); // Force flush
-    //             Ok(())
-    //         },
-    //         Expr(expr, line_lookup) => {
-    //             if let Err(e) = expr.to_higher_ast(line_lookup).to_json_writer(std::io::stdout(), false) {
-    //                 println!("{}", e);
-    //             }
-    //             print!("\n"); // Force flush
-    //             Ok(())
-    //         },
-    //         Import(imp, line_lookup) => {
-    //             if let Err(e) = imp.to_higher_ast(line_lookup).to_json_writer(std::io::stdout(), false) {
-    //                 println!("{}", e);
-    //             }
-    //             print!("\n"); // Force flush
-    //             Ok(())
-    //         },
-    //     }
-    // }
-    // JsonPretty {
-    //     "json (pretty)",
-    //     commands: {},
-    //     {
-    //         Decl(decl, line_lookup) => {
-    //             if let Err(e) = decl.to_higher_ast(line_lookup).to_json_writer(std::io::stdout(), true) {
-    //                 println!("{}", e);
-    //             }
-    //             print!("\n"); // Force flush
-    //             Ok(())
-    //         },
-    //         Expr(expr, line_lookup) => {
-    //             if let Err(e) = expr.to_higher_ast(line_lookup).to_json_writer(std::io::stdout(), true) {
-    //                 println!("{}", e);
-    //             }
-    //             print!("\n"); // Force flush
-    //             Ok(())
-    //         },
-    //         Import(imp, line_lookup) => {
-    //             if let Err(e) = imp.to_higher_ast(line_lookup).to_json_writer(std::io::stdout(), true) {
-    //                 println!("{}", e);
-    //             }
-    //             print!("\n"); // Force flush
-    //             Ok(())
-    //         },
-    //     }
-    // }
 }
 // pub(super) struct Rowan;
 // impl ReplMode for Rowan {
@@ -4,3 +4,344 @@ fn parse_remote_reference_file() {
     let parsed = super::parse_remote_file("https://raw.githubusercontent.com/ren-lang/compiler/dd75310b42fc34b04f3b40af27333a4a06f62d73/reference/syntax.ren").expect("Failed reading from remote file");
     expect_test::expect_file!["./syntax.ren.parsed"].assert_eq(&parsed.debug_tree());
 }
+
+#[test]
+fn eval_mode_keeps_bindings() {
+    use crate::{repl::Environment, ReplModes};
+    let mut env = Environment::default();
+    let mut output = Vec::new();
+    for line in [
+        "let double = fun x -> x * 2",
+        "let four = double 2",
+        "double four",
+        "missing",
+    ] {
+        let (stmt, line_lookup) = parser::parse_stmt_ast(line).unwrap();
+        ReplModes::Eval
+            .handle_stmt(&mut output, stmt, &line_lookup, &mut env)
+            .unwrap();
+    }
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "double = <function>\nfour = 4\n8\nError: unbound variable `missing`\n"
+    );
+}
+
+#[test]
+fn parse_writes_lowered_module() {
+    let mut output = Vec::new();
+    crate::OutputFormat::Higher
+        .write_module(
+            &mut output,
+            "import \"./utils\" as Utils exposing { helper }\nimport pkg \"ren/array\" as Array\npub let a = 1\n",
+        )
+        .unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.starts_with("Module("), "{}", output);
+    assert!(output.contains(
+        r#"[Import { source: Project, path: "./utils", alias: ["Utils"], exposing: ["helper"] }, Import { source: Package, path: "ren/array", alias: ["Array"], exposing: [] }]"#
+    ), "{}", output);
+}
+
+#[test]
+fn parse_writes_json_stmt() {
+    use crate::OutputFormat;
+    let mut output = Vec::new();
+    OutputFormat::Json.write_stmt(&mut output, "x").unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "[{\"$\":\"Var\",\"type\":[{\"$\":\"Hole\"}],\"span\":[[1,1],[1,2]],\"comment\":[]},\"x\"]\n"
+    );
+}
+
+#[test]
+fn parse_writes_rowan_tree_with_errors() {
+    use crate::OutputFormat;
+    let mut output = Vec::new();
+    OutputFormat::Rowan.write_stmt(&mut output, "f (x").unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        parser::parse_expression("f (x").debug_tree() + "\n"
+    );
+
+    let mut output = Vec::new();
+    OutputFormat::Rowan
+        .write_module(&mut output, "let x = (1\n")
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        parser::parse_module("let x = (1\n").debug_tree() + "\n"
+    );
+}
+
+#[test]
+fn parse_writes_ren() {
+    use crate::OutputFormat;
+    let mut output = Vec::new();
+    OutputFormat::Ren
+        .write_stmt(&mut output, "f (x)   ((1+2)*3)")
+        .unwrap();
+    assert_eq!(String::from_utf8(output).unwrap(), "f x ((1 + 2) * 3)\n");
+
+    let mut output = Vec::new();
+    OutputFormat::Ren
+        .write_module(&mut output, "import \"./a\" as A\nlet x = A.b\nlet y = 1\n")
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "import \"./a\" as A\n\nlet x = A.b\n\nlet y = 1\n"
+    );
+}
+
+#[test]
+fn json_mode_writes_decls() {
+    let mut output = Vec::new();
+    let (stmt, line_lookup) = parser::parse_stmt_ast("ext now : Number = \"now\"").unwrap();
+    crate::ReplModes::Json
+        .handle_stmt(&mut output, stmt, &line_lookup, &mut Default::default())
+        .unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(
+        output.starts_with(r#"[{"$":"Ext","type":[{"$":"Con"},"Number"],"inferred":false,"#),
+        "{}",
+        output
+    );
+    assert!(output.ends_with("[false,\"now\",\"now\"]]\n"), "{}", output);
+}
+
+#[test]
+fn fmt_checks_and_rewrites() {
+    use crate::{fmt, CliError};
+    let mut output = Vec::new();
+    fmt::format_stream(
+        "let x   = [1,2] // two\n".as_bytes(),
+        &mut output,
+        false,
+        80,
+    )
+    .unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "let x = [1, 2] // two\n"
+    );
+    assert!(matches!(
+        fmt::format_stream("let x   = 1\n".as_bytes(), Vec::new(), true, 80),
+        Err(CliError::Unformatted(_))
+    ));
+    fmt::format_stream("let x = 1\n".as_bytes(), Vec::new(), true, 80).unwrap();
+
+    let path = std::env::temp_dir().join(format!("ren-fmt-{}.ren", std::process::id()));
+    std::fs::write(&path, "let x   = 1\n").unwrap();
+    let paths = [path.clone()];
+    assert!(matches!(
+        fmt::format_files(&paths, true, 80),
+        Err(CliError::Unformatted(_))
+    ));
+    fmt::format_files(&paths, false, 80).unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "let x = 1\n");
+    fmt::format_files(&paths, true, 80).unwrap();
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_reports_expectations() {
+    use crate::{expect::run_expectations, CliError};
+    let mut output = Vec::new();
+    let result = run_expectations(
+        "let double = fun x -> x * 2\nexpect (double 2) == 4\nexpect assert (double 1) > 3; #true\nexpect (double 3) == 7\n",
+        &mut output,
+    );
+    assert!(matches!(result, Err(CliError::Failed(2))));
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "PASS 2:1 (double 2) == 4\nFAIL 3:1 assert (double 1) > 3; #true\n  assertion failed\nFAIL 4:1 (double 3) == 7\n1 passed, 2 failed\n"
+    );
+    run_expectations("let a = 1\nexpect a == 1\n", Vec::new()).unwrap();
+}
+
+#[test]
+fn test_reports_errors_in_globals() {
+    use crate::expect::run_expectations;
+    let mut output = Vec::new();
+    run_expectations("let a = assert 1 > 2; 1\nexpect a == 1\n", &mut output).unwrap_err();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "FAIL 2:1 a == 1\n  assertion failed\n0 passed, 1 failed\n"
+    );
+}
+
+#[test]
+fn test_user_defined_operators() {
+    use crate::expect::run_expectations;
+    let mut output = Vec::new();
+    run_expectations(
+        "expect (1 <+> 2 * 3) == 9\nexpect (2 <^> 1 <^> 2) == 4\ninfixl 8 <+> = fun a b -> a + b\ninfixr 0 <^> = fun a b -> a * b\n",
+        &mut output,
+    )
+    .unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "PASS 1:1 1 <+> 2 * 3 == 9\nPASS 2:1 (2 <^> 1 <^> 2) == 4\n2 passed, 0 failed\n"
+    );
+}
+
+/// Write the files of a project to a fresh directory, returning the directory
+fn temp_project(name: &str, files: &[(&str, &str)]) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("ren-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    for (path, source) in files {
+        let path = dir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, source).unwrap();
+    }
+    dir
+}
+
+#[test]
+fn load_project_orders_imports() {
+    use ast::pretty::Pretty;
+    let dir = temp_project(
+        "load",
+        &[
+            (
+                "main.ren",
+                "import \"./util/list\" as List exposing { <+> }\nimport pkg \"ren/array\" as Array\nimport \"./shared\" as Shared\nlet x = 1 <+> 2 <+> 3\n",
+            ),
+            (
+                "util/list.ren",
+                "import \"../shared\" as Shared\npub infixr 5 <+> = fun a b -> a\n",
+            ),
+            ("shared.ren", "pub let one = 1\n"),
+            (".pkg/ren/array.ren", "import \"./internal\" as Internal\n"),
+            (".pkg/ren/internal.ren", "pub let x = 1\n"),
+        ],
+    );
+    let modules = crate::project::load_project(&dir.join("main.ren")).unwrap();
+    assert_eq!(
+        modules
+            .iter()
+            .map(|m| m.path.to_string_lossy().into_owned())
+            .collect::<Vec<_>>(),
+        [
+            "shared.ren",
+            "util/list.ren",
+            ".pkg/ren/internal.ren",
+            ".pkg/ren/array.ren",
+            "main.ren"
+        ]
+    );
+    assert_eq!(modules[1].module.meta().name(), "list");
+    // The fixity of the imported operator is used to parse the entry module
+    let main = &modules[4].module;
+    assert_eq!(main.declarations()[0].pretty(80), "let x = 1 <+> (2 <+> 3)");
+}
+
+#[test]
+fn load_project_reports_cycles() {
+    let dir = temp_project(
+        "cycle",
+        &[
+            ("a.ren", "import \"./b\" as B\n"),
+            ("b.ren", "import \"./c\" as C\n"),
+            ("c.ren", "import \"./b\" as B\n"),
+        ],
+    );
+    assert_eq!(
+        crate::project::load_project(&dir.join("a.ren"))
+            .unwrap_err()
+            .to_string(),
+        "import cycle: b.ren -> c.ren -> b.ren"
+    );
+}
+
+#[test]
+fn build_writes_modules() {
+    let dir = temp_project(
+        "build",
+        &[
+            (
+                "main.ren",
+                "import \"./lib\" as Lib exposing { double }\nimport \"./util.ren\" as Util\npub let f = fun x -> switch x on case #true -> double 1\n",
+            ),
+            ("lib.ren", "pub let double = fun x -> x * 2\n"),
+            ("util.ren", "pub let one = 1\n"),
+            ("bad.ren", "let a = b\n"),
+        ],
+    );
+    let out_dir = dir.join("out");
+    let mut output = Vec::new();
+    crate::build::build_project(&dir.join("main.ren"), Some(&out_dir), &mut output).unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "warning: main.ren:3:22: switch does not match every value, e.g. `#false`\nbuilt 3 module(s)\n"
+    );
+    let main = std::fs::read_to_string(out_dir.join("main.js")).unwrap();
+    assert!(main.contains("from \"./lib.js\";"), "{}", main);
+    assert!(out_dir.join("lib.js").exists());
+    // Imports with the `.ren` extension refer to the same file as the module is written to
+    assert!(main.contains("from \"./util.js\";"), "{}", main);
+    assert!(out_dir.join("util.js").exists());
+
+    let error =
+        crate::build::build_project(&dir.join("bad.ren"), None, std::io::sink()).unwrap_err();
+    assert_eq!(error.to_string(), "bad.ren: 1:9: unbound variable `b`");
+}
+
+#[test]
+fn build_copies_ffi_files() {
+    let dir = temp_project(
+        "build-ffi",
+        &[
+            ("main.ren", "import \"./sub/time\" as Time\n"),
+            ("sub/time.ren", "pub ext now : Number = \"now\"\n"),
+            ("sub/time.ffi.js", "export const now = Date.now\n"),
+            ("missing.ren", "ext now : Number = \"now\"\n"),
+        ],
+    );
+    let out_dir = dir.join("out");
+    crate::build::build_project(&dir.join("main.ren"), Some(&out_dir), std::io::sink()).unwrap();
+    let time = std::fs::read_to_string(out_dir.join("sub/time.js")).unwrap();
+    assert!(time.contains("from \"./time.ffi.js\";"), "{}", time);
+    assert_eq!(
+        std::fs::read_to_string(out_dir.join("sub/time.ffi.js")).unwrap(),
+        "export const now = Date.now\n"
+    );
+
+    let error =
+        crate::build::build_project(&dir.join("missing.ren"), Some(&out_dir), std::io::sink())
+            .unwrap_err();
+    assert!(
+        error.to_string().starts_with("missing.ffi.js: "),
+        "{}",
+        error
+    );
+    assert!(!out_dir.join("missing.js").exists());
+}
+
+#[test]
+fn build_imports_packages_relative_to_modules() {
+    let dir = temp_project(
+        "build-pkg",
+        &[
+            ("main.ren", "import \"./sub/b\" as B\n"),
+            (
+                "sub/b.ren",
+                "import pkg \"ren/array\" as Array\npub let n = Array.length [1]\n",
+            ),
+            (".pkg/ren/array.ren", "pub let length = fun xs -> 1\n"),
+            ("escape.ren", "import \"../outside\" as Outside\n"),
+        ],
+    );
+    let out_dir = dir.join("out");
+    crate::build::build_project(&dir.join("main.ren"), Some(&out_dir), std::io::sink()).unwrap();
+    let b = std::fs::read_to_string(out_dir.join("sub/b.js")).unwrap();
+    assert!(b.contains("from \"../.pkg/ren/array.js\";"), "{}", b);
+    assert!(out_dir.join(".pkg/ren/array.js").exists());
+
+    let error =
+        crate::build::build_project(&dir.join("escape.ren"), None, std::io::sink()).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "escape.ren: import \"../outside\" is outside the project"
+    );
+}
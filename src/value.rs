@@ -1,300 +1,206 @@
-use std::collections::HashMap;
-use std::ops::Deref;
+use std::{fmt, rc::Rc};
+
+use ast::{Expr, Pattern};
+
+use crate::eval::Locals;
 
 //use ast::IdLower;
 type IdLower = String;
 
-type ParamCount = usize;
-
-macro_rules! vtmap {
-    ( $([ $( $t:ty $(,)?),+ ]: $vt:expr$(,)?),+ ) => {
-        $($(impl ValueTypeMapper for $t {
-            fn get_type_for(&self) -> ValueType {
-                $vt
-            }
-        })+)+
-    };
+/// A runtime value. Booleans and `undefined` are the enums `#true`, `#false` and `#undefined`,
+/// as they are in Ren source.
+#[derive(Debug, Clone)]
+pub enum Value {
+    Array(Vec<Value>),
+    Enum(String, Vec<Value>),
+    Function(Rc<Closure>),
+    Number(f64),
+    Record(Vec<(IdLower, Value)>),
+    String(String),
 }
 
-pub trait ValueTypeMapper {
-    fn get_type_for(&self) -> ValueType;
-}
-impl<T> ValueTypeMapper for Option<T>
-where
-    T: ValueTypeMapper,
-{
-    fn get_type_for(&self) -> ValueType {
-        match self {
-            Some(v) => v.get_type_for(),
-            None => ValueType::Undefined,
-        }
-    }
+/// A (possibly partially applied) function, along with the local variables it closes over
+#[derive(Debug)]
+pub struct Closure {
+    pub(crate) params: Vec<Pattern>,
+    pub(crate) body: Rc<Expr>,
+    pub(crate) locals: Locals,
+    pub(crate) args: Vec<Value>,
 }
 
-impl<T> ValueTypeMapper for Vec<T>
-where
-    T: ValueTypeMapper,
-{
-    fn get_type_for(&self) -> ValueType {
-        ValueType::Array(self.iter().map(T::get_type_for).collect())
-    }
-}
-impl ValueTypeMapper for HashMap<IdLower, Value> {
-    fn get_type_for(&self) -> ValueType {
-        ValueType::Object(
-            self.iter()
-                .map(|(k, v)| (k.to_owned(), v.v_type.clone()))
-                .collect(),
-        )
+impl Value {
+    pub fn boolean(b: bool) -> Self {
+        Self::Enum(b.to_string(), Vec::new())
     }
-}
-
-vtmap!(
-    [()]: ValueType::Unknown,
-    [String, &str]: ValueType::String,
-    [u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64,]: ValueType::Number,
-    [bool]: ValueType::Boolean,
-);
-
-#[derive(Debug, PartialEq)]
-pub struct Value<T: ValueTypeMapper = ()> {
-    v_type: ValueType,
-    value: T,
-}
-
-#[derive(Debug, Clone, PartialEq)]
-pub enum ValueType {
-    Function(Box<ValueType>, Box<ValueType>),
-    Object(HashMap<IdLower, ValueType>),
-    Array(Vec<ValueType>),
-    Number,
-    String,
-    Boolean,
-    Undefined,
-    Unknown,
-}
-impl ValueType {
-    pub fn func_param_types(&self) -> Option<Vec<ValueType>> {
-        self.function_type().map(|(p, _)| p)
+    pub fn undefined() -> Self {
+        Self::Enum("undefined".to_string(), Vec::new())
     }
-    pub fn function_type(&self) -> Option<(Vec<ValueType>, ValueType)> {
-        if let ValueType::Function(a, b) = self {
-            let mut res = vec![*a.clone()];
-            let mut t = *b.clone();
-            while let ValueType::Function(a, b) = t {
-                res.push(*a);
-                t = *b;
-            }
-            Some((res, t))
-        } else {
-            None
+    pub fn as_boolean(&self) -> Option<bool> {
+        match self {
+            Self::Enum(tag, args) if args.is_empty() && tag == "true" => Some(true),
+            Self::Enum(tag, args) if args.is_empty() && tag == "false" => Some(false),
+            _ => None,
         }
     }
-    pub fn function(params: Vec<ValueType>, ret_type: ValueType) -> Self {
-        if params.len() > 0 {
-            let mut t = ret_type;
-            for p_t in params.into_iter().rev() {
-                t = ValueType::Function(Box::new(p_t), Box::new(t));
-            }
-            t
-        } else {
-            Self::Unknown
+    pub fn field(&self, key: &str) -> Option<&Value> {
+        match self {
+            Self::Record(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
         }
     }
-    pub fn function_n(num_args: ParamCount) -> Self {
-        let mut t = ValueType::Function(Box::new(ValueType::Unknown), Box::new(ValueType::Unknown));
-        for _ in 1..num_args {
-            t = ValueType::Function(Box::new(ValueType::Unknown), Box::new(t));
+    /// The text of a value when it is interpolated into a string
+    pub fn to_text(&self) -> String {
+        match self {
+            Self::String(s) => s.clone(),
+            _ => self.to_string(),
         }
-        t
     }
-    /*pub fn default_value<T>(self) -> Value<T>
-    where T: ValueTypeMapper {
-        Value {
-            v_type: self,
-            value: match &self {
-                ValueType::Function(..) => (),
-                ValueType::Object(..) => HashMap::new(),
-                ValueType::Array(..) => Vec::new(),
-                ValueType::Number => 0,
-                ValueType::String => String::new(),
-                ValueType::Boolean => false,
-                ValueType::Undefined => (),
-                ValueType::Unknown => (),
+}
+
+/// Values are compared structurally. Functions are only equal to themselves.
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Array(a), Self::Array(b)) => a == b,
+            (Self::Enum(t1, a1), Self::Enum(t2, a2)) => t1 == t2 && a1 == a2,
+            (Self::Function(f1), Self::Function(f2)) => Rc::ptr_eq(f1, f2),
+            (Self::Number(n1), Self::Number(n2)) => n1 == n2,
+            (Self::Record(f1), Self::Record(f2)) => {
+                f1.len() == f2.len() && f1.iter().all(|(k, v)| other.field(k) == Some(v))
             }
+            (Self::String(s1), Self::String(s2)) => s1 == s2,
+            _ => false,
         }
-    }*/
+    }
 }
 
-#[allow(dead_code)]
-impl Value {
-    pub fn new<T, V>(value: V) -> Value<T>
-    where
-        T: ValueTypeMapper,
-        V: Into<T>,
-    {
-        let v = value.into();
-        Value {
-            v_type: T::get_type_for(&v),
-            value: v,
-        }
+impl From<f64> for Value {
+    fn from(n: f64) -> Self {
+        Self::Number(n)
     }
-    pub fn from_type<T>(v_type: ValueType, value: T) -> Value<T>
-    where
-        T: ValueTypeMapper,
-    {
-        Value { v_type, value }
+}
+impl From<i32> for Value {
+    fn from(n: i32) -> Self {
+        Self::Number(n.into())
     }
-    pub fn function_n(num_args: ParamCount) -> Self {
-        Self::from_type(ValueType::function_n(num_args), ())
+}
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Self::boolean(b)
     }
-    pub fn function(params: Vec<ValueType>, ret_type: ValueType) -> Self {
-        let t = ValueType::function(params, ret_type);
-        if let ValueType::Function(..) = t {
-            Self {
-                v_type: t,
-                value: (), //TODO: function value
-            }
-        } else {
-            Self::undefined()
-        }
+}
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Self::String(s.to_string())
     }
-    pub fn get_num_args(&self) -> ParamCount {
-        let mut c = 0;
-        let mut t = &self.v_type;
-        while let ValueType::Function(_, r) = t {
-            c += 1;
-            t = r;
-        }
-        c
+}
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Self::String(s)
     }
-    pub fn undefined() -> Value<()> {
-        Value {
-            v_type: ValueType::Undefined,
-            value: (),
-        }
+}
+impl From<()> for Value {
+    fn from(_: ()) -> Self {
+        Self::undefined()
     }
 }
-#[allow(dead_code)]
-impl<T> Value<T>
-where
-    T: ValueTypeMapper,
-{
-    pub fn get(&self) -> &T {
-        &self.value
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(v: Option<T>) -> Self {
+        v.map_or_else(Self::undefined, Into::into)
+    }
+}
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(items: Vec<T>) -> Self {
+        Self::Array(items.into_iter().map(Into::into).collect())
     }
 }
 
-impl<T> Deref for Value<T>
-where
-    T: ValueTypeMapper,
-{
-    type Target = T;
-
-    fn deref(&self) -> &Self::Target {
-        self.get()
+/// Values are displayed as the Ren literal which would create them
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn list(f: &mut fmt::Formatter<'_>, items: &[Value]) -> fmt::Result {
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{}", item)?;
+            }
+            Ok(())
+        }
+        match self {
+            Self::Array(items) => {
+                write!(f, "[")?;
+                list(f, items)?;
+                write!(f, "]")
+            }
+            Self::Enum(tag, args) => {
+                write!(f, "#{}", tag)?;
+                for arg in args {
+                    match arg {
+                        Self::Enum(_, a) if !a.is_empty() => write!(f, " ({})", arg)?,
+                        _ => write!(f, " {}", arg)?,
+                    }
+                }
+                Ok(())
+            }
+            Self::Function(_) => write!(f, "<function>"),
+            Self::Number(n) => write!(f, "{}", n),
+            Self::Record(fields) if fields.is_empty() => write!(f, "{{}}"),
+            Self::Record(fields) => {
+                write!(f, "{{ ")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", key, value)?;
+                }
+                write!(f, " }}")
+            }
+            Self::String(s) => write!(f, "{:?}", s),
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{Value, ValueType as VT};
+    use super::Value;
     #[test]
-    fn test_undefined_type() {
-        assert_eq!(
-            Value::new(()),
-            Value {
-                v_type: VT::Unknown,
-                value: (),
-            }
-        );
-        assert_eq!(
-            Value::undefined(),
-            Value {
-                v_type: VT::Undefined,
-                value: (),
-            }
-        );
+    fn test_undefined() {
+        assert_eq!(Value::from(()), Value::Enum("undefined".to_owned(), vec![]));
     }
     #[test]
-    fn test_string_type() {
-        assert_eq!(
-            Value::new("string"),
-            Value {
-                v_type: VT::String,
-                value: "string".to_owned(),
-            }
-        );
+    fn test_string() {
+        assert_eq!(Value::from("string"), Value::String("string".to_owned()));
     }
     #[test]
     fn test_string_optional() {
         assert_eq!(
-            Value::new(Some("string".to_owned())),
-            Value {
-                v_type: VT::String,
-                value: Some("string".to_owned()),
-            }
-        );
-        assert_eq!(
-            Value::new::<Option<String>, Option<String>>(None),
-            Value {
-                v_type: VT::Undefined,
-                value: None,
-            }
+            Value::from(Some("string".to_owned())),
+            Value::String("string".to_owned())
         );
+        assert_eq!(Value::from(None::<String>), Value::undefined());
     }
     #[test]
-    fn test_number_type() {
-        assert_eq!(
-            Value::new(3.14),
-            Value {
-                v_type: VT::Number,
-                value: 3.14f64,
-            }
-        );
-        assert_eq!(
-            Value::new(5u8),
-            Value {
-                v_type: VT::Number,
-                value: 5u8,
-            }
-        );
+    #[allow(clippy::approx_constant)]
+    fn test_number() {
+        assert_eq!(Value::from(3.14), Value::Number(3.14));
     }
     #[test]
-    fn test_function_type() {
-        assert_eq!(
-            Value::from_type(VT::function_n(2), ()),
-            Value {
-                v_type: VT::Function(
-                    Box::new(VT::Unknown),
-                    Box::new(VT::Function(Box::new(VT::Unknown), Box::new(VT::Unknown)))
+    fn test_display() {
+        let value = Value::Record(vec![
+            ("a".to_owned(), Value::from(vec![1, 2])),
+            (
+                "b".to_owned(),
+                Value::Enum(
+                    "just".to_owned(),
+                    vec![Value::Enum("just".to_owned(), vec![Value::from("x")])],
                 ),
-                value: (),
-            }
-        );
-        assert_eq!(
-            Value::function(vec![VT::String, VT::String, VT::Number], VT::Number),
-            Value {
-                v_type: VT::Function(
-                    Box::new(VT::String),
-                    Box::new(VT::Function(
-                        Box::new(VT::String),
-                        Box::new(VT::Function(Box::new(VT::Number), Box::new(VT::Number),))
-                    ))
-                ),
-                value: (),
-            }
-        );
+            ),
+            ("c".to_owned(), Value::boolean(true)),
+        ]);
         assert_eq!(
-            VT::Function(
-                Box::new(VT::String),
-                Box::new(VT::Function(
-                    Box::new(VT::Number),
-                    Box::new(VT::Array(vec![VT::String, VT::Number]))
-                ))
-            )
-            .func_param_types(),
-            Some(vec![VT::String, VT::Number])
+            value.to_string(),
+            r#"{ a: [1, 2], b: #just (#just "x"), c: #true }"#
         );
     }
 }